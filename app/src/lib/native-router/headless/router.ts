@@ -1512,9 +1512,13 @@ export class NativeRouterService {
       this.programId
     );
     
-    // Oracle cache PDA
+    // Oracle cache PDA: clé par paire, mints triées (même cache pour A→B et B→A)
+    const [mintLo, mintHi] =
+      Buffer.compare(safeInputMint.toBuffer(), safeOutputMint.toBuffer()) <= 0
+        ? [safeInputMint, safeOutputMint]
+        : [safeOutputMint, safeInputMint];
     const [oracleCache] = PublicKey.findProgramAddressSync(
-      [Buffer.from("oracle_cache"), DEFAULT_ORACLE.toBuffer()],
+      [Buffer.from("oracle_cache"), mintLo.toBuffer(), mintHi.toBuffer()],
       this.programId
     );
     
@@ -1794,8 +1798,8 @@ export class NativeRouterService {
       { pubkey: userRebatePdaExists ? accounts.userRebatePda : NONE_ACCOUNT, isSigner: false, isWritable: userRebatePdaExists },
       // 16. rebate_vault
       { pubkey: accounts.rebateVault, isSigner: false, isWritable: true },
-      // 17. oracle_cache (optional - seulement si existe)
-      { pubkey: oracleCacheExists ? accounts.oracleCache : NONE_ACCOUNT, isSigner: false, isWritable: oracleCacheExists },
+      // 17. oracle_cache (obligatoire: porte le circuit breaker de la paire, cf. initialize_oracle_cache)
+      { pubkey: accounts.oracleCache, isSigner: false, isWritable: true },
       // 18. venue_score (optional - seulement si existe)
       { pubkey: venueScoreExists ? accounts.venueScore : NONE_ACCOUNT, isSigner: false, isWritable: venueScoreExists },
       // 19. token_program
@@ -2059,9 +2059,13 @@ export class NativeRouterService {
       this.programId
     );
     
-    // Oracle cache PDA
+    // Oracle cache PDA: clé par paire, mints triées (même cache pour A→B et B→A)
+    const [mintLo, mintHi] =
+      Buffer.compare(safeInputMint.toBuffer(), safeOutputMint.toBuffer()) <= 0
+        ? [safeInputMint, safeOutputMint]
+        : [safeOutputMint, safeInputMint];
     const [oracleCache] = PublicKey.findProgramAddressSync(
-      [Buffer.from("oracle_cache"), DEFAULT_ORACLE.toBuffer()],
+      [Buffer.from("oracle_cache"), mintLo.toBuffer(), mintHi.toBuffer()],
       this.programId
     );
     
@@ -8,5 +8,5 @@ pub mod oracle;
 pub mod types;
 
 pub use optimizer::{compute_optimal_weights, DexInfo};
-pub use oracle::fetch_price;
+pub use oracle::{fetch_feed_price, fetch_price};
 pub use types::*;
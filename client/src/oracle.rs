@@ -6,6 +6,36 @@ use crate::types::PriceData;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
+use swapback_price_source::{self as price_source, PriceObservation, PriceSourceKind};
+
+/// Fetch and decode an oracle feed account with the same decoders as the
+/// on-chain programs. `kind` is detected from the account owner when `None`
+/// (required for local test feeds).
+pub fn fetch_feed_price(
+    rpc_client: &RpcClient,
+    feed: &Pubkey,
+    kind: Option<PriceSourceKind>,
+) -> Result<PriceData, Box<dyn std::error::Error>> {
+    let account = rpc_client.get_account(feed)?;
+    let kind = kind
+        .or_else(|| price_source::detect_kind(&account.owner))
+        .ok_or_else(|| format!("Unknown oracle owner {} for feed {}", account.owner, feed))?;
+
+    let observation = price_source::read_kind(kind, &account.owner, &account.data)
+        .map_err(|e| format!("Failed to decode {} feed {}: {:?}", kind.as_str(), feed, e))?;
+
+    Ok(price_data_from_observation(&observation))
+}
+
+/// Convert a normalized observation (8 decimals) into client `PriceData`
+pub fn price_data_from_observation(observation: &PriceObservation) -> PriceData {
+    PriceData {
+        price: observation.price,
+        confidence: observation.confidence,
+        timestamp: observation.publish_time.max(0) as u64,
+        source: observation.kind.as_str().to_string(),
+    }
+}
 
 /// Fetch price from Pyth oracle
 pub async fn fetch_pyth_price(
@@ -192,4 +222,17 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("below minimum"));
     }
+
+    #[test]
+    fn test_price_data_from_test_feed() {
+        let data = price_source::TestFeed::encode(15_050, 10, -2, 1_700_000_000, 42);
+        let observation =
+            price_source::read_kind(PriceSourceKind::TestFeed, &Pubkey::new_unique(), &data).unwrap();
+        let price_data = price_data_from_observation(&observation);
+
+        assert_eq!(price_data.price, 15_050_000_000);
+        assert_eq!(price_data.confidence, 10_000_000);
+        assert_eq!(price_data.timestamp, 1_700_000_000);
+        assert_eq!(price_data.source, "test-feed");
+    }
 }
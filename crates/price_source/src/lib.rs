@@ -0,0 +1,315 @@
+//! SwapBack Price Sources - décodage unifié des oracles
+//!
+//! Chaque fournisseur (Pyth legacy, Pyth pull, Switchboard v2 et On-Demand,
+//! feed de test)
+//! implémente `PriceSource` et produit la même `PriceObservation` normalisée
+//! en 8 décimales. Les décodeurs ne travaillent que sur `(owner, data)` :
+//! ils sont utilisés tels quels on-chain (router) et off-chain
+//! (swapback-client) après un `get_account` RPC.
+
+use solana_program::pubkey::Pubkey;
+
+pub mod pyth_legacy;
+pub mod pyth_pull;
+pub mod switchboard_on_demand;
+#[cfg(feature = "switchboard-v2")]
+pub mod switchboard_v2;
+pub mod test_feed;
+
+pub use pyth_legacy::PythLegacy;
+pub use pyth_pull::PythPull;
+pub use switchboard_on_demand::SwitchboardOnDemand;
+#[cfg(feature = "switchboard-v2")]
+pub use switchboard_v2::SwitchboardV2;
+pub use test_feed::TestFeed;
+
+/// Nombre de décimales de toutes les observations normalisées
+pub const PRICE_DECIMALS: u32 = 8;
+
+/// Tolérance pour les horloges oracle en avance sur le cluster
+pub const MAX_FUTURE_DRIFT_SECS: i64 = 60;
+
+/// Fournisseur à l'origine d'une observation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSourceKind {
+    PythLegacy,
+    PythPull,
+    SwitchboardV2,
+    SwitchboardOnDemand,
+    TestFeed,
+}
+
+impl PriceSourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceSourceKind::PythLegacy => "pyth-legacy",
+            PriceSourceKind::PythPull => "pyth-pull",
+            PriceSourceKind::SwitchboardV2 => "switchboard-v2",
+            PriceSourceKind::SwitchboardOnDemand => "switchboard-on-demand",
+            PriceSourceKind::TestFeed => "test-feed",
+        }
+    }
+}
+
+/// Observation de prix normalisée (prix et confidence en 8 décimales)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceObservation {
+    pub price: u64,
+    pub confidence: u64,
+    pub publish_time: i64,
+    /// Slot de publication côté oracle (0 si le fournisseur ne l'expose pas)
+    pub slot: u64,
+    pub kind: PriceSourceKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSourceError {
+    /// Le compte n'appartient pas au programme oracle attendu
+    InvalidOwner,
+    /// Données trop courtes, discriminator ou variante inconnus
+    InvalidAccountData,
+    /// Prix nul, négatif, non publié ou non représentable en 8 décimales
+    InvalidPrice,
+    /// Prix plus vieux que la limite de staleness
+    StalePrice,
+    /// Timestamp oracle trop loin dans le futur
+    FutureTimestamp,
+    /// Confidence supérieure au seuil demandé
+    ConfidenceTooWide,
+}
+
+/// Décodeur d'un type de compte oracle
+pub trait PriceSource {
+    const KIND: PriceSourceKind;
+
+    /// Programme propriétaire attendu (None = pas de contrôle, feed de test)
+    fn owner() -> Option<Pubkey>;
+
+    /// Contrôle du propriétaire (surchargé quand plusieurs déploiements coexistent)
+    fn accepts_owner(owner: &Pubkey) -> bool {
+        match Self::owner() {
+            Some(expected) => *owner == expected,
+            None => true,
+        }
+    }
+
+    /// Décode les données brutes du compte en observation normalisée
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError>;
+}
+
+/// Vérifie le propriétaire puis décode avec la source `S`
+pub fn read<S: PriceSource>(owner: &Pubkey, data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+    if !S::accepts_owner(owner) {
+        return Err(PriceSourceError::InvalidOwner);
+    }
+    S::decode(data)
+}
+
+/// Décode un compte dont le type de source est connu à l'exécution
+pub fn read_kind(
+    kind: PriceSourceKind,
+    owner: &Pubkey,
+    data: &[u8],
+) -> Result<PriceObservation, PriceSourceError> {
+    match kind {
+        PriceSourceKind::PythLegacy => read::<PythLegacy>(owner, data),
+        PriceSourceKind::PythPull => read::<PythPull>(owner, data),
+        #[cfg(feature = "switchboard-v2")]
+        PriceSourceKind::SwitchboardV2 => read::<SwitchboardV2>(owner, data),
+        #[cfg(not(feature = "switchboard-v2"))]
+        PriceSourceKind::SwitchboardV2 => Err(PriceSourceError::InvalidOwner),
+        PriceSourceKind::SwitchboardOnDemand => read::<SwitchboardOnDemand>(owner, data),
+        PriceSourceKind::TestFeed => read::<TestFeed>(owner, data),
+    }
+}
+
+/// Détecte la source à partir du propriétaire du compte (le feed de test
+/// n'est jamais détecté, il doit être demandé explicitement; Switchboard V2
+/// seulement avec la feature `switchboard-v2`)
+pub fn detect_kind(owner: &Pubkey) -> Option<PriceSourceKind> {
+    #[cfg(feature = "switchboard-v2")]
+    if *owner == switchboard_v2::SWITCHBOARD_V2_PROGRAM_ID {
+        return Some(PriceSourceKind::SwitchboardV2);
+    }
+    if *owner == pyth_pull::PYTH_RECEIVER_PROGRAM_ID {
+        Some(PriceSourceKind::PythPull)
+    } else if *owner == pyth_legacy::PYTH_ORACLE_PROGRAM_ID {
+        Some(PriceSourceKind::PythLegacy)
+    } else if SwitchboardOnDemand::accepts_owner(owner) {
+        Some(PriceSourceKind::SwitchboardOnDemand)
+    } else {
+        None
+    }
+}
+
+impl PriceObservation {
+    /// Âge du prix en secondes (négatif si publié dans le futur)
+    pub fn age(&self, now: i64) -> i64 {
+        now.saturating_sub(self.publish_time)
+    }
+
+    /// Rejette un prix trop vieux ou trop loin dans le futur
+    pub fn check_staleness(&self, now: i64, max_staleness_secs: i64) -> Result<(), PriceSourceError> {
+        let age = self.age(now);
+        if age > max_staleness_secs {
+            return Err(PriceSourceError::StalePrice);
+        }
+        if age < -MAX_FUTURE_DRIFT_SECS {
+            return Err(PriceSourceError::FutureTimestamp);
+        }
+        Ok(())
+    }
+
+    /// Rejette un prix dont le slot de publication est trop loin du slot
+    /// courant (indépendant des horloges des validateurs)
+    pub fn check_slot_lag(&self, current_slot: u64, max_slot_lag: u64) -> Result<(), PriceSourceError> {
+        if self.slot == 0 || current_slot.saturating_sub(self.slot) > max_slot_lag {
+            return Err(PriceSourceError::StalePrice);
+        }
+        Ok(())
+    }
+
+    /// Confidence relative au prix, en basis points
+    pub fn confidence_bps(&self) -> u64 {
+        if self.price == 0 {
+            return u64::MAX;
+        }
+        (self.confidence as u128 * 10_000 / self.price as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// Rejette une confidence plus large que `max_confidence_bps`
+    pub fn check_confidence(&self, max_confidence_bps: u64) -> Result<(), PriceSourceError> {
+        if self.confidence_bps() > max_confidence_bps {
+            return Err(PriceSourceError::ConfidenceTooWide);
+        }
+        Ok(())
+    }
+}
+
+/// Normalise `value * 10^expo` vers 8 décimales (troncature vers zéro)
+pub fn normalize_decimal(value: i128, expo: i32) -> Option<u64> {
+    let scaled = rescale(value, expo, -(PRICE_DECIMALS as i32))?;
+    u64::try_from(scaled).ok()
+}
+
+/// Convertit `value * 10^expo` vers l'exposant `target_expo` (troncature vers
+/// zéro). Retourne None pour une valeur négative ou en cas d'overflow.
+pub fn rescale(value: i128, expo: i32, target_expo: i32) -> Option<u128> {
+    let exponent_diff = (expo as i64).checked_sub(target_expo as i64)?;
+    let scaled = if exponent_diff >= 0 {
+        value.checked_mul(pow10_i128(u32::try_from(exponent_diff).ok()?)?)?
+    } else {
+        value.checked_div(pow10_i128(u32::try_from(-exponent_diff).ok()?)?)?
+    };
+
+    if scaled < 0 {
+        None
+    } else {
+        Some(scaled as u128)
+    }
+}
+
+fn pow10_i128(exp: u32) -> Option<i128> {
+    10_i128.checked_pow(exp)
+}
+
+/// Lecture little-endian bornée (toutes les sources partagent ces helpers)
+pub(crate) fn read_array<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], PriceSourceError> {
+    data.get(offset..offset.checked_add(N).ok_or(PriceSourceError::InvalidAccountData)?)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(PriceSourceError::InvalidAccountData)
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32, PriceSourceError> {
+    read_array::<4>(data, offset).map(u32::from_le_bytes)
+}
+
+pub(crate) fn read_i32(data: &[u8], offset: usize) -> Result<i32, PriceSourceError> {
+    read_array::<4>(data, offset).map(i32::from_le_bytes)
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64, PriceSourceError> {
+    read_array::<8>(data, offset).map(u64::from_le_bytes)
+}
+
+pub(crate) fn read_i64(data: &[u8], offset: usize) -> Result<i64, PriceSourceError> {
+    read_array::<8>(data, offset).map(i64::from_le_bytes)
+}
+
+pub(crate) fn read_i128(data: &[u8], offset: usize) -> Result<i128, PriceSourceError> {
+    read_array::<16>(data, offset).map(i128::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_decimal() {
+        // Prix de $150.50 avec expo -8 (format Pyth standard)
+        assert_eq!(normalize_decimal(15050000000, -8), Some(15050000000));
+
+        // Prix de $1.50 avec expo -8
+        assert_eq!(normalize_decimal(150000000, -8), Some(150000000));
+
+        // Prix de $150.50 avec expo -6: 2 décimales manquantes, multiplie par 100
+        assert_eq!(normalize_decimal(150500000, -6), Some(15050000000));
+
+        // Prix de $150.50 avec expo -10: 2 décimales de trop, divise par 100
+        assert_eq!(normalize_decimal(1505000000000_i128, -10), Some(15050000000));
+
+        // Troncature vers zéro au-delà de 8 décimales
+        assert_eq!(normalize_decimal(1505000000099_i128, -10), Some(15050000000));
+
+        // Prix négatif -> None
+        assert_eq!(normalize_decimal(-100, -8), None);
+
+        // Hors u64 -> None
+        assert_eq!(normalize_decimal(i128::MAX / 10, -8), None);
+    }
+
+    #[test]
+    fn test_rescale_custom_target() {
+        // 150.5 (expo -2) vers 6 décimales
+        assert_eq!(rescale(15050, -2, -6), Some(150_500_000));
+        assert_eq!(rescale(15050, -2, 0), Some(150));
+        assert_eq!(rescale(1, 0, -60), None);
+    }
+
+    #[test]
+    fn test_staleness_and_confidence() {
+        let obs = PriceObservation {
+            price: 100_000_000,
+            confidence: 1_000_000,
+            publish_time: 1_000,
+            slot: 0,
+            kind: PriceSourceKind::TestFeed,
+        };
+        assert_eq!(obs.check_staleness(1_030, 30), Ok(()));
+        assert_eq!(obs.check_staleness(1_031, 30), Err(PriceSourceError::StalePrice));
+        assert_eq!(obs.check_staleness(900, 30), Err(PriceSourceError::FutureTimestamp));
+        assert_eq!(obs.confidence_bps(), 100);
+        assert_eq!(obs.check_confidence(100), Ok(()));
+        assert_eq!(obs.check_confidence(99), Err(PriceSourceError::ConfidenceTooWide));
+
+        let obs = PriceObservation { slot: 500, ..obs };
+        assert_eq!(obs.check_slot_lag(550, 50), Ok(()));
+        assert_eq!(obs.check_slot_lag(551, 50), Err(PriceSourceError::StalePrice));
+        let unknown_slot = PriceObservation { slot: 0, ..obs };
+        assert_eq!(unknown_slot.check_slot_lag(1, 50), Err(PriceSourceError::StalePrice));
+    }
+
+    #[test]
+    fn test_read_checks_owner() {
+        let data = TestFeed::encode(100_000_000, 0, -8, 1_000, 42);
+        assert!(read::<TestFeed>(&Pubkey::new_unique(), &data).is_ok());
+
+        let wrong = Pubkey::new_unique();
+        assert_eq!(read::<PythPull>(&wrong, &data), Err(PriceSourceError::InvalidOwner));
+        assert_eq!(detect_kind(&wrong), None);
+        assert_eq!(
+            detect_kind(&pyth_pull::PYTH_RECEIVER_PROGRAM_ID),
+            Some(PriceSourceKind::PythPull)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Pyth legacy push oracle (comptes Price du programme FsJ3A3u2...)
+
+use solana_program::{pubkey, pubkey::Pubkey};
+
+use crate::{
+    normalize_decimal, read_i32, read_i64, read_u32, read_u64, PriceObservation, PriceSource,
+    PriceSourceError, PriceSourceKind,
+};
+
+/// Pyth Oracle Program ID (mainnet)
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const ACCOUNT_TYPE_PRICE: u32 = 3;
+const PRICE_STATUS_TRADING: u32 = 1;
+
+// Offsets de pyth-sdk-solana::state::SolanaPriceAccount
+const MAGIC_OFFSET: usize = 0;
+const ATYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const AGG_PUB_SLOT_OFFSET: usize = 232;
+const MIN_LEN: usize = AGG_PUB_SLOT_OFFSET + 8;
+
+pub struct PythLegacy;
+
+impl PriceSource for PythLegacy {
+    const KIND: PriceSourceKind = PriceSourceKind::PythLegacy;
+
+    fn owner() -> Option<Pubkey> {
+        Some(PYTH_ORACLE_PROGRAM_ID)
+    }
+
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+        if data.len() < MIN_LEN
+            || read_u32(data, MAGIC_OFFSET)? != PYTH_MAGIC
+            || read_u32(data, ATYPE_OFFSET)? != ACCOUNT_TYPE_PRICE
+        {
+            return Err(PriceSourceError::InvalidAccountData);
+        }
+
+        // Un agrégat hors statut Trading n'a pas de prix exploitable
+        if read_u32(data, AGG_STATUS_OFFSET)? != PRICE_STATUS_TRADING {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+
+        let expo = read_i32(data, EXPO_OFFSET)?;
+        let price = normalize_decimal(read_i64(data, AGG_PRICE_OFFSET)? as i128, expo)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+        if price == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+        let confidence = normalize_decimal(read_u64(data, AGG_CONF_OFFSET)? as i128, expo)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+
+        Ok(PriceObservation {
+            price,
+            confidence,
+            publish_time: read_i64(data, TIMESTAMP_OFFSET)?,
+            slot: read_u64(data, AGG_PUB_SLOT_OFFSET)?,
+            kind: Self::KIND,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(price: i64, conf: u64, expo: i32, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 3312];
+        data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[ATYPE_OFFSET..ATYPE_OFFSET + 4].copy_from_slice(&ACCOUNT_TYPE_PRICE.to_le_bytes());
+        data[EXPO_OFFSET..EXPO_OFFSET + 4].copy_from_slice(&expo.to_le_bytes());
+        data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&1_000i64.to_le_bytes());
+        data[AGG_PRICE_OFFSET..AGG_PRICE_OFFSET + 8].copy_from_slice(&price.to_le_bytes());
+        data[AGG_CONF_OFFSET..AGG_CONF_OFFSET + 8].copy_from_slice(&conf.to_le_bytes());
+        data[AGG_STATUS_OFFSET..AGG_STATUS_OFFSET + 4].copy_from_slice(&status.to_le_bytes());
+        data[AGG_PUB_SLOT_OFFSET..AGG_PUB_SLOT_OFFSET + 8].copy_from_slice(&99u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_decode_trading_price() {
+        let obs = PythLegacy::decode(&account(150_500_000, 100_000, -6, PRICE_STATUS_TRADING)).unwrap();
+        assert_eq!(obs.price, 15_050_000_000);
+        assert_eq!(obs.confidence, 10_000_000);
+        assert_eq!(obs.publish_time, 1_000);
+        assert_eq!(obs.slot, 99);
+    }
+
+    #[test]
+    fn test_rejects_halted_or_malformed() {
+        assert_eq!(
+            PythLegacy::decode(&account(150_500_000, 0, -6, 2)),
+            Err(PriceSourceError::InvalidPrice)
+        );
+
+        let mut bad_magic = account(150_500_000, 0, -6, PRICE_STATUS_TRADING);
+        bad_magic[0] = 0;
+        assert_eq!(PythLegacy::decode(&bad_magic), Err(PriceSourceError::InvalidAccountData));
+        assert_eq!(PythLegacy::decode(&[0u8; 100]), Err(PriceSourceError::InvalidAccountData));
+    }
+}
@@ -0,0 +1,174 @@
+//! Pyth pull oracle (PriceUpdateV2, owné par le Pyth Receiver)
+//!
+//! Désérialisation manuelle pour éviter les conflits de version Anchor avec
+//! pyth-solana-receiver-sdk.
+
+use solana_program::{pubkey, pubkey::Pubkey};
+
+use crate::{
+    normalize_decimal, read_array, read_i32, read_i64, read_u64, PriceObservation, PriceSource,
+    PriceSourceError, PriceSourceKind,
+};
+
+/// Pyth Pull Oracle Program ID (mainnet)
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+const DISCRIMINATOR_LEN: usize = 8;
+const WRITE_AUTHORITY_LEN: usize = 32;
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8 + 8 + 8 + 8;
+const POSTED_SLOT_LEN: usize = 8;
+
+/// Niveau de vérification Wormhole du message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+/// Champs bruts d'un compte PriceUpdateV2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceUpdateV2 {
+    pub verification_level: VerificationLevel,
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub posted_slot: u64,
+}
+
+pub struct PythPull;
+
+impl PythPull {
+    /// Parse la structure PriceUpdateV2 de pyth_solana_receiver_sdk:
+    /// - 8 bytes: Anchor discriminator
+    /// - 32 bytes: write_authority (Pubkey, NOT Option!)
+    /// - VerificationLevel enum (Borsh):
+    ///     * variant 0 => Partial { num_signatures: u8 } (2 bytes total)
+    ///     * variant 1 => Full (1 byte total)
+    /// - PriceFeedMessage: feed_id [u8; 32], price i64, conf u64, exponent i32,
+    ///   publish_time i64, prev_publish_time i64, ema_price i64, ema_conf u64
+    /// - 8 bytes: posted_slot (u64)
+    pub fn parse(data: &[u8]) -> Result<PriceUpdateV2, PriceSourceError> {
+        let mut offset = DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN;
+
+        let variant = *data.get(offset).ok_or(PriceSourceError::InvalidAccountData)?;
+        offset += 1;
+
+        let verification_level = match variant {
+            0 => {
+                let num_signatures = *data.get(offset).ok_or(PriceSourceError::InvalidAccountData)?;
+                offset += 1;
+                VerificationLevel::Partial { num_signatures }
+            }
+            1 => VerificationLevel::Full,
+            _ => return Err(PriceSourceError::InvalidAccountData),
+        };
+
+        if data.len() < offset + PRICE_MESSAGE_LEN + POSTED_SLOT_LEN {
+            return Err(PriceSourceError::InvalidAccountData);
+        }
+
+        Ok(PriceUpdateV2 {
+            verification_level,
+            feed_id: read_array::<32>(data, offset)?,
+            price: read_i64(data, offset + 32)?,
+            conf: read_u64(data, offset + 40)?,
+            exponent: read_i32(data, offset + 48)?,
+            publish_time: read_i64(data, offset + 52)?,
+            posted_slot: read_u64(data, offset + PRICE_MESSAGE_LEN)?,
+        })
+    }
+}
+
+impl PriceSource for PythPull {
+    const KIND: PriceSourceKind = PriceSourceKind::PythPull;
+
+    fn owner() -> Option<Pubkey> {
+        Some(PYTH_RECEIVER_PROGRAM_ID)
+    }
+
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+        let update = Self::parse(data)?;
+
+        let price = normalize_decimal(update.price as i128, update.exponent)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+        if price == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+        let confidence = normalize_decimal(update.conf as i128, update.exponent)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+
+        Ok(PriceObservation {
+            price,
+            confidence,
+            publish_time: update.publish_time,
+            slot: update.posted_slot,
+            kind: Self::KIND,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn encode_for_tests(
+    verification_level: VerificationLevel,
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    posted_slot: u64,
+) -> Vec<u8> {
+    let mut data = vec![0u8; DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN];
+    match verification_level {
+        VerificationLevel::Partial { num_signatures } => data.extend_from_slice(&[0, num_signatures]),
+        VerificationLevel::Full => data.push(1),
+    }
+    data.extend_from_slice(&[7u8; 32]);
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&conf.to_le_bytes());
+    data.extend_from_slice(&exponent.to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes());
+    data.extend_from_slice(&[0u8; 24]); // prev_publish_time, ema_price, ema_conf
+    data.extend_from_slice(&posted_slot.to_le_bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_full_and_partial() {
+        let full = encode_for_tests(VerificationLevel::Full, 15_050_000_000, 5_000_000, -8, 1_000, 77);
+        let obs = PythPull::decode(&full).unwrap();
+        assert_eq!(obs.price, 15_050_000_000);
+        assert_eq!(obs.confidence, 5_000_000);
+        assert_eq!(obs.slot, 77);
+
+        let partial = encode_for_tests(
+            VerificationLevel::Partial { num_signatures: 5 },
+            150_500_000,
+            0,
+            -6,
+            1_000,
+            77,
+        );
+        let update = PythPull::parse(&partial).unwrap();
+        assert_eq!(update.verification_level, VerificationLevel::Partial { num_signatures: 5 });
+        assert_eq!(PythPull::decode(&partial).unwrap().price, 15_050_000_000);
+    }
+
+    #[test]
+    fn test_rejects_bad_layout() {
+        let mut data = encode_for_tests(VerificationLevel::Full, 1, 0, -8, 0, 0);
+        data.truncate(data.len() - 1);
+        assert_eq!(PythPull::parse(&data), Err(PriceSourceError::InvalidAccountData));
+
+        let mut data = encode_for_tests(VerificationLevel::Full, 1, 0, -8, 0, 0);
+        data[DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN] = 2;
+        assert_eq!(PythPull::parse(&data), Err(PriceSourceError::InvalidAccountData));
+
+        let negative = encode_for_tests(VerificationLevel::Full, -1, 0, -8, 0, 0);
+        assert_eq!(PythPull::decode(&negative), Err(PriceSourceError::InvalidPrice));
+    }
+}
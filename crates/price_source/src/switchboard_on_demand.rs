@@ -0,0 +1,157 @@
+//! Switchboard On-Demand (PullFeedAccountData)
+//!
+//! Le résultat courant (`CurrentResult`) porte la médiane des soumissions,
+//! leur écart-type et le slot du résultat. Les valeurs sont des décimaux
+//! i128 à 18 décimales.
+
+use solana_program::{pubkey, pubkey::Pubkey};
+
+use crate::{
+    normalize_decimal, read_array, read_i128, read_i64, read_u32, read_u64, PriceObservation,
+    PriceSource, PriceSourceError, PriceSourceKind,
+};
+
+/// Switchboard On-Demand Program ID (mainnet)
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// Switchboard On-Demand Program ID (devnet)
+pub const SWITCHBOARD_ON_DEMAND_DEVNET_PROGRAM_ID: Pubkey =
+    pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+
+const PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+/// Exposant des décimaux Switchboard On-Demand (PRECISION = 18)
+const ON_DEMAND_EXPO: i32 = -18;
+
+// PullFeedAccountData (repr(C)): 8 discriminator + 32 submissions de 64 bytes
+const MIN_SAMPLE_SIZE_OFFSET: usize = 2215;
+const LAST_UPDATE_TIMESTAMP_OFFSET: usize = 2216;
+// CurrentResult
+const RESULT_OFFSET: usize = 2264;
+const RESULT_VALUE_OFFSET: usize = RESULT_OFFSET;
+const RESULT_STD_DEV_OFFSET: usize = RESULT_OFFSET + 16;
+const RESULT_NUM_SAMPLES_OFFSET: usize = RESULT_OFFSET + 96;
+const RESULT_SLOT_OFFSET: usize = RESULT_OFFSET + 104;
+const MAX_STALENESS_OFFSET: usize = RESULT_OFFSET + 128;
+const MIN_LEN: usize = MAX_STALENESS_OFFSET + 4;
+
+/// Champs utiles d'un compte PullFeedAccountData
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PullFeed {
+    pub value: i128,
+    pub std_dev: i128,
+    pub num_samples: u8,
+    pub min_sample_size: u8,
+    pub result_slot: u64,
+    pub last_update_timestamp: i64,
+    /// Staleness maximale configurée sur le feed, en slots (0 = non configurée)
+    pub max_staleness_slots: u32,
+}
+
+pub struct SwitchboardOnDemand;
+
+impl SwitchboardOnDemand {
+    pub fn parse(data: &[u8]) -> Result<PullFeed, PriceSourceError> {
+        if data.len() < MIN_LEN || read_array::<8>(data, 0)? != PULL_FEED_DISCRIMINATOR {
+            return Err(PriceSourceError::InvalidAccountData);
+        }
+
+        Ok(PullFeed {
+            value: read_i128(data, RESULT_VALUE_OFFSET)?,
+            std_dev: read_i128(data, RESULT_STD_DEV_OFFSET)?,
+            num_samples: read_array::<1>(data, RESULT_NUM_SAMPLES_OFFSET)?[0],
+            min_sample_size: read_array::<1>(data, MIN_SAMPLE_SIZE_OFFSET)?[0],
+            result_slot: read_u64(data, RESULT_SLOT_OFFSET)?,
+            last_update_timestamp: read_i64(data, LAST_UPDATE_TIMESTAMP_OFFSET)?,
+            max_staleness_slots: read_u32(data, MAX_STALENESS_OFFSET)?,
+        })
+    }
+}
+
+impl PriceSource for SwitchboardOnDemand {
+    const KIND: PriceSourceKind = PriceSourceKind::SwitchboardOnDemand;
+
+    fn owner() -> Option<Pubkey> {
+        Some(SWITCHBOARD_ON_DEMAND_PROGRAM_ID)
+    }
+
+    fn accepts_owner(owner: &Pubkey) -> bool {
+        *owner == SWITCHBOARD_ON_DEMAND_PROGRAM_ID || *owner == SWITCHBOARD_ON_DEMAND_DEVNET_PROGRAM_ID
+    }
+
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+        let feed = Self::parse(data)?;
+
+        // Le résultat n'est valide qu'avec assez de soumissions d'oracles
+        if feed.num_samples == 0 || feed.num_samples < feed.min_sample_size {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+
+        let price = normalize_decimal(feed.value, ON_DEMAND_EXPO).ok_or(PriceSourceError::InvalidPrice)?;
+        if price == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+        let confidence =
+            normalize_decimal(feed.std_dev, ON_DEMAND_EXPO).ok_or(PriceSourceError::InvalidPrice)?;
+
+        Ok(PriceObservation {
+            price,
+            confidence,
+            publish_time: feed.last_update_timestamp,
+            slot: feed.result_slot,
+            kind: Self::KIND,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn encode_for_tests(value: i128, std_dev: i128, num_samples: u8, min_sample_size: u8, slot: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 3208];
+    data[0..8].copy_from_slice(&PULL_FEED_DISCRIMINATOR);
+    data[MIN_SAMPLE_SIZE_OFFSET] = min_sample_size;
+    data[LAST_UPDATE_TIMESTAMP_OFFSET..LAST_UPDATE_TIMESTAMP_OFFSET + 8].copy_from_slice(&1_000i64.to_le_bytes());
+    data[RESULT_VALUE_OFFSET..RESULT_VALUE_OFFSET + 16].copy_from_slice(&value.to_le_bytes());
+    data[RESULT_STD_DEV_OFFSET..RESULT_STD_DEV_OFFSET + 16].copy_from_slice(&std_dev.to_le_bytes());
+    data[RESULT_NUM_SAMPLES_OFFSET] = num_samples;
+    data[RESULT_SLOT_OFFSET..RESULT_SLOT_OFFSET + 8].copy_from_slice(&slot.to_le_bytes());
+    data[MAX_STALENESS_OFFSET..MAX_STALENESS_OFFSET + 4].copy_from_slice(&150u32.to_le_bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read;
+
+    #[test]
+    fn test_decode_current_result() {
+        // 150.5 et écart-type 0.25 en 18 décimales
+        let data = encode_for_tests(150_500_000_000_000_000_000, 250_000_000_000_000_000, 5, 3, 1_234);
+        let obs = SwitchboardOnDemand::decode(&data).unwrap();
+        assert_eq!(obs.price, 15_050_000_000);
+        assert_eq!(obs.confidence, 25_000_000);
+        assert_eq!(obs.slot, 1_234);
+        assert_eq!(obs.publish_time, 1_000);
+        assert_eq!(SwitchboardOnDemand::parse(&data).unwrap().max_staleness_slots, 150);
+    }
+
+    #[test]
+    fn test_rejects_insufficient_samples() {
+        let data = encode_for_tests(150_500_000_000_000_000_000, 0, 2, 3, 1_234);
+        assert_eq!(SwitchboardOnDemand::decode(&data), Err(PriceSourceError::InvalidPrice));
+        let data = encode_for_tests(150_500_000_000_000_000_000, 0, 0, 0, 1_234);
+        assert_eq!(SwitchboardOnDemand::decode(&data), Err(PriceSourceError::InvalidPrice));
+    }
+
+    #[test]
+    fn test_accepts_mainnet_and_devnet_owners() {
+        let data = encode_for_tests(150_500_000_000_000_000_000, 0, 1, 1, 1);
+        assert!(read::<SwitchboardOnDemand>(&SWITCHBOARD_ON_DEMAND_PROGRAM_ID, &data).is_ok());
+        assert!(read::<SwitchboardOnDemand>(&SWITCHBOARD_ON_DEMAND_DEVNET_PROGRAM_ID, &data).is_ok());
+        assert_eq!(
+            read::<SwitchboardOnDemand>(&Pubkey::new_unique(), &data),
+            Err(PriceSourceError::InvalidOwner)
+        );
+    }
+}
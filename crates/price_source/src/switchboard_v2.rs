@@ -0,0 +1,109 @@
+//! Switchboard V2 (AggregatorAccountData, latest_confirmed_round)
+//!
+//! Lecture manuelle du layout zero-copy packed pour ne pas dépendre de
+//! switchboard-solana (conflits de version Anchor, conversion f64).
+
+use solana_program::{pubkey, pubkey::Pubkey};
+
+use crate::{
+    normalize_decimal, read_array, read_i128, read_i64, read_u32, read_u64, PriceObservation,
+    PriceSource, PriceSourceError, PriceSourceKind,
+};
+
+/// Switchboard V2 Program ID (mainnet)
+pub const SWITCHBOARD_V2_PROGRAM_ID: Pubkey = pubkey!("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+
+const AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+
+// AggregatorAccountData.latest_confirmed_round (AggregatorRound, repr(packed))
+const ROUND_OFFSET: usize = 341;
+const NUM_SUCCESS_OFFSET: usize = ROUND_OFFSET;
+const ROUND_OPEN_SLOT_OFFSET: usize = ROUND_OFFSET + 9;
+const ROUND_OPEN_TIMESTAMP_OFFSET: usize = ROUND_OFFSET + 17;
+const RESULT_MANTISSA_OFFSET: usize = ROUND_OFFSET + 25;
+const RESULT_SCALE_OFFSET: usize = ROUND_OFFSET + 41;
+const STD_DEV_MANTISSA_OFFSET: usize = ROUND_OFFSET + 45;
+const STD_DEV_SCALE_OFFSET: usize = ROUND_OFFSET + 61;
+const MIN_LEN: usize = STD_DEV_SCALE_OFFSET + 4;
+
+pub struct SwitchboardV2;
+
+impl PriceSource for SwitchboardV2 {
+    const KIND: PriceSourceKind = PriceSourceKind::SwitchboardV2;
+
+    fn owner() -> Option<Pubkey> {
+        Some(SWITCHBOARD_V2_PROGRAM_ID)
+    }
+
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+        if data.len() < MIN_LEN || read_array::<8>(data, 0)? != AGGREGATOR_DISCRIMINATOR {
+            return Err(PriceSourceError::InvalidAccountData);
+        }
+
+        // Aucun round confirmé: pas de résultat publié
+        if read_u32(data, NUM_SUCCESS_OFFSET)? == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+
+        let scale = read_u32(data, RESULT_SCALE_OFFSET)?;
+        let expo = -i32::try_from(scale).map_err(|_| PriceSourceError::InvalidPrice)?;
+        let price = normalize_decimal(read_i128(data, RESULT_MANTISSA_OFFSET)?, expo)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+        if price == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+
+        let std_dev_scale = read_u32(data, STD_DEV_SCALE_OFFSET)?;
+        let std_dev_expo = -i32::try_from(std_dev_scale).map_err(|_| PriceSourceError::InvalidPrice)?;
+        let confidence = normalize_decimal(read_i128(data, STD_DEV_MANTISSA_OFFSET)?, std_dev_expo)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+
+        Ok(PriceObservation {
+            price,
+            confidence,
+            publish_time: read_i64(data, ROUND_OPEN_TIMESTAMP_OFFSET)?,
+            slot: read_u64(data, ROUND_OPEN_SLOT_OFFSET)?,
+            kind: Self::KIND,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregator(mantissa: i128, scale: u32, num_success: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 3851];
+        data[0..8].copy_from_slice(&AGGREGATOR_DISCRIMINATOR);
+        data[NUM_SUCCESS_OFFSET..NUM_SUCCESS_OFFSET + 4].copy_from_slice(&num_success.to_le_bytes());
+        data[ROUND_OPEN_SLOT_OFFSET..ROUND_OPEN_SLOT_OFFSET + 8].copy_from_slice(&55u64.to_le_bytes());
+        data[ROUND_OPEN_TIMESTAMP_OFFSET..ROUND_OPEN_TIMESTAMP_OFFSET + 8]
+            .copy_from_slice(&1_000i64.to_le_bytes());
+        data[RESULT_MANTISSA_OFFSET..RESULT_MANTISSA_OFFSET + 16].copy_from_slice(&mantissa.to_le_bytes());
+        data[RESULT_SCALE_OFFSET..RESULT_SCALE_OFFSET + 4].copy_from_slice(&scale.to_le_bytes());
+        data[STD_DEV_MANTISSA_OFFSET..STD_DEV_MANTISSA_OFFSET + 16].copy_from_slice(&25i128.to_le_bytes());
+        data[STD_DEV_SCALE_OFFSET..STD_DEV_SCALE_OFFSET + 4].copy_from_slice(&2u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_decode_latest_round() {
+        // 150.5 avec scale 9
+        let obs = SwitchboardV2::decode(&aggregator(150_500_000_000, 9, 3)).unwrap();
+        assert_eq!(obs.price, 15_050_000_000);
+        assert_eq!(obs.confidence, 25_000_000);
+        assert_eq!(obs.publish_time, 1_000);
+        assert_eq!(obs.slot, 55);
+    }
+
+    #[test]
+    fn test_rejects_empty_round_and_bad_discriminator() {
+        assert_eq!(
+            SwitchboardV2::decode(&aggregator(150_500_000_000, 9, 0)),
+            Err(PriceSourceError::InvalidPrice)
+        );
+        let mut data = aggregator(150_500_000_000, 9, 3);
+        data[0] = 0;
+        assert_eq!(SwitchboardV2::decode(&data), Err(PriceSourceError::InvalidAccountData));
+    }
+}
@@ -0,0 +1,86 @@
+//! Feed de test local (localnet, tests d'intégration, client en mode mock)
+//!
+//! Aucun contrôle de propriétaire: n'importe qui peut créer ce layout, il ne
+//! doit être accepté on-chain que derrière une feature de test.
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    normalize_decimal, read_array, read_i32, read_i64, read_u64, PriceObservation, PriceSource,
+    PriceSourceError, PriceSourceKind,
+};
+
+pub const TEST_FEED_MAGIC: [u8; 8] = *b"SBTESTFD";
+
+/// magic (8) + price (8) + conf (8) + expo (4) + publish_time (8) + slot (8)
+pub const TEST_FEED_LEN: usize = 8 + 8 + 8 + 4 + 8 + 8;
+
+pub struct TestFeed;
+
+impl TestFeed {
+    /// Sérialise un feed de test (utilisé par les tests et les scripts localnet)
+    pub fn encode(price: i64, conf: u64, expo: i32, publish_time: i64, slot: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(TEST_FEED_LEN);
+        data.extend_from_slice(&TEST_FEED_MAGIC);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&expo.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&slot.to_le_bytes());
+        data
+    }
+}
+
+impl PriceSource for TestFeed {
+    const KIND: PriceSourceKind = PriceSourceKind::TestFeed;
+
+    fn owner() -> Option<Pubkey> {
+        None
+    }
+
+    fn decode(data: &[u8]) -> Result<PriceObservation, PriceSourceError> {
+        if data.len() < TEST_FEED_LEN || read_array::<8>(data, 0)? != TEST_FEED_MAGIC {
+            return Err(PriceSourceError::InvalidAccountData);
+        }
+
+        let expo = read_i32(data, 24)?;
+        let price = normalize_decimal(read_i64(data, 8)? as i128, expo)
+            .ok_or(PriceSourceError::InvalidPrice)?;
+        if price == 0 {
+            return Err(PriceSourceError::InvalidPrice);
+        }
+        let confidence =
+            normalize_decimal(read_u64(data, 16)? as i128, expo).ok_or(PriceSourceError::InvalidPrice)?;
+
+        Ok(PriceObservation {
+            price,
+            confidence,
+            publish_time: read_i64(data, 28)?,
+            slot: read_u64(data, 36)?,
+            kind: Self::KIND,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let obs = TestFeed::decode(&TestFeed::encode(1_505, 5, -1, 1_000, 42)).unwrap();
+        assert_eq!(obs.price, 15_050_000_000);
+        assert_eq!(obs.confidence, 50_000_000);
+        assert_eq!(obs.publish_time, 1_000);
+        assert_eq!(obs.slot, 42);
+        assert_eq!(obs.kind, PriceSourceKind::TestFeed);
+    }
+
+    #[test]
+    fn test_rejects_zero_price() {
+        assert_eq!(
+            TestFeed::decode(&TestFeed::encode(0, 0, -8, 1_000, 42)),
+            Err(PriceSourceError::InvalidPrice)
+        );
+    }
+}
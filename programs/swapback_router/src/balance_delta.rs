@@ -0,0 +1,136 @@
+//! Contrôle des deltas de solde autour d'un CPI de venue
+//!
+//! Chaque adaptateur `cpi_*` se contente de valider ses comptes et d'invoquer la
+//! venue; le wrapper mesure les soldes source/destination avant et après le CPI
+//! et impose: input dépensé ≤ amount_in (sans marge), output reçu ≥ min_out.
+//! L'input non dépensé (fill partiel) reste sur le compte source de l'utilisateur
+//! et est retourné à l'appelant pour être tracé.
+
+use anchor_lang::prelude::*;
+use crate::token_extensions::unpack_token_account;
+use crate::ErrorCode;
+
+/// Résultat mesuré d'un CPI de swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceDelta {
+    /// Input effectivement prélevé sur le compte source
+    pub spent: u64,
+    /// Output effectivement crédité sur le compte destination
+    pub received: u64,
+    /// Input demandé mais non consommé (fill partiel)
+    pub unspent: u64,
+}
+
+/// Exécute `cpi` entre deux snapshots de soldes et vérifie les deltas
+pub fn with_balance_deltas<F>(
+    source: &AccountInfo,
+    destination: &AccountInfo,
+    amount_in: u64,
+    min_out: u64,
+    cpi: F,
+) -> Result<BalanceDelta>
+where
+    F: FnOnce() -> Result<()>,
+{
+    let source_before = read_token_amount(source)?;
+    let destination_before = read_token_amount(destination)?;
+
+    cpi()?;
+
+    let source_after = read_token_amount(source)?;
+    let destination_after = read_token_amount(destination)?;
+
+    compute_delta(
+        source_before,
+        source_after,
+        destination_before,
+        destination_after,
+        amount_in,
+        min_out,
+    )
+}
+
+/// Calcule et vérifie les deltas à partir des soldes avant/après
+pub fn compute_delta(
+    source_before: u64,
+    source_after: u64,
+    destination_before: u64,
+    destination_after: u64,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<BalanceDelta> {
+    let spent = source_before
+        .checked_sub(source_after)
+        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
+    let received = destination_after
+        .checked_sub(destination_before)
+        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
+
+    require!(spent > 0, ErrorCode::VenueNoInputSpent);
+    if spent > amount_in {
+        msg!("Venue pulled {} input, allowed {}", spent, amount_in);
+        return err!(ErrorCode::VenueInputOverspent);
+    }
+    if received < min_out {
+        msg!("Slippage exceeded, got {} expected min {}", received, min_out);
+        return err!(ErrorCode::SlippageExceeded);
+    }
+
+    Ok(BalanceDelta {
+        spent,
+        received,
+        unspent: amount_in.saturating_sub(spent),
+    })
+}
+
+/// Solde d'un compte SPL Token ou Token-2022
+pub fn read_token_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
+    let token_account =
+        unpack_token_account(&data).map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
+    Ok(token_account.amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_fill() {
+        let delta = compute_delta(1_000, 0, 50, 1_050, 1_000, 990).unwrap();
+        assert_eq!(delta, BalanceDelta { spent: 1_000, received: 1_000, unspent: 0 });
+    }
+
+    #[test]
+    fn test_partial_fill_records_unspent() {
+        // Phoenix IOC: reste de lot non consommé
+        let delta = compute_delta(1_000, 37, 0, 950, 1_000, 900).unwrap();
+        assert_eq!(delta.spent, 963);
+        assert_eq!(delta.unspent, 37);
+    }
+
+    #[test]
+    fn test_rejects_any_overspend() {
+        assert!(compute_delta(2_000, 1_000, 0, 1_000, 1_000, 0).is_ok());
+        assert_eq!(
+            compute_delta(2_000, 999, 0, 1_000, 1_000, 0).unwrap_err(),
+            error!(ErrorCode::VenueInputOverspent)
+        );
+    }
+
+    #[test]
+    fn test_rejects_no_input_and_slippage() {
+        assert_eq!(
+            compute_delta(1_000, 1_000, 0, 10, 1_000, 0).unwrap_err(),
+            error!(ErrorCode::VenueNoInputSpent)
+        );
+        assert_eq!(
+            compute_delta(1_000, 0, 0, 989, 1_000, 990).unwrap_err(),
+            error!(ErrorCode::SlippageExceeded)
+        );
+        // Destination débitée: route incohérente
+        assert!(compute_delta(1_000, 0, 100, 50, 1_000, 0).is_err());
+    }
+}
@@ -6,6 +6,7 @@ use anchor_lang::solana_program::{
 use anchor_spl::token::TokenAccount as SplTokenAccount;
 use solana_program::program::invoke_signed;
 
+use crate::balance_delta::read_token_amount;
 use crate::{ErrorCode, JupiterRouteParams, SwapToC, JUPITER_PROGRAM_ID};
 
 pub const JUPITER_SWAP_ACCOUNT_COUNT: usize = 48;
@@ -68,7 +69,7 @@ pub fn swap(
     );
 
     let destination_account_info = ctx.accounts.user_token_account_b.to_account_info();
-    let amount_before = ctx.accounts.user_token_account_b.amount;
+    let amount_before = crate::balance_delta::read_token_amount(&destination_account_info)?;
 
     let instruction = Instruction {
         program_id: JUPITER_PROGRAM_ID,
@@ -95,15 +96,6 @@ pub fn swap(
     Ok(amount_out)
 }
 
-/// Helper: read token amount from AccountInfo using anchor_spl TokenAccount
-fn token_amount(ai: &AccountInfo) -> Result<u64> {
-    let data = ai.try_borrow_data()?;
-    let mut data_slice: &[u8] = &data;
-    let acc = SplTokenAccount::try_deserialize(&mut data_slice)
-        .map_err(|_| error!(ErrorCode::InvalidTokenAccount))?;
-    Ok(acc.amount)
-}
-
 /// Exécute un swap Jupiter via CPI en rejouant l'instruction (data + remaining_accounts),
 /// puis calcule `amount_out` via delta de la token account de destination.
 ///
@@ -123,8 +115,8 @@ pub fn swap_with_balance_deltas<'info>(
     signer_seeds: &[&[&[u8]]],
 ) -> Result<u64> {
     // Snapshot balances
-    let pre_in = token_amount(user_source_ata)?;
-    let pre_out = token_amount(user_dest_ata)?;
+    let pre_in = read_token_amount(user_source_ata)?;
+    let pre_out = read_token_amount(user_dest_ata)?;
 
     // CPI: build metas from remaining accounts with pre-allocation
     let account_count = remaining_accounts.len();
@@ -149,8 +141,8 @@ pub fn swap_with_balance_deltas<'info>(
         .map_err(|_| error!(ErrorCode::JupiterCpiFailed))?;
 
     // Snapshot balances post
-    let post_in = token_amount(user_source_ata)?;
-    let post_out = token_amount(user_dest_ata)?;
+    let post_in = read_token_amount(user_source_ata)?;
+    let post_out = read_token_amount(user_dest_ata)?;
 
     // Deltas
     let spent_in = pre_in.saturating_sub(post_in);
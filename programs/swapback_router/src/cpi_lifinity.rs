@@ -1,8 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::solana_program::program_pack::Pack;
-use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, LIFINITY_PROGRAM_ID};
 
@@ -25,19 +23,19 @@ const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 /// 10 pythAccount (readonly)
 /// 11 pythPcAccount (readonly)
 /// 12 configAccount (mut)
-const AUTHORITY_INDEX: usize = 0;
-const AMM_INDEX: usize = 1;
-const USER_TRANSFER_AUTHORITY_INDEX: usize = 2;
-const SOURCE_INFO_INDEX: usize = 3;
-const DESTINATION_INFO_INDEX: usize = 4;
-const SWAP_SOURCE_INDEX: usize = 5;
-const SWAP_DESTINATION_INDEX: usize = 6;
-const POOL_MINT_INDEX: usize = 7;
-const FEE_ACCOUNT_INDEX: usize = 8;
-const TOKEN_PROGRAM_INDEX: usize = 9;
-const PYTH_ACCOUNT_INDEX: usize = 10;
-const PYTH_PC_ACCOUNT_INDEX: usize = 11;
-const CONFIG_ACCOUNT_INDEX: usize = 12;
+pub(crate) const AUTHORITY_INDEX: usize = 0;
+pub(crate) const AMM_INDEX: usize = 1;
+pub(crate) const USER_TRANSFER_AUTHORITY_INDEX: usize = 2;
+pub(crate) const SOURCE_INFO_INDEX: usize = 3;
+pub(crate) const DESTINATION_INFO_INDEX: usize = 4;
+pub(crate) const SWAP_SOURCE_INDEX: usize = 5;
+pub(crate) const SWAP_DESTINATION_INDEX: usize = 6;
+pub(crate) const POOL_MINT_INDEX: usize = 7;
+pub(crate) const FEE_ACCOUNT_INDEX: usize = 8;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 9;
+pub(crate) const PYTH_ACCOUNT_INDEX: usize = 10;
+pub(crate) const PYTH_PC_ACCOUNT_INDEX: usize = 11;
+pub(crate) const CONFIG_ACCOUNT_INDEX: usize = 12;
 
 /// Minimum accounts for Lifinity swap
 pub const LIFINITY_SWAP_ACCOUNT_COUNT: usize = 13;
@@ -53,7 +51,7 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
     if account_slice.len() < LIFINITY_SWAP_ACCOUNT_COUNT {
         msg!("Lifinity: insufficient accounts, need {} got {}", 
              LIFINITY_SWAP_ACCOUNT_COUNT, account_slice.len());
@@ -78,19 +76,14 @@ pub fn swap(
     let user_token_a_key = ctx.accounts.user_token_account_a.key();
     let user_token_b_key = ctx.accounts.user_token_account_b.key();
 
-    // Determine swap direction
-    let destination_account = 
-        if source_info.key() == user_token_a_key && destination_info.key() == user_token_b_key {
-            destination_info
-        } else if source_info.key() == user_token_b_key && destination_info.key() == user_token_a_key {
-            destination_info
-        } else {
-            msg!("Lifinity: token account mismatch");
-            return err!(ErrorCode::DexExecutionFailed);
-        };
-
-    // Read pre-swap balance
-    let pre_amount = read_token_amount(destination_account)?;
+    // Source et destination: la paire de comptes de l'utilisateur, dans un sens ou l'autre
+    let pairing = (source_info.key(), destination_info.key());
+    if pairing != (user_token_a_key, user_token_b_key)
+        && pairing != (user_token_b_key, user_token_a_key)
+    {
+        msg!("Lifinity: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
 
     // Build Lifinity swap instruction
     // Lifinity swap params: amount_in, minimum_amount_out
@@ -137,26 +130,6 @@ pub fn swap(
             error!(ErrorCode::DexExecutionFailed)
         })?;
 
-    // Verify output
-    let post_amount = read_token_amount(destination_account)?;
-    let amount_out = post_amount
-        .checked_sub(pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        msg!("Lifinity: slippage exceeded, got {} expected min {}", amount_out, min_out);
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    msg!("Lifinity swap success: {} in -> {} out", amount_in, amount_out);
-    Ok(amount_out)
+    Ok(())
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account = SplAccount::unpack(&data)
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
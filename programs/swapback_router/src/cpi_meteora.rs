@@ -10,21 +10,21 @@ use crate::{ErrorCode, SwapToC, METEORA_DLMM_PROGRAM_ID};
 const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 
 /// Account indices for Meteora DLMM swap
-const LB_PAIR_INDEX: usize = 0;
-const BIN_ARRAY_BITMAP_EXTENSION_INDEX: usize = 1;
-const RESERVE_X_INDEX: usize = 2;
-const RESERVE_Y_INDEX: usize = 3;
-const USER_TOKEN_X_INDEX: usize = 4;
-const USER_TOKEN_Y_INDEX: usize = 5;
-const TOKEN_X_MINT_INDEX: usize = 6;
-const TOKEN_Y_MINT_INDEX: usize = 7;
-const ORACLE_INDEX: usize = 8;
-const HOST_FEE_INDEX: usize = 9;
-const USER_INDEX: usize = 10;
-const TOKEN_X_PROGRAM_INDEX: usize = 11;
-const TOKEN_Y_PROGRAM_INDEX: usize = 12;
-const EVENT_AUTHORITY_INDEX: usize = 13;
-const PROGRAM_INDEX: usize = 14;
+pub(crate) const LB_PAIR_INDEX: usize = 0;
+pub(crate) const BIN_ARRAY_BITMAP_EXTENSION_INDEX: usize = 1;
+pub(crate) const RESERVE_X_INDEX: usize = 2;
+pub(crate) const RESERVE_Y_INDEX: usize = 3;
+pub(crate) const USER_TOKEN_X_INDEX: usize = 4;
+pub(crate) const USER_TOKEN_Y_INDEX: usize = 5;
+pub(crate) const TOKEN_X_MINT_INDEX: usize = 6;
+pub(crate) const TOKEN_Y_MINT_INDEX: usize = 7;
+pub(crate) const ORACLE_INDEX: usize = 8;
+pub(crate) const HOST_FEE_INDEX: usize = 9;
+pub(crate) const USER_INDEX: usize = 10;
+pub(crate) const TOKEN_X_PROGRAM_INDEX: usize = 11;
+pub(crate) const TOKEN_Y_PROGRAM_INDEX: usize = 12;
+pub(crate) const EVENT_AUTHORITY_INDEX: usize = 13;
+pub(crate) const PROGRAM_INDEX: usize = 14;
 
 /// Minimum accounts required for Meteora DLMM swap
 pub const METEORA_SWAP_ACCOUNT_COUNT: usize = 15;
@@ -41,7 +41,7 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
     if account_slice.len() < METEORA_SWAP_ACCOUNT_COUNT {
         msg!("Meteora DLMM: insufficient accounts, need {} got {}", 
              METEORA_SWAP_ACCOUNT_COUNT, account_slice.len());
@@ -79,11 +79,6 @@ pub fn swap(
         return err!(ErrorCode::DexExecutionFailed);
     };
 
-    // Read both user-side token balances pre/post and compute the positive delta.
-    // This is more robust than assuming a single destination account and avoids underflow
-    // when the observed account is actually the source (post < pre).
-    let expected_output_key = ctx.accounts.user_token_account_b.key();
-
     // --- DEBUG LOGGING: dump relevant account pubkeys + mints (best-effort) ---
     fn account_mint_str(account: &AccountInfo) -> Option<String> {
         if account.data_is_empty() { return None; }
@@ -102,10 +97,6 @@ pub fn swap(
     if let Some(m) = account_mint_str(&account_slice[RESERVE_Y_INDEX]) { msg!("Meteora DLMM: reserve_y.mint={}", m); }
     msg!("Meteora DLMM: token_x_mint={}, token_y_mint={}", account_slice[TOKEN_X_MINT_INDEX].key(), account_slice[TOKEN_Y_MINT_INDEX].key());
 
-    // Read pre-swap balances (best-effort: both must be SPL Token accounts)
-    let pre_x = read_token_amount(user_token_x)?;
-    let pre_y = read_token_amount(user_token_y)?;
-
     // Build swap instruction data
     // Meteora DLMM swap params: amount_in, min_out, x_to_y
     let mut data = Vec::with_capacity(8 + 8 + 8 + 1);
@@ -138,45 +129,6 @@ pub fn swap(
             error!(ErrorCode::DexExecutionFailed)
         })?;
 
-    // Verify output
-    let post_x = read_token_amount(user_token_x)?;
-    let post_y = read_token_amount(user_token_y)?;
-
-    let delta_x = post_x.checked_sub(pre_x).unwrap_or(0);
-    let delta_y = post_y.checked_sub(pre_y).unwrap_or(0);
-
-    // Prefer the delta on the router-declared output account.
-    // Fallbacks:
-    // - if it doesn't match x/y keys (unexpected), use direction x_to_y
-    // - if still zero, take the max positive delta as a last resort
-    let mut amount_out = if expected_output_key == user_token_x.key() {
-        delta_x
-    } else if expected_output_key == user_token_y.key() {
-        delta_y
-    } else if x_to_y {
-        delta_y
-    } else {
-        delta_x
-    };
-
-    if amount_out == 0 {
-        amount_out = core::cmp::max(delta_x, delta_y);
-    }
-
-    if amount_out < min_out {
-        msg!("Meteora DLMM: slippage exceeded, got {} expected min {}", amount_out, min_out);
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    msg!("Meteora DLMM swap success: {} in -> {} out", amount_in, amount_out);
-    Ok(amount_out)
+    Ok(())
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account = SplAccount::unpack(&data)
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+use crate::{ErrorCode, SwapToC, METEORA_DAMM_PROGRAM_ID, METEORA_DAMM_V2_PROGRAM_ID};
+
+/// Meteora Dynamic Vault program (vaults de prêt sous-jacents aux pools Dynamic AMM)
+pub const METEORA_VAULT_PROGRAM_ID: Pubkey = pubkey!("24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi");
+
+/// `swap` discriminator (identique pour Dynamic AMM et DAMM v2)
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Account indices for Meteora Dynamic AMM swap
+pub(crate) const POOL_INDEX: usize = 0;
+pub(crate) const USER_SOURCE_TOKEN_INDEX: usize = 1;
+pub(crate) const USER_DESTINATION_TOKEN_INDEX: usize = 2;
+pub(crate) const A_VAULT_INDEX: usize = 3;
+pub(crate) const B_VAULT_INDEX: usize = 4;
+pub(crate) const A_TOKEN_VAULT_INDEX: usize = 5;
+pub(crate) const B_TOKEN_VAULT_INDEX: usize = 6;
+pub(crate) const A_VAULT_LP_MINT_INDEX: usize = 7;
+pub(crate) const B_VAULT_LP_MINT_INDEX: usize = 8;
+pub(crate) const A_VAULT_LP_INDEX: usize = 9;
+pub(crate) const B_VAULT_LP_INDEX: usize = 10;
+pub(crate) const PROTOCOL_TOKEN_FEE_INDEX: usize = 11;
+pub(crate) const USER_INDEX: usize = 12;
+pub(crate) const VAULT_PROGRAM_INDEX: usize = 13;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 14;
+
+/// Accounts for Meteora Dynamic AMM swap
+pub const METEORA_DAMM_SWAP_ACCOUNT_COUNT: usize = 15;
+
+/// Account indices for Meteora DAMM v2 (cp-amm) swap
+pub(crate) const V2_POOL_AUTHORITY_INDEX: usize = 0;
+pub(crate) const V2_POOL_INDEX: usize = 1;
+pub(crate) const V2_INPUT_TOKEN_ACCOUNT_INDEX: usize = 2;
+pub(crate) const V2_OUTPUT_TOKEN_ACCOUNT_INDEX: usize = 3;
+pub(crate) const V2_TOKEN_A_VAULT_INDEX: usize = 4;
+pub(crate) const V2_TOKEN_B_VAULT_INDEX: usize = 5;
+pub(crate) const V2_TOKEN_A_MINT_INDEX: usize = 6;
+pub(crate) const V2_TOKEN_B_MINT_INDEX: usize = 7;
+pub(crate) const V2_PAYER_INDEX: usize = 8;
+pub(crate) const V2_TOKEN_A_PROGRAM_INDEX: usize = 9;
+pub(crate) const V2_TOKEN_B_PROGRAM_INDEX: usize = 10;
+pub(crate) const V2_REFERRAL_TOKEN_ACCOUNT_INDEX: usize = 11;
+pub(crate) const V2_EVENT_AUTHORITY_INDEX: usize = 12;
+pub(crate) const V2_PROGRAM_INDEX: usize = 13;
+
+/// Accounts for Meteora DAMM v2 swap (referral optionnel = ID du programme)
+pub const METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT: usize = 14;
+
+/// Execute a swap through a Meteora Dynamic AMM pool
+///
+/// Pools constant-product ou stable dont la liquidité est déposée dans des
+/// vaults de prêt Meteora: le swap passe par les vaults A/B et leurs LP.
+pub fn swap(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> Result<()> {
+    if account_slice.len() < METEORA_DAMM_SWAP_ACCOUNT_COUNT {
+        msg!("Meteora DAMM: insufficient accounts, need {} got {}",
+             METEORA_DAMM_SWAP_ACCOUNT_COUNT, account_slice.len());
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let user = &account_slice[USER_INDEX];
+    if !user.is_signer || user.key() != ctx.accounts.user.key() {
+        msg!("Meteora DAMM: user must be the signing swapper");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    if account_slice[USER_SOURCE_TOKEN_INDEX].key() != ctx.accounts.user_token_account_a.key()
+        || account_slice[USER_DESTINATION_TOKEN_INDEX].key() != ctx.accounts.user_token_account_b.key()
+    {
+        msg!("Meteora DAMM: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    // swap(in_amount: u64, minimum_out_amount: u64)
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+
+    let account_metas: Vec<AccountMeta> = account_slice[..METEORA_DAMM_SWAP_ACCOUNT_COUNT]
+        .iter()
+        .enumerate()
+        .map(|(i, info)| {
+            // Tout est writable sauf le user et les programmes
+            let is_writable = !matches!(i, USER_INDEX | VAULT_PROGRAM_INDEX | TOKEN_PROGRAM_INDEX);
+
+            if is_writable {
+                AccountMeta::new(*info.key, info.is_signer)
+            } else {
+                AccountMeta::new_readonly(*info.key, info.is_signer)
+            }
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: METEORA_DAMM_PROGRAM_ID,
+        accounts: account_metas,
+        data,
+    };
+
+    invoke(&instruction, &account_slice[..METEORA_DAMM_SWAP_ACCOUNT_COUNT])
+        .map_err(|e| {
+            msg!("Meteora DAMM swap failed: {:?}", e);
+            error!(ErrorCode::DexExecutionFailed)
+        })?;
+
+    Ok(())
+}
+
+/// Execute a swap through a Meteora DAMM v2 (cp-amm) pool
+pub fn swap_v2(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> Result<()> {
+    if account_slice.len() < METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT {
+        msg!("Meteora DAMM v2: insufficient accounts, need {} got {}",
+             METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT, account_slice.len());
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let payer = &account_slice[V2_PAYER_INDEX];
+    if !payer.is_signer || payer.key() != ctx.accounts.user.key() {
+        msg!("Meteora DAMM v2: payer must be the signing swapper");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    if account_slice[V2_INPUT_TOKEN_ACCOUNT_INDEX].key() != ctx.accounts.user_token_account_a.key()
+        || account_slice[V2_OUTPUT_TOKEN_ACCOUNT_INDEX].key() != ctx.accounts.user_token_account_b.key()
+    {
+        msg!("Meteora DAMM v2: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    // swap(SwapParameters { amount_in: u64, minimum_amount_out: u64 })
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+
+    let account_metas: Vec<AccountMeta> = account_slice[..METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT]
+        .iter()
+        .enumerate()
+        .map(|(i, info)| {
+            let is_writable = matches!(i,
+                V2_POOL_INDEX |
+                V2_INPUT_TOKEN_ACCOUNT_INDEX |
+                V2_OUTPUT_TOKEN_ACCOUNT_INDEX |
+                V2_TOKEN_A_VAULT_INDEX |
+                V2_TOKEN_B_VAULT_INDEX |
+                V2_REFERRAL_TOKEN_ACCOUNT_INDEX
+            ) && *info.key != METEORA_DAMM_V2_PROGRAM_ID;
+
+            if is_writable {
+                AccountMeta::new(*info.key, info.is_signer)
+            } else {
+                AccountMeta::new_readonly(*info.key, info.is_signer)
+            }
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: METEORA_DAMM_V2_PROGRAM_ID,
+        accounts: account_metas,
+        data,
+    };
+
+    invoke(&instruction, &account_slice[..METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT])
+        .map_err(|e| {
+            msg!("Meteora DAMM v2 swap failed: {:?}", e);
+            error!(ErrorCode::DexExecutionFailed)
+        })?;
+
+    Ok(())
+}
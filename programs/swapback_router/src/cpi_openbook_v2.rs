@@ -0,0 +1,212 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+use crate::{ErrorCode, SwapToC, OPENBOOK_V2_PROGRAM_ID};
+
+/// OpenBook v2 `place_take_order` discriminator
+const PLACE_TAKE_ORDER_DISCRIMINATOR: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+
+/// PlaceOrderType::ImmediateOrCancel
+const ORDER_TYPE_IMMEDIATE_OR_CANCEL: u8 = 1;
+
+/// Nombre max d'ordres maker consommés par un take (borne le coût en CU)
+const OPENBOOK_MATCH_LIMIT: u8 = 50;
+
+/// Account indices for OpenBook v2 place_take_order
+pub(crate) const SIGNER_INDEX: usize = 0;
+pub(crate) const PENALTY_PAYER_INDEX: usize = 1;
+pub(crate) const MARKET_INDEX: usize = 2;
+pub(crate) const MARKET_AUTHORITY_INDEX: usize = 3;
+pub(crate) const BIDS_INDEX: usize = 4;
+pub(crate) const ASKS_INDEX: usize = 5;
+pub(crate) const MARKET_BASE_VAULT_INDEX: usize = 6;
+pub(crate) const MARKET_QUOTE_VAULT_INDEX: usize = 7;
+pub(crate) const EVENT_HEAP_INDEX: usize = 8;
+pub(crate) const USER_BASE_ACCOUNT_INDEX: usize = 9;
+pub(crate) const USER_QUOTE_ACCOUNT_INDEX: usize = 10;
+pub(crate) const ORACLE_A_INDEX: usize = 11;
+pub(crate) const ORACLE_B_INDEX: usize = 12;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 13;
+pub(crate) const SYSTEM_PROGRAM_INDEX: usize = 14;
+pub(crate) const OPEN_ORDERS_ADMIN_INDEX: usize = 15;
+
+/// Accounts for OpenBook v2 place_take_order (comptes optionnels = ID du programme)
+pub const OPENBOOK_V2_SWAP_ACCOUNT_COUNT: usize = 16;
+
+/// OpenBook v2 Market offsets (bytes, discriminator inclus)
+///
+/// Layout zero-copy `Market`: [...][oracle_config:88][quoteLotSize:i64][baseLotSize:i64]...
+pub(crate) const OPENBOOK_QUOTE_LOT_SIZE_OFFSET: usize = 448;
+pub(crate) const OPENBOOK_BASE_LOT_SIZE_OFFSET: usize = 456;
+const OPENBOOK_REQUIRED_HEADER_LEN: usize = OPENBOOK_BASE_LOT_SIZE_OFFSET + 8;
+
+/// OpenBook v2 side
+#[repr(u8)]
+pub enum OpenBookSide {
+    Bid = 0,  // Buy base with quote
+    Ask = 1,  // Sell base for quote
+}
+
+/// Execute a swap through OpenBook v2 CLOB
+///
+/// Ordre taker IOC sans compte OpenOrders: le matching règle directement
+/// sur les comptes utilisateur, le reste non exécuté est annulé.
+pub fn swap(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_in: u64,
+    _min_out: u64, // pas de min-fill côté OpenBook: imposé par le wrapper de deltas de solde
+) -> Result<()> {
+    if account_slice.len() < OPENBOOK_V2_SWAP_ACCOUNT_COUNT {
+        msg!("OpenBook v2: insufficient accounts, need {} got {}",
+             OPENBOOK_V2_SWAP_ACCOUNT_COUNT, account_slice.len());
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let signer = &account_slice[SIGNER_INDEX];
+    if !signer.is_signer {
+        msg!("OpenBook v2: signer must be signer");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    if signer.key() != ctx.accounts.user.key() || account_slice[PENALTY_PAYER_INDEX].key() != signer.key() {
+        msg!("OpenBook v2: signer mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let base_account = &account_slice[USER_BASE_ACCOUNT_INDEX];
+    let quote_account = &account_slice[USER_QUOTE_ACCOUNT_INDEX];
+
+    let user_token_a_key = ctx.accounts.user_token_account_a.key();
+    let user_token_b_key = ctx.accounts.user_token_account_b.key();
+
+    // user_token_account_a = input, user_token_account_b = output
+    // Bid: input is quote, output is base
+    // Ask: input is base, output is quote
+    let side = if quote_account.key() == user_token_a_key && base_account.key() == user_token_b_key {
+        OpenBookSide::Bid
+    } else if base_account.key() == user_token_a_key && quote_account.key() == user_token_b_key {
+        OpenBookSide::Ask
+    } else {
+        msg!("OpenBook v2: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    };
+
+    let (base_lot_size, quote_lot_size) = read_market_lot_sizes(&account_slice[MARKET_INDEX])?;
+
+    // Conversion atoms -> lots; l'arrondi reste sur le compte source (fill partiel tracé)
+    let (price_lots, max_base_lots, max_quote_lots) = match side {
+        OpenBookSide::Ask => (1i64, lots(amount_in, base_lot_size)?, i64::MAX),
+        OpenBookSide::Bid => (i64::MAX, i64::MAX, lots(amount_in, quote_lot_size)?),
+    };
+
+    if max_base_lots == 0 || max_quote_lots == 0 {
+        msg!("OpenBook v2: amount_in too small for lot size");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    // Build PlaceTakeOrderArgs:
+    // side u8, price_lots i64, max_base_lots i64, max_quote_lots_including_fees i64,
+    // order_type u8, limit u8
+    let mut data = Vec::with_capacity(8 + 1 + 8 * 3 + 2);
+    data.extend_from_slice(&PLACE_TAKE_ORDER_DISCRIMINATOR);
+    data.push(side as u8);
+    data.extend_from_slice(&price_lots.to_le_bytes());
+    data.extend_from_slice(&max_base_lots.to_le_bytes());
+    data.extend_from_slice(&max_quote_lots.to_le_bytes());
+    data.push(ORDER_TYPE_IMMEDIATE_OR_CANCEL);
+    data.push(OPENBOOK_MATCH_LIMIT);
+
+    let account_metas: Vec<AccountMeta> = account_slice[..OPENBOOK_V2_SWAP_ACCOUNT_COUNT]
+        .iter()
+        .enumerate()
+        .map(|(i, info)| {
+            let is_writable = matches!(i,
+                SIGNER_INDEX |
+                PENALTY_PAYER_INDEX |
+                MARKET_INDEX |
+                BIDS_INDEX |
+                ASKS_INDEX |
+                MARKET_BASE_VAULT_INDEX |
+                MARKET_QUOTE_VAULT_INDEX |
+                EVENT_HEAP_INDEX |
+                USER_BASE_ACCOUNT_INDEX |
+                USER_QUOTE_ACCOUNT_INDEX
+            ) && *info.key != OPENBOOK_V2_PROGRAM_ID;
+
+            if is_writable {
+                AccountMeta::new(*info.key, info.is_signer)
+            } else {
+                AccountMeta::new_readonly(*info.key, info.is_signer)
+            }
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: OPENBOOK_V2_PROGRAM_ID,
+        accounts: account_metas,
+        data,
+    };
+
+    invoke(&instruction, &account_slice[..OPENBOOK_V2_SWAP_ACCOUNT_COUNT])
+        .map_err(|e| {
+            msg!("OpenBook v2 swap failed: {:?}", e);
+            error!(ErrorCode::DexExecutionFailed)
+        })?;
+
+    Ok(())
+}
+
+pub(crate) fn read_market_lot_sizes(market: &AccountInfo) -> Result<(u64, u64)> {
+    let data = market
+        .try_borrow_data()
+        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
+    if data.len() < OPENBOOK_REQUIRED_HEADER_LEN {
+        msg!("OpenBook v2: market header too short");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let read_i64 = |offset: usize| -> Result<i64> {
+        Ok(i64::from_le_bytes(
+            data[offset..offset + 8]
+                .try_into()
+                .map_err(|_| error!(ErrorCode::DexExecutionFailed))?,
+        ))
+    };
+    let quote_lot_size = read_i64(OPENBOOK_QUOTE_LOT_SIZE_OFFSET)?;
+    let base_lot_size = read_i64(OPENBOOK_BASE_LOT_SIZE_OFFSET)?;
+    if base_lot_size <= 0 || quote_lot_size <= 0 {
+        msg!("OpenBook v2: invalid lot sizes");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    Ok((base_lot_size as u64, quote_lot_size as u64))
+}
+
+/// Nombre de lots entiers contenus dans `amount` atoms
+fn lots(amount: u64, lot_size: u64) -> Result<i64> {
+    i64::try_from(amount / lot_size).map_err(|_| error!(ErrorCode::DexExecutionFailed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lot_conversion_rounds_down() {
+        assert_eq!(lots(1_999_999, 1_000_000).unwrap(), 1);
+        assert_eq!(lots(999, 1_000).unwrap(), 0);
+        assert_eq!(lots(u64::MAX, 1).unwrap_err(), error!(ErrorCode::DexExecutionFailed));
+    }
+
+    #[test]
+    fn test_read_market_lot_sizes() {
+        let mut data = vec![0u8; OPENBOOK_REQUIRED_HEADER_LEN];
+        data[OPENBOOK_QUOTE_LOT_SIZE_OFFSET..][..8].copy_from_slice(&10i64.to_le_bytes());
+        data[OPENBOOK_BASE_LOT_SIZE_OFFSET..][..8].copy_from_slice(&1_000i64.to_le_bytes());
+        let (key, owner, mut lamports) = (Pubkey::new_unique(), OPENBOOK_V2_PROGRAM_ID, 0);
+        let market = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert_eq!(read_market_lot_sizes(&market).unwrap(), (1_000, 10));
+    }
+}
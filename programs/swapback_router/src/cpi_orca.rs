@@ -1,11 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::solana_program::program_pack::Pack;
-use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, ORCA_WHIRLPOOL_PROGRAM_ID};
 
+/// Account indices for Whirlpool swap
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 0;
+pub(crate) const WHIRLPOOL_INDEX: usize = 2;
+pub(crate) const TOKEN_OWNER_A_INDEX: usize = 3;
+pub(crate) const TOKEN_VAULT_A_INDEX: usize = 4;
+pub(crate) const TOKEN_OWNER_B_INDEX: usize = 5;
+pub(crate) const TOKEN_VAULT_B_INDEX: usize = 6;
+pub(crate) const TICK_ARRAY_0_INDEX: usize = 7;
+pub(crate) const TICK_ARRAY_2_INDEX: usize = 9;
+
 pub const ORCA_SWAP_ACCOUNT_COUNT: usize = 11;
 const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 const MIN_SQRT_PRICE: u128 = 4_295_048_016; // Aligns with Whirlpool min price + 1
@@ -16,15 +24,35 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
+    swap_with_mode(ctx, account_slice, amount_in, min_out, true)
+}
+
+/// Exact-output: `amount_out` exact, au plus `max_in` prélevé (amount_specified_is_input = false)
+pub fn swap_exact_out(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_out: u64,
+    max_in: u64,
+) -> Result<()> {
+    swap_with_mode(ctx, account_slice, amount_out, max_in, false)
+}
+
+fn swap_with_mode(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount: u64,
+    other_amount_threshold: u64,
+    amount_specified_is_input: bool,
+) -> Result<()> {
     let user_token_a = ctx.accounts.user_token_account_a.key();
     let user_token_b = ctx.accounts.user_token_account_b.key();
     if account_slice.len() < ORCA_SWAP_ACCOUNT_COUNT {
         return err!(ErrorCode::DexExecutionFailed);
     }
 
-    let token_owner_a = &account_slice[3];
-    let token_owner_b = &account_slice[5];
+    let token_owner_a = &account_slice[TOKEN_OWNER_A_INDEX];
+    let token_owner_b = &account_slice[TOKEN_OWNER_B_INDEX];
 
     // IMPORTANT:
     // - `token_owner_a/b` sont les comptes utilisateur pour les tokens A/B du pool Orca.
@@ -47,8 +75,6 @@ pub fn swap(
         return err!(ErrorCode::DexExecutionFailed);
     }
 
-    let pre_amount = read_token_amount(destination_account)?;
-
     let sqrt_price_limit = if a_to_b {
         MIN_SQRT_PRICE
     } else {
@@ -57,10 +83,10 @@ pub fn swap(
 
     let mut data = Vec::with_capacity(8 + 8 + 8 + 16 + 2);
     data.extend_from_slice(&SWAP_DISCRIMINATOR);
-    data.extend_from_slice(&amount_in.to_le_bytes());
-    data.extend_from_slice(&min_out.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&other_amount_threshold.to_le_bytes());
     data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
-    data.push(1u8); // amount_specified_is_input
+    data.push(amount_specified_is_input as u8);
     data.push(if a_to_b { 1u8 } else { 0u8 });
 
     let account_metas: Vec<AccountMeta> = account_slice
@@ -84,23 +110,6 @@ pub fn swap(
 
     invoke(&instruction, &accounts).map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
 
-    let post_amount = read_token_amount(destination_account)?;
-    let amount_out = post_amount
-        .checked_sub(pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    Ok(amount_out)
+    Ok(())
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account =
-        SplAccount::unpack(&data).map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
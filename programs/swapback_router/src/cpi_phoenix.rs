@@ -1,8 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::solana_program::program_pack::Pack;
-use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, PHOENIX_PROGRAM_ID};
 
@@ -26,15 +24,15 @@ const SELF_TRADE_DECREMENT_TAKE: u8 = 2;
 const PHOENIX_DEFAULT_MATCH_LIMIT: u64 = 2048;
 
 /// Account indices for Phoenix swap
-const PHOENIX_PROGRAM_INDEX: usize = 0;
-const LOG_AUTHORITY_INDEX: usize = 1;
-const MARKET_INDEX: usize = 2;
-const TRADER_INDEX: usize = 3;
-const BASE_ACCOUNT_INDEX: usize = 4;
-const QUOTE_ACCOUNT_INDEX: usize = 5;
-const BASE_VAULT_INDEX: usize = 6;
-const QUOTE_VAULT_INDEX: usize = 7;
-const TOKEN_PROGRAM_INDEX: usize = 8;
+pub(crate) const PHOENIX_PROGRAM_INDEX: usize = 0;
+pub(crate) const LOG_AUTHORITY_INDEX: usize = 1;
+pub(crate) const MARKET_INDEX: usize = 2;
+pub(crate) const TRADER_INDEX: usize = 3;
+pub(crate) const BASE_ACCOUNT_INDEX: usize = 4;
+pub(crate) const QUOTE_ACCOUNT_INDEX: usize = 5;
+pub(crate) const BASE_VAULT_INDEX: usize = 6;
+pub(crate) const QUOTE_VAULT_INDEX: usize = 7;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 8;
 
 /// Minimum accounts for Phoenix swap (matches phoenix-sdk createSwapInstruction keys)
 pub const PHOENIX_SWAP_ACCOUNT_COUNT: usize = 9;
@@ -65,7 +63,80 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
+    let side = resolve_side(ctx, account_slice)?;
+
+    // Parse lot sizes from market header to convert raw SPL amounts to Phoenix lots.
+    let (base_lot_size, quote_lot_size) = read_market_lot_sizes(&account_slice[MARKET_INDEX])?;
+    if base_lot_size == 0 || quote_lot_size == 0 {
+        msg!("Phoenix: invalid lot sizes");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let (num_base_lots, num_quote_lots, min_base_lots_to_fill, min_quote_lots_to_fill) = match side {
+        PhoenixSide::Ask => {
+            // Sell base for quote: in = base atoms, out = quote atoms
+            let in_base_lots = amount_in.checked_div(base_lot_size).unwrap_or(0);
+            let min_quote_lots = div_ceil(min_out, quote_lot_size)?;
+            (in_base_lots, 0u64, 0u64, min_quote_lots)
+        }
+        PhoenixSide::Bid => {
+            // Buy base with quote: in = quote atoms, out = base atoms
+            let in_quote_lots = amount_in.checked_div(quote_lot_size).unwrap_or(0);
+            let min_base_lots = div_ceil(min_out, base_lot_size)?;
+            (0u64, in_quote_lots, min_base_lots, 0u64)
+        }
+    };
+
+    if num_base_lots == 0 && num_quote_lots == 0 {
+        msg!("Phoenix: amount_in too small for lot size");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    invoke_ioc(
+        account_slice,
+        side,
+        num_base_lots,
+        num_quote_lots,
+        min_base_lots_to_fill,
+        min_quote_lots_to_fill,
+    )
+}
+
+/// Exact-output swap: buys exactly `amount_out` base atoms (rounded up to whole base lots)
+///
+/// Only the Bid direction (output = base) is native. The quote budget `max_in` is passed
+/// to Phoenix as the order's quote lots (taker fee included): the IOC fails rather than
+/// spend more, since every base lot must fill.
+pub fn swap_exact_out(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_out: u64,
+    max_in: u64,
+) -> Result<()> {
+    let side = resolve_side(ctx, account_slice)?;
+    if !matches!(side, PhoenixSide::Bid) {
+        msg!("Phoenix: exact-out requires base as output");
+        return err!(ErrorCode::ExactOutNotSupported);
+    }
+
+    let (base_lot_size, quote_lot_size) = read_market_lot_sizes(&account_slice[MARKET_INDEX])?;
+    if base_lot_size == 0 || quote_lot_size == 0 {
+        msg!("Phoenix: invalid lot sizes");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+    let base_lots = div_ceil(amount_out, base_lot_size)?;
+    let max_quote_lots = max_in / quote_lot_size;
+    if max_quote_lots == 0 {
+        msg!("Phoenix: max_in too small for lot size");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    invoke_ioc(account_slice, side, base_lots, max_quote_lots, base_lots, 0)
+}
+
+/// Checks the Phoenix accounts and derives the side from the router's input/output accounts
+fn resolve_side(ctx: &Context<SwapToC>, account_slice: &[AccountInfo]) -> Result<PhoenixSide> {
     if account_slice.len() < PHOENIX_SWAP_ACCOUNT_COUNT {
         msg!("Phoenix: insufficient accounts, need {} got {}", 
              PHOENIX_SWAP_ACCOUNT_COUNT, account_slice.len());
@@ -109,47 +180,25 @@ pub fn swap(
     // Phoenix Swap expects baseAccount and quoteAccount (ATAs for market base/quote mints).
     // Bid: input is quote, output is base
     // Ask: input is base, output is quote
-    let (side, _source_account, destination_account) = if quote_account.key() == user_token_a_key
-        && base_account.key() == user_token_b_key
-    {
-        (PhoenixSide::Bid, quote_account, base_account)
+    if quote_account.key() == user_token_a_key && base_account.key() == user_token_b_key {
+        Ok(PhoenixSide::Bid)
     } else if base_account.key() == user_token_a_key && quote_account.key() == user_token_b_key {
-        (PhoenixSide::Ask, base_account, quote_account)
+        Ok(PhoenixSide::Ask)
     } else {
         msg!("Phoenix: token account mismatch");
-        return err!(ErrorCode::DexExecutionFailed);
-    };
-
-    // Parse lot sizes from market header to convert raw SPL amounts to Phoenix lots.
-    let (base_lot_size, quote_lot_size) = read_market_lot_sizes(&account_slice[MARKET_INDEX])?;
-    if base_lot_size == 0 || quote_lot_size == 0 {
-        msg!("Phoenix: invalid lot sizes");
-        return err!(ErrorCode::DexExecutionFailed);
+        err!(ErrorCode::DexExecutionFailed)
     }
+}
 
-    let (num_base_lots, num_quote_lots, min_base_lots_to_fill, min_quote_lots_to_fill) = match side {
-        PhoenixSide::Ask => {
-            // Sell base for quote: in = base atoms, out = quote atoms
-            let in_base_lots = amount_in.checked_div(base_lot_size).unwrap_or(0);
-            let min_quote_lots = div_ceil(min_out, quote_lot_size)?;
-            (in_base_lots, 0u64, 0u64, min_quote_lots)
-        }
-        PhoenixSide::Bid => {
-            // Buy base with quote: in = quote atoms, out = base atoms
-            let in_quote_lots = amount_in.checked_div(quote_lot_size).unwrap_or(0);
-            let min_base_lots = div_ceil(min_out, base_lot_size)?;
-            (0u64, in_quote_lots, min_base_lots, 0u64)
-        }
-    };
-
-    if num_base_lots == 0 && num_quote_lots == 0 {
-        msg!("Phoenix: amount_in too small for lot size");
-        return err!(ErrorCode::DexExecutionFailed);
-    }
-
-    // Read pre-swap balance
-    let pre_amount = read_token_amount(destination_account)?;
-
+/// Swap instruction with an ImmediateOrCancel order packet
+fn invoke_ioc(
+    account_slice: &[AccountInfo],
+    side: PhoenixSide,
+    num_base_lots: u64,
+    num_quote_lots: u64,
+    min_base_lots_to_fill: u64,
+    min_quote_lots_to_fill: u64,
+) -> Result<()> {
     // Build Phoenix Swap instruction args:
     // [u8 discriminator][OrderPacket enum]
     // OrderPacket::ImmediateOrCancel layout matches phoenix-sdk beet serialization.
@@ -212,19 +261,7 @@ pub fn swap(
             error!(ErrorCode::DexExecutionFailed)
         })?;
 
-    // Verify output
-    let post_amount = read_token_amount(destination_account)?;
-    let amount_out = post_amount
-        .checked_sub(pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        msg!("Phoenix: slippage exceeded, got {} expected min {}", amount_out, min_out);
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    msg!("Phoenix swap success: {} in -> {} out", amount_in, amount_out);
-    Ok(amount_out)
+    Ok(())
 }
 
 fn read_market_lot_sizes(market: &AccountInfo) -> Result<(u64, u64)> {
@@ -270,11 +307,3 @@ fn write_coption_u64(buf: &mut Vec<u8>, value: Option<u64>) {
     }
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account = SplAccount::unpack(&data)
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
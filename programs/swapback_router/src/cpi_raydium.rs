@@ -6,23 +6,23 @@ use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, RAYDIUM_AMM_PROGRAM_ID};
 
-const TOKEN_PROGRAM_INDEX: usize = 0;
-const AMM_POOL_INDEX: usize = 1;
-const AMM_AUTHORITY_INDEX: usize = 2;
-const AMM_OPEN_ORDERS_INDEX: usize = 3;
-const AMM_COIN_VAULT_INDEX: usize = 4;
-const AMM_PC_VAULT_INDEX: usize = 5;
-const MARKET_PROGRAM_INDEX: usize = 6;
-const MARKET_INDEX: usize = 7;
-const MARKET_BIDS_INDEX: usize = 8;
-const MARKET_ASKS_INDEX: usize = 9;
-const MARKET_EVENT_QUEUE_INDEX: usize = 10;
-const MARKET_COIN_VAULT_INDEX: usize = 11;
-const MARKET_PC_VAULT_INDEX: usize = 12;
-const MARKET_VAULT_SIGNER_INDEX: usize = 13;
-const USER_SOURCE_INDEX: usize = 14;
-const USER_DESTINATION_INDEX: usize = 15;
-const USER_OWNER_INDEX: usize = 16;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 0;
+pub(crate) const AMM_POOL_INDEX: usize = 1;
+pub(crate) const AMM_AUTHORITY_INDEX: usize = 2;
+pub(crate) const AMM_OPEN_ORDERS_INDEX: usize = 3;
+pub(crate) const AMM_COIN_VAULT_INDEX: usize = 4;
+pub(crate) const AMM_PC_VAULT_INDEX: usize = 5;
+pub(crate) const MARKET_PROGRAM_INDEX: usize = 6;
+pub(crate) const MARKET_INDEX: usize = 7;
+pub(crate) const MARKET_BIDS_INDEX: usize = 8;
+pub(crate) const MARKET_ASKS_INDEX: usize = 9;
+pub(crate) const MARKET_EVENT_QUEUE_INDEX: usize = 10;
+pub(crate) const MARKET_COIN_VAULT_INDEX: usize = 11;
+pub(crate) const MARKET_PC_VAULT_INDEX: usize = 12;
+pub(crate) const MARKET_VAULT_SIGNER_INDEX: usize = 13;
+pub(crate) const USER_SOURCE_INDEX: usize = 14;
+pub(crate) const USER_DESTINATION_INDEX: usize = 15;
+pub(crate) const USER_OWNER_INDEX: usize = 16;
 
 pub const RAYDIUM_SWAP_ACCOUNT_COUNT: usize = USER_OWNER_INDEX + 1;
 
@@ -31,7 +31,7 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
     if account_slice.len() < RAYDIUM_SWAP_ACCOUNT_COUNT {
         return err!(ErrorCode::DexExecutionFailed);
     }
@@ -66,8 +66,6 @@ pub fn swap(
     }
 
     let source_mint = read_token_mint(user_source)?;
-    let destination_pre_amount = read_token_amount(user_destination)?;
-
     let coin_vault_mint = read_token_mint(&account_slice[AMM_COIN_VAULT_INDEX])?;
     let pc_vault_mint = read_token_mint(&account_slice[AMM_PC_VAULT_INDEX])?;
 
@@ -83,16 +81,7 @@ pub fn swap(
 
     invoke(&instruction, account_slice).map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
 
-    let post_amount = read_token_amount(user_destination)?;
-    let amount_out = post_amount
-        .checked_sub(destination_pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    Ok(amount_out)
+    Ok(())
 }
 
 fn read_token_account(account: &AccountInfo) -> Result<SplAccount> {
@@ -104,9 +93,6 @@ fn read_token_account(account: &AccountInfo) -> Result<SplAccount> {
     Ok(account_data)
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    Ok(read_token_account(account)?.amount)
-}
 
 fn read_token_mint(account: &AccountInfo) -> Result<Pubkey> {
     Ok(read_token_account(account)?.mint)
@@ -1,8 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::solana_program::program_pack::Pack;
-use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, RAYDIUM_CLMM_PROGRAM_ID};
 
@@ -10,18 +8,18 @@ use crate::{ErrorCode, SwapToC, RAYDIUM_CLMM_PROGRAM_ID};
 const SWAP_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
 
 /// Account indices for Raydium CLMM swap
-const PAYER_INDEX: usize = 0;
-const AMM_CONFIG_INDEX: usize = 1;
-const POOL_STATE_INDEX: usize = 2;
-const INPUT_TOKEN_ACCOUNT_INDEX: usize = 3;
-const OUTPUT_TOKEN_ACCOUNT_INDEX: usize = 4;
-const INPUT_VAULT_INDEX: usize = 5;
-const OUTPUT_VAULT_INDEX: usize = 6;
-const OBSERVATION_STATE_INDEX: usize = 7;
-const TOKEN_PROGRAM_INDEX: usize = 8;
-const TICK_ARRAY_0_INDEX: usize = 9;
-const TICK_ARRAY_1_INDEX: usize = 10;
-const TICK_ARRAY_2_INDEX: usize = 11;
+pub(crate) const PAYER_INDEX: usize = 0;
+pub(crate) const AMM_CONFIG_INDEX: usize = 1;
+pub(crate) const POOL_STATE_INDEX: usize = 2;
+pub(crate) const INPUT_TOKEN_ACCOUNT_INDEX: usize = 3;
+pub(crate) const OUTPUT_TOKEN_ACCOUNT_INDEX: usize = 4;
+pub(crate) const INPUT_VAULT_INDEX: usize = 5;
+pub(crate) const OUTPUT_VAULT_INDEX: usize = 6;
+pub(crate) const OBSERVATION_STATE_INDEX: usize = 7;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 8;
+pub(crate) const TICK_ARRAY_0_INDEX: usize = 9;
+pub(crate) const TICK_ARRAY_1_INDEX: usize = 10;
+pub(crate) const TICK_ARRAY_2_INDEX: usize = 11;
 
 /// Minimum accounts for Raydium CLMM swap (without extra tick arrays)
 pub const RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT: usize = 12;
@@ -39,7 +37,27 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
+    swap_with_mode(ctx, account_slice, amount_in, min_out, true)
+}
+
+/// Exact-output swap (`is_base_input = false`): `amount_out` exact, at most `max_in` spent
+pub fn swap_exact_out(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_out: u64,
+    max_in: u64,
+) -> Result<()> {
+    swap_with_mode(ctx, account_slice, amount_out, max_in, false)
+}
+
+fn swap_with_mode(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount: u64,
+    other_amount_threshold: u64,
+    is_base_input: bool,
+) -> Result<()> {
     if account_slice.len() < RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT {
         msg!("Raydium CLMM: insufficient accounts, need {} got {}", 
              RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT, account_slice.len());
@@ -65,19 +83,16 @@ pub fn swap(
     let user_token_b_key = ctx.accounts.user_token_account_b.key();
 
     // Determine swap direction (a_to_b or b_to_a)
-    let (zero_for_one, destination_account) = 
+    let zero_for_one = 
         if input_token_account.key() == user_token_a_key && output_token_account.key() == user_token_b_key {
-            (true, output_token_account)
+            true
         } else if input_token_account.key() == user_token_b_key && output_token_account.key() == user_token_a_key {
-            (false, output_token_account)
+            false
         } else {
             msg!("Raydium CLMM: token account mismatch");
             return err!(ErrorCode::DexExecutionFailed);
         };
 
-    // Read pre-swap balance
-    let pre_amount = read_token_amount(destination_account)?;
-
     // Calculate sqrt price limit based on direction
     let sqrt_price_limit_x64 = if zero_for_one {
         MIN_SQRT_PRICE_X64
@@ -87,16 +102,16 @@ pub fn swap(
 
     // Build swap instruction data
     // Raydium CLMM swap params:
-    // - amount: u64 (input amount)
-    // - other_amount_threshold: u64 (min output)
+    // - amount: u64 (input amount, or output amount when !is_base_input)
+    // - other_amount_threshold: u64 (min output, or max input when !is_base_input)
     // - sqrt_price_limit_x64: u128
     // - is_base_input: bool
     let mut data = Vec::with_capacity(8 + 8 + 8 + 16 + 1);
     data.extend_from_slice(&SWAP_DISCRIMINATOR);
-    data.extend_from_slice(&amount_in.to_le_bytes());
-    data.extend_from_slice(&min_out.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&other_amount_threshold.to_le_bytes());
     data.extend_from_slice(&sqrt_price_limit_x64.to_le_bytes());
-    data.push(1u8); // is_base_input = true (we specify input amount)
+    data.push(is_base_input as u8);
 
     // Build account metas
     let account_metas: Vec<AccountMeta> = account_slice
@@ -136,26 +151,6 @@ pub fn swap(
             error!(ErrorCode::DexExecutionFailed)
         })?;
 
-    // Verify output
-    let post_amount = read_token_amount(destination_account)?;
-    let amount_out = post_amount
-        .checked_sub(pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        msg!("Raydium CLMM: slippage exceeded, got {} expected min {}", amount_out, min_out);
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    msg!("Raydium CLMM swap success: {} in -> {} out", amount_in, amount_out);
-    Ok(amount_out)
+    Ok(())
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account = SplAccount::unpack(&data)
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+use crate::{ErrorCode, SwapToC, RAYDIUM_CPMM_PROGRAM_ID};
+
+/// Raydium CPMM `swap_base_input` discriminator
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+/// Account indices for Raydium CPMM swap_base_input
+pub(crate) const PAYER_INDEX: usize = 0;
+pub(crate) const AUTHORITY_INDEX: usize = 1;
+pub(crate) const AMM_CONFIG_INDEX: usize = 2;
+pub(crate) const POOL_STATE_INDEX: usize = 3;
+pub(crate) const INPUT_TOKEN_ACCOUNT_INDEX: usize = 4;
+pub(crate) const OUTPUT_TOKEN_ACCOUNT_INDEX: usize = 5;
+pub(crate) const INPUT_VAULT_INDEX: usize = 6;
+pub(crate) const OUTPUT_VAULT_INDEX: usize = 7;
+pub(crate) const INPUT_TOKEN_PROGRAM_INDEX: usize = 8;
+pub(crate) const OUTPUT_TOKEN_PROGRAM_INDEX: usize = 9;
+pub(crate) const INPUT_TOKEN_MINT_INDEX: usize = 10;
+pub(crate) const OUTPUT_TOKEN_MINT_INDEX: usize = 11;
+pub(crate) const OBSERVATION_STATE_INDEX: usize = 12;
+
+/// Accounts for Raydium CPMM swap_base_input
+pub const RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT: usize = 13;
+
+/// Execute a swap through Raydium CPMM (constant product, Token-2022 aware)
+///
+/// CPMM is Raydium's Anchor-based x*y=k program without OpenBook market
+/// accounts; most newly launched pairs (incl. Token-2022 mints) live there.
+pub fn swap(
+    ctx: &Context<SwapToC>,
+    account_slice: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> Result<()> {
+    if account_slice.len() < RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT {
+        msg!("Raydium CPMM: insufficient accounts, need {} got {}",
+             RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT, account_slice.len());
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    let payer = &account_slice[PAYER_INDEX];
+    if !payer.is_signer {
+        msg!("Raydium CPMM: payer must be signer");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    if payer.key() != ctx.accounts.user.key() {
+        msg!("Raydium CPMM: payer mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    // swap_base_input: on paie toujours depuis le compte A vers le compte B
+    if account_slice[INPUT_TOKEN_ACCOUNT_INDEX].key() != ctx.accounts.user_token_account_a.key()
+        || account_slice[OUTPUT_TOKEN_ACCOUNT_INDEX].key() != ctx.accounts.user_token_account_b.key()
+    {
+        msg!("Raydium CPMM: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
+
+    // Build swap instruction data
+    // Raydium CPMM swap_base_input params:
+    // - amount_in: u64
+    // - minimum_amount_out: u64
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&SWAP_BASE_INPUT_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+
+    let account_metas: Vec<AccountMeta> = account_slice[..RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT]
+        .iter()
+        .enumerate()
+        .map(|(i, info)| {
+            // Writable accounts: pool_state, token accounts, vaults, observation
+            let is_writable = matches!(i,
+                POOL_STATE_INDEX |
+                INPUT_TOKEN_ACCOUNT_INDEX |
+                OUTPUT_TOKEN_ACCOUNT_INDEX |
+                INPUT_VAULT_INDEX |
+                OUTPUT_VAULT_INDEX |
+                OBSERVATION_STATE_INDEX
+            );
+
+            if is_writable {
+                AccountMeta::new(*info.key, info.is_signer)
+            } else {
+                AccountMeta::new_readonly(*info.key, info.is_signer)
+            }
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM_ID,
+        accounts: account_metas,
+        data,
+    };
+
+    invoke(&instruction, &account_slice[..RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT])
+        .map_err(|e| {
+            msg!("Raydium CPMM swap failed: {:?}", e);
+            error!(ErrorCode::DexExecutionFailed)
+        })?;
+
+    Ok(())
+}
@@ -1,8 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
-use anchor_lang::solana_program::program_pack::Pack;
-use anchor_spl::token::spl_token::state::Account as SplAccount;
 
 use crate::{ErrorCode, SwapToC, SABER_PROGRAM_ID};
 
@@ -10,16 +8,16 @@ use crate::{ErrorCode, SwapToC, SABER_PROGRAM_ID};
 const SWAP_DISCRIMINATOR: u8 = 1; // Saber uses simple u8 discriminator
 
 /// Account indices for Saber swap
-const SWAP_INFO_INDEX: usize = 0;
-const SWAP_AUTHORITY_INDEX: usize = 1;
-const USER_AUTHORITY_INDEX: usize = 2;
-const SOURCE_ACCOUNT_INDEX: usize = 3;
-const SWAP_SOURCE_INDEX: usize = 4;
-const SWAP_DESTINATION_INDEX: usize = 5;
-const DESTINATION_ACCOUNT_INDEX: usize = 6;
-const ADMIN_FEE_DESTINATION_INDEX: usize = 7;
-const TOKEN_PROGRAM_INDEX: usize = 8;
-const CLOCK_INDEX: usize = 9;
+pub(crate) const SWAP_INFO_INDEX: usize = 0;
+pub(crate) const SWAP_AUTHORITY_INDEX: usize = 1;
+pub(crate) const USER_AUTHORITY_INDEX: usize = 2;
+pub(crate) const SOURCE_ACCOUNT_INDEX: usize = 3;
+pub(crate) const SWAP_SOURCE_INDEX: usize = 4;
+pub(crate) const SWAP_DESTINATION_INDEX: usize = 5;
+pub(crate) const DESTINATION_ACCOUNT_INDEX: usize = 6;
+pub(crate) const ADMIN_FEE_DESTINATION_INDEX: usize = 7;
+pub(crate) const TOKEN_PROGRAM_INDEX: usize = 8;
+pub(crate) const CLOCK_INDEX: usize = 9;
 
 /// Minimum accounts for Saber swap
 pub const SABER_SWAP_ACCOUNT_COUNT: usize = 10;
@@ -41,7 +39,7 @@ pub fn swap(
     account_slice: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
-) -> Result<u64> {
+) -> Result<()> {
     if account_slice.len() < SABER_SWAP_ACCOUNT_COUNT {
         msg!("Saber: insufficient accounts, need {} got {}", 
              SABER_SWAP_ACCOUNT_COUNT, account_slice.len());
@@ -66,20 +64,13 @@ pub fn swap(
     let user_token_a_key = ctx.accounts.user_token_account_a.key();
     let user_token_b_key = ctx.accounts.user_token_account_b.key();
 
-    let dest_account = 
-        if source_account.key() == user_token_a_key && 
-           destination_account.key() == user_token_b_key {
-            destination_account
-        } else if source_account.key() == user_token_b_key && 
-                  destination_account.key() == user_token_a_key {
-            destination_account
-        } else {
-            msg!("Saber: token account mismatch");
-            return err!(ErrorCode::DexExecutionFailed);
-        };
-
-    // Read pre-swap balance
-    let pre_amount = read_token_amount(dest_account)?;
+    let pairing = (source_account.key(), destination_account.key());
+    if pairing != (user_token_a_key, user_token_b_key)
+        && pairing != (user_token_b_key, user_token_a_key)
+    {
+        msg!("Saber: token account mismatch");
+        return err!(ErrorCode::DexExecutionFailed);
+    }
 
     // Build Saber swap instruction
     // Saber swap uses: discriminator (1 byte) + amount_in (8 bytes) + min_out (8 bytes)
@@ -114,19 +105,7 @@ pub fn swap(
             error!(ErrorCode::DexExecutionFailed)
         })?;
 
-    // Verify output
-    let post_amount = read_token_amount(dest_account)?;
-    let amount_out = post_amount
-        .checked_sub(pre_amount)
-        .ok_or_else(|| error!(ErrorCode::DexExecutionFailed))?;
-
-    if amount_out < min_out {
-        msg!("Saber: slippage exceeded, got {} expected min {}", amount_out, min_out);
-        return err!(ErrorCode::SlippageExceeded);
-    }
-
-    msg!("Saber stableswap success: {} in -> {} out", amount_in, amount_out);
-    Ok(amount_out)
+    Ok(())
 }
 
 /// Check if pair is a stable pair (both are stablecoins)
@@ -139,11 +118,3 @@ pub fn is_stable_pair(mint_a: &Pubkey, mint_b: &Pubkey) -> bool {
     stables.iter().any(|&s| s == mint_b_str)
 }
 
-fn read_token_amount(account: &AccountInfo) -> Result<u64> {
-    let data = account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    let token_account = SplAccount::unpack(&data)
-        .map_err(|_| error!(ErrorCode::DexExecutionFailed))?;
-    Ok(token_account.amount)
-}
@@ -1,14 +1,22 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, TokenInterface};
+
+mod balance_delta;
 
 // CPI modules for DEX integrations
 mod cpi_jupiter;
 mod cpi_lifinity;
 mod cpi_meteora;
+mod cpi_meteora_damm;
+mod cpi_openbook_v2;
 mod cpi_orca;
 mod cpi_phoenix;
 mod cpi_raydium;
 mod cpi_raydium_clmm;
+mod cpi_raydium_cpmm;
 mod cpi_saber;
 mod cpi_sanctum;
 
@@ -16,17 +24,25 @@ pub mod error;
 pub mod instructions;
 #[macro_use]
 pub mod logging;
+pub mod lst_pricing;
 pub mod math;
+mod mev_guard;
+mod native_sol;
 mod oracle_v2;  // Must be before oracle (oracle depends on oracle_v2)
 mod oracle;
 pub mod oracle_cache;
+mod phoenix_maker;
+mod pool_validation;
+mod quote;
+mod rfq;
 pub mod routing;
 pub mod slippage;
 pub mod state;
+mod token_extensions;
 pub mod venue_scoring;
 
 // Re-export Pyth V2 feed IDs for external use
-pub use oracle_v2::feed_ids;
+pub use oracle_v2::{feed_ids, PythUpdatePolicy};
 
 // Custom getrandom stub for Solana BPF target
 #[cfg(target_os = "solana")]
@@ -38,11 +54,15 @@ mod proptest_fuzz;
 
 // Re-export for external use
 pub use error::SwapbackError;
-pub use state::{DcaPlan, RouterConfig, RouterState, UserRebate};
+pub use state::{
+    DcaPlan, PhoenixMakerOrder, RfqMakers, RouterConfig, RouterState, TokenAllowlist, UserRebate,
+    VenueEntry, VenueRegistry,
+};
 
 // Internal use
 use routing::{
-    adjust_venue_weights_with_scores, parse_venue_scores_by_pubkey, MIN_QUALITY_SCORE_DEFAULT,
+    adjust_venue_weights_with_scores, is_clob_venue, parse_venue_scores_by_pubkey,
+    pubkey_to_venue_type, MIN_QUALITY_SCORE_DEFAULT,
 };
 
 // Program ID - Deployed on mainnet-beta (Dec 8, 2025)
@@ -55,11 +75,15 @@ declare_id!("APHj6L2b2bA2q62jwYZp38dqbTxQUqwatqdUum1trPnN");
 // AMM DEXs
 pub const RAYDIUM_AMM_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
 pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
 pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
 pub const METEORA_DLMM_PROGRAM_ID: Pubkey = pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+pub const METEORA_DAMM_PROGRAM_ID: Pubkey = pubkey!("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB");
+pub const METEORA_DAMM_V2_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
 
 // CLOB DEXs
 pub const PHOENIX_PROGRAM_ID: Pubkey = pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+pub const OPENBOOK_V2_PROGRAM_ID: Pubkey = pubkey!("opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb");
 
 // Oracle-based AMMs
 pub const LIFINITY_PROGRAM_ID: Pubkey = pubkey!("EewxydAPCCVuNEyrVN68PuSYdQ7wKn27V9Gjeoi8dy3S");
@@ -68,6 +92,9 @@ pub const LIFINITY_PROGRAM_ID: Pubkey = pubkey!("EewxydAPCCVuNEyrVN68PuSYdQ7wKn2
 pub const SANCTUM_PROGRAM_ID: Pubkey = pubkey!("5ocnV1qiCgaQR8Jb8xWnVbApfaygJ8tNoZfgPwsgx9kx");
 pub const SABER_PROGRAM_ID: Pubkey = pubkey!("SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ");
 
+// RFQ: devis signés par des market makers, réglés par le router lui-même
+pub const RFQ_VENUE_ID: Pubkey = ID;
+
 // Aggregator (fallback)
 pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 
@@ -77,6 +104,36 @@ pub const BUYBACK_PROGRAM_ID: Pubkey = pubkey!("7wCCwRXxWvMY2DJDRrnhFg3b8jVPb5vV
 // cNFT Program ID with unlock_tokens (verified Nov 14, 2025)
 pub const CNFT_PROGRAM_ID: Pubkey = pubkey!("26kzow1KF3AbrbFA7M3WxXVCtcMRgzMXkAKtVYDDt6Ru");
 
+/// CPI adapter compiled into the router for one venue
+pub struct VenueAdapter {
+    pub program_id: Pubkey,
+    /// Minimum CPI accounts the adapter consumes from remaining_accounts
+    pub min_accounts: usize,
+    pub swap: fn(&Context<SwapToC>, &[AccountInfo], u64, u64) -> Result<()>,
+}
+
+/// Code side of venue support: the only list of executable venues. Which of them
+/// the router may route to (enabled, caps, account count) is the VenueRegistry's call.
+/// Also seeded into the registry at initialization.
+pub const VENUE_ADAPTERS: [VenueAdapter; 12] = [
+    VenueAdapter { program_id: RAYDIUM_AMM_PROGRAM_ID, min_accounts: cpi_raydium::RAYDIUM_SWAP_ACCOUNT_COUNT, swap: cpi_raydium::swap },
+    VenueAdapter { program_id: ORCA_WHIRLPOOL_PROGRAM_ID, min_accounts: cpi_orca::ORCA_SWAP_ACCOUNT_COUNT, swap: cpi_orca::swap },
+    VenueAdapter { program_id: RAYDIUM_CLMM_PROGRAM_ID, min_accounts: cpi_raydium_clmm::RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT, swap: cpi_raydium_clmm::swap },
+    VenueAdapter { program_id: METEORA_DLMM_PROGRAM_ID, min_accounts: cpi_meteora::METEORA_SWAP_ACCOUNT_COUNT, swap: cpi_meteora::swap },
+    VenueAdapter { program_id: PHOENIX_PROGRAM_ID, min_accounts: cpi_phoenix::PHOENIX_SWAP_ACCOUNT_COUNT, swap: cpi_phoenix::swap },
+    VenueAdapter { program_id: LIFINITY_PROGRAM_ID, min_accounts: cpi_lifinity::LIFINITY_SWAP_ACCOUNT_COUNT, swap: cpi_lifinity::swap },
+    VenueAdapter { program_id: SANCTUM_PROGRAM_ID, min_accounts: cpi_sanctum::SANCTUM_SWAP_ACCOUNT_COUNT, swap: cpi_sanctum::swap },
+    VenueAdapter { program_id: SABER_PROGRAM_ID, min_accounts: cpi_saber::SABER_SWAP_ACCOUNT_COUNT, swap: cpi_saber::swap },
+    VenueAdapter { program_id: RAYDIUM_CPMM_PROGRAM_ID, min_accounts: cpi_raydium_cpmm::RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT, swap: cpi_raydium_cpmm::swap },
+    VenueAdapter { program_id: OPENBOOK_V2_PROGRAM_ID, min_accounts: cpi_openbook_v2::OPENBOOK_V2_SWAP_ACCOUNT_COUNT, swap: cpi_openbook_v2::swap },
+    VenueAdapter { program_id: METEORA_DAMM_PROGRAM_ID, min_accounts: cpi_meteora_damm::METEORA_DAMM_SWAP_ACCOUNT_COUNT, swap: cpi_meteora_damm::swap },
+    VenueAdapter { program_id: METEORA_DAMM_V2_PROGRAM_ID, min_accounts: cpi_meteora_damm::METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT, swap: cpi_meteora_damm::swap_v2 },
+];
+
+pub fn venue_adapter(program_id: &Pubkey) -> Option<&'static VenueAdapter> {
+    VENUE_ADAPTERS.iter().find(|adapter| adapter.program_id == *program_id)
+}
+
 // Jito MEV Protection (mainnet tip accounts)
 pub const JITO_TIP_PROGRAM_ID: Pubkey = pubkey!("T1pyyaTNZsKv2WcRAB8oVnk93mLJw2XzjtVYqCsaHqt");
 pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
@@ -100,6 +157,25 @@ pub const MAX_STALENESS_SECS: i64 = 300; // 5 minutes max staleness (default)
 pub const MIN_STALENESS_SECS: i64 = 10;  // 10 seconds minimum (security floor)
 pub const MAX_ORACLE_DIVERGENCE_BPS: u64 = 200; // 2% max divergence between feeds
 
+// Pyth pull update policy defaults (RouterConfig, mirrored into RouterState)
+pub const DEFAULT_PYTH_MIN_SIGNATURES: u8 = 5;  // Min Wormhole signatures for Partial updates
+pub const DEFAULT_PYTH_MAX_SLOT_LAG: u64 = 150; // ~60s between posted_slot and Clock::slot
+pub const MAX_PYTH_SLOT_LAG: u64 = 750;         // ~MAX_STALENESS_SECS in slots
+
+// Per-pair circuit breaker defaults (OracleCache)
+pub const DEFAULT_PAIR_MAX_MOVE_BPS: u16 = 1_000;      // 10% move within the window halts the pair
+pub const DEFAULT_PAIR_MOVE_WINDOW_SECS: i64 = 60;     // 1 minute measurement window
+pub const DEFAULT_PAIR_HALT_SECS: i64 = 900;           // 15 minutes halt
+pub const DEFAULT_PAIR_DIVERGENCE_STRIKES: u8 = 3;     // 3 consecutive divergent reads halt the pair
+pub const MAX_PAIR_HALT_SECS: i64 = 86_400;            // 24h max automatic halt
+
+// Per-venue circuit breaker defaults (VenueScore)
+pub const DEFAULT_VENUE_MAX_FAILURES: u8 = 3;          // 3 failures within the window quarantine the venue
+pub const DEFAULT_VENUE_FAILURE_WINDOW_SECS: i64 = 600; // 10 minutes counting window
+pub const DEFAULT_VENUE_QUARANTINE_SECS: i64 = 1_800;  // 30 minutes cool-down
+pub const DEFAULT_VENUE_MAX_ADVERSE_BPS: u16 = 300;    // Fill >3% worse than oracle counts as a failure
+pub const MAX_VENUE_QUARANTINE_SECS: i64 = 86_400;     // 24h max automatic quarantine
+
 // NPI (Routing Profit) allocation configuration (basis points, 10000 = 100%)
 // Total must equal 100% to avoid over-allocation
 pub const DEFAULT_REBATE_BPS: u16 = 7000; // 70% du NPI → Rebates utilisateurs
@@ -246,8 +322,17 @@ pub struct CancelDcaPlan<'info> {
     pub user: Signer<'info>,
 }
 
+/// Authority-only: the pair's oracles are bound once, here, and checked by every reader
 #[derive(Accounts)]
+#[instruction(token_a: Pubkey, token_b: Pubkey)]
 pub struct InitializeOracleCache<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -255,30 +340,327 @@ pub struct InitializeOracleCache<'info> {
         init,
         payer = authority,
         space = oracle_cache::OracleCache::LEN,
+        seeds = [b"oracle_cache", token_a.as_ref(), token_b.as_ref()],
+        bump
+    )]
+    pub oracle_cache: Account<'info, oracle_cache::OracleCache>,
+
+    /// CHECK: Primary oracle of the pair (e.g. Pyth feed), validated by the oracle readers
+    pub oracle: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless crank: reads the pair's oracles and feeds the circuit breaker
+#[derive(Accounts)]
+pub struct RefreshOracleCache<'info> {
+    #[account(seeds = [b"router_state"], bump = state.bump)]
+    pub state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"oracle_cache", oracle_cache.token_pair[0].as_ref(), oracle_cache.token_pair[1].as_ref()],
+        bump = oracle_cache.bump
+    )]
+    pub oracle_cache: Account<'info, oracle_cache::OracleCache>,
+
+    /// CHECK: Must be the cache's primary oracle (checked in the handler)
+    pub primary_oracle: AccountInfo<'info>,
+
+    /// CHECK: Must be the cache's fallback oracle when one is configured
+    pub fallback_oracle: Option<AccountInfo<'info>>,
+}
+
+/// Authority-only: move a pre-circuit-breaker OracleCache (keyed by its oracle)
+/// to the pair-keyed PDA and close the legacy account
+#[derive(Accounts)]
+#[instruction(token_a: Pubkey, token_b: Pubkey)]
+pub struct MigrateOracleCache<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    /// CHECK: Legacy OracleCache, layout and discriminator checked in the handler
+    #[account(
+        mut,
         seeds = [b"oracle_cache", oracle.key().as_ref()],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidOraclePrice
+    )]
+    pub legacy_oracle_cache: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = oracle_cache::OracleCache::LEN,
+        seeds = [b"oracle_cache", token_a.as_ref(), token_b.as_ref()],
         bump
     )]
     pub oracle_cache: Account<'info, oracle_cache::OracleCache>,
 
-    /// CHECK: The oracle account to cache (e.g. Pyth feed)
+    /// CHECK: Oracle the legacy cache is keyed on, kept as the pair's primary oracle
     pub oracle: AccountInfo<'info>,
 
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Authority-only circuit breaker management for one venue score
 #[derive(Accounts)]
-pub struct InitializeVenueScore<'info> {
+pub struct ManageVenueCircuitBreaker<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"venue_score", venue_score.venue.as_ref(), venue_score.pool.as_ref()],
+        bump = venue_score.bump
+    )]
+    pub venue_score: Account<'info, venue_scoring::VenueScore>,
+
+    #[account(
+        mut,
+        seeds = [b"venue_registry"],
+        bump = venue_registry.bump
+    )]
+    pub venue_registry: Account<'info, VenueRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Plan owner: grow a SwapPlan created before `max_slot`
+#[derive(Accounts)]
+pub struct MigrateSwapPlan<'info> {
+    /// CHECK: Legacy SwapPlan, layout and discriminator checked in the handler
+    #[account(
+        mut,
+        seeds = [b"swap_plan", user.key().as_ref()],
+        bump,
+        owner = crate::ID @ ErrorCode::UnauthorizedPlanAccess
+    )]
+    pub plan: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only: grow a RouterState created before the Pyth update policy
+#[derive(Accounts)]
+pub struct MigrateRouterState<'info> {
+    /// CHECK: Legacy RouterState, layout, discriminator and authority checked in the handler
+    #[account(
+        mut,
+        seeds = [b"router_state"],
+        bump,
+        owner = crate::ID @ ErrorCode::Unauthorized
+    )]
+    pub state: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only: grow a RouterConfig created before the Pyth update policy
+#[derive(Accounts)]
+pub struct MigrateRouterConfig<'info> {
+    /// CHECK: Legacy RouterConfig, layout, discriminator and authority checked in the handler
+    #[account(
+        mut,
+        seeds = [b"router_config"],
+        bump,
+        owner = crate::ID @ ErrorCode::Unauthorized
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only circuit breaker management for one pair
+#[derive(Accounts)]
+pub struct ManagePairCircuitBreaker<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"oracle_cache", oracle_cache.token_pair[0].as_ref(), oracle_cache.token_pair[1].as_ref()],
+        bump = oracle_cache.bump
+    )]
+    pub oracle_cache: Account<'info, oracle_cache::OracleCache>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVenueRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = VenueRegistry::LEN,
+        seeds = [b"venue_registry"],
+        bump
+    )]
+    pub venue_registry: Account<'info, VenueRegistry>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only venue registry management
+#[derive(Accounts)]
+pub struct ManageVenueRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [b"venue_registry"],
+        bump = venue_registry.bump
+    )]
+    pub venue_registry: Account<'info, VenueRegistry>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenAllowlist<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = TokenAllowlist::LEN,
+        seeds = [b"token_allowlist"],
+        bump
+    )]
+    pub token_allowlist: Account<'info, TokenAllowlist>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
     #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only Token-2022 allowlist management
+#[derive(Accounts)]
+pub struct ManageTokenAllowlist<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_allowlist"],
+        bump = token_allowlist.bump
+    )]
+    pub token_allowlist: Account<'info, TokenAllowlist>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRfqMakers<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = RfqMakers::LEN,
+        seeds = [b"rfq_makers"],
+        bump
+    )]
+    pub rfq_makers: Account<'info, RfqMakers>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only RFQ market maker whitelist management
+#[derive(Accounts)]
+pub struct ManageRfqMakers<'info> {
+    #[account(
+        mut,
+        seeds = [b"rfq_makers"],
+        bump = rfq_makers.bump
+    )]
+    pub rfq_makers: Account<'info, RfqMakers>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, RouterState>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(venue: Pubkey, pool: Option<Pubkey>)]
+pub struct InitializeVenueScore<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"router_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
     pub state: Account<'info, RouterState>,
 
     #[account(
         init,
         payer = authority,
         space = venue_scoring::VenueScore::LEN,
-        seeds = [b"venue_score", state.key().as_ref()],
+        seeds = [b"venue_score", venue.as_ref(), pool.unwrap_or_default().as_ref()],
         bump
     )]
     pub venue_score: Account<'info, venue_scoring::VenueScore>,
@@ -286,6 +668,224 @@ pub struct InitializeVenueScore<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// User setup of their own Phoenix maker seat and escrows for one market
+#[derive(Accounts)]
+pub struct InitializePhoenixMaker<'info> {
+    #[account(seeds = [b"router_state"], bump = state.bump)]
+    pub state: Box<Account<'info, RouterState>>,
+
+    /// CHECK: Router-owned Phoenix trader PDA of the user (no data, signs Phoenix CPIs)
+    #[account(mut, seeds = [b"phoenix_maker", market.key().as_ref(), user.key().as_ref()], bump)]
+    pub maker_seat: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"phoenix_maker_vault", market.key().as_ref(), user.key().as_ref(), base_mint.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = maker_seat
+    )]
+    pub maker_base_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"phoenix_maker_vault", market.key().as_ref(), user.key().as_ref(), quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = maker_seat
+    )]
+    pub maker_quote_account: Box<Account<'info, TokenAccount>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Phoenix market, header parsed in the handler
+    #[account(mut, owner = PHOENIX_PROGRAM_ID @ ErrorCode::InvalidPoolAccount)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix seat PDA ["seat", market, maker_seat], checked in the handler
+    #[account(mut)]
+    pub seat: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix program
+    #[account(address = PHOENIX_PROGRAM_ID)]
+    pub phoenix_program: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix log authority
+    #[account(address = pool_validation::PHOENIX_LOG_AUTHORITY)]
+    pub log_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct PlacePhoenixMakerOrder<'info> {
+    #[account(seeds = [b"router_state"], bump = state.bump)]
+    pub state: Box<Account<'info, RouterState>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = PhoenixMakerOrder::LEN,
+        seeds = [b"phoenix_order", user.key().as_ref(), market.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub order: Box<Account<'info, PhoenixMakerOrder>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_input_account.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub user_input_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Router-owned Phoenix trader PDA of the user
+    #[account(seeds = [b"phoenix_maker", market.key().as_ref(), user.key().as_ref()], bump)]
+    pub maker_seat: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"phoenix_maker_vault",
+            market.key().as_ref(),
+            user.key().as_ref(),
+            maker_base_account.mint.as_ref()
+        ],
+        bump
+    )]
+    pub maker_base_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"phoenix_maker_vault",
+            market.key().as_ref(),
+            user.key().as_ref(),
+            maker_quote_account.mint.as_ref()
+        ],
+        bump
+    )]
+    pub maker_quote_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Phoenix market, header parsed in the handler
+    #[account(mut, owner = PHOENIX_PROGRAM_ID @ ErrorCode::InvalidPoolAccount)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix seat of the user's maker PDA, approval enforced by Phoenix
+    pub seat: UncheckedAccount<'info>,
+
+    /// CHECK: Market base vault, checked against the header
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Market quote vault, checked against the header
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix program
+    #[account(address = PHOENIX_PROGRAM_ID)]
+    pub phoenix_program: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix log authority
+    #[account(address = pool_validation::PHOENIX_LOG_AUTHORITY)]
+    pub log_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless once the order has expired; before that only its owner may settle
+#[derive(Accounts)]
+pub struct SettlePhoenixMakerOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"phoenix_order", order.user.as_ref(), order.market.as_ref(), &order.nonce.to_le_bytes()],
+        bump = order.bump,
+        has_one = user @ ErrorCode::InvalidPhoenixOrder,
+        has_one = market @ ErrorCode::InvalidPhoenixOrder,
+        close = user
+    )]
+    pub order: Box<Account<'info, PhoenixMakerOrder>>,
+
+    pub settler: Signer<'info>,
+
+    /// CHECK: Order owner, receives the order rent
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_base_account.owner == order.user @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub user_base_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_quote_account.owner == order.user @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub user_quote_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Router-owned Phoenix trader PDA of the order owner
+    #[account(seeds = [b"phoenix_maker", market.key().as_ref(), order.user.as_ref()], bump)]
+    pub maker_seat: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"phoenix_maker_vault",
+            market.key().as_ref(),
+            order.user.as_ref(),
+            maker_base_account.mint.as_ref()
+        ],
+        bump
+    )]
+    pub maker_base_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"phoenix_maker_vault",
+            market.key().as_ref(),
+            order.user.as_ref(),
+            maker_quote_account.mint.as_ref()
+        ],
+        bump
+    )]
+    pub maker_quote_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Phoenix market, bound to the order
+    #[account(mut, owner = PHOENIX_PROGRAM_ID @ ErrorCode::InvalidPoolAccount)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: Market base vault, checked against the header
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Market quote vault, checked against the header
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix program
+    #[account(address = PHOENIX_PROGRAM_ID)]
+    pub phoenix_program: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix log authority
+    #[account(address = pool_validation::PHOENIX_LOG_AUTHORITY)]
+    pub log_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 #[program]
 pub mod swapback_router {
     use super::*;
@@ -314,6 +914,9 @@ pub mod swapback_router {
         state.total_boost_vault = 0;
         state.total_treasury_from_fees = 0;
         state.total_buyburn = 0;
+        state.pyth_require_full_verification = false;
+        state.pyth_min_signatures = DEFAULT_PYTH_MIN_SIGNATURES;
+        state.pyth_max_slot_lag = DEFAULT_PYTH_MAX_SLOT_LAG;
         state.bump = ctx.bumps.state;
         Ok(())
     }
@@ -527,11 +1130,71 @@ pub mod swapback_router {
         create_plan_processor::process_create_plan(ctx, plan_id, plan_data)
     }
 
+    /// Migrate a SwapPlan created before `max_slot` existed (no slot limit)
+    pub fn migrate_swap_plan(ctx: Context<MigrateSwapPlan>) -> Result<()> {
+        let info = ctx.accounts.plan.to_account_info();
+        let plan = SwapPlan::from_legacy(&info.try_borrow_data()?)
+            .ok_or(ErrorCode::SwapPlanAlreadyMigrated)?;
+        require_keys_eq!(plan.user, ctx.accounts.user.key(), ErrorCode::UnauthorizedPlanAccess);
+
+        swap_toc_processor::grow_account(
+            &info,
+            &ctx.accounts.user,
+            &ctx.accounts.system_program,
+            SwapPlan::LEN,
+        )?;
+        plan.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        msg!("🗺️ Swap plan migrated for {}", plan.user);
+        Ok(())
+    }
+
+    /// Swap with oracle protection; the `SwapResult` is published as Borsh return data
     pub fn swap_toc<'info>(
         ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
         args: SwapArgs,
-    ) -> Result<()> {
-        swap_toc_processor::process_swap_toc(ctx, args)
+    ) -> Result<SwapResult> {
+        let pending_unwrap = native_sol::prepare(&ctx, args.native_sol, args.amount_in)?;
+        let pending_tip = mev_guard::prepare_tip(&ctx, &args)?;
+        let min_out = args.min_out;
+        let result = swap_toc_processor::process_swap_toc(ctx, args)?;
+        pending_unwrap.finish()?;
+        if let Some(pending_tip) = pending_tip {
+            pending_tip.finish(&result, min_out)?;
+        }
+        Ok(result)
+    }
+
+    /// CPI entry point of `swap_toc` for other programs (vaults, strategies)
+    ///
+    /// Same accounts and arguments as `swap_toc`. `user` may be a PDA of the
+    /// calling program, signing through `invoke_signed`; its token accounts must
+    /// have the PDA as authority. The PDA pays the `user_rebate` rent on first use
+    /// (and the lamports of `native_sol` options), so it must be a system-owned
+    /// account funded with SOL. A halted pair or a price jump fails the call like
+    /// `swap_toc`; on success the caller reads `SwapResult` with `get_return_data`
+    /// right after the CPI.
+    pub fn swap_toc_cpi<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
+        args: SwapArgs,
+    ) -> Result<SwapResult> {
+        swap_toc(ctx, args)
+    }
+
+    /// Exact-output swap: deliver exactly `amount_out`, spend at most `max_in`
+    pub fn swap_exact_out<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
+        args: SwapExactOutArgs,
+    ) -> Result<SwapResult> {
+        swap_toc_processor::process_swap_exact_out(ctx, args)
+    }
+
+    /// Read-only route quote: estimated output per venue, returned as Borsh return data
+    ///
+    /// Takes the same `VenueWeight`s and remaining accounts as a dynamic plan swap;
+    /// meant to be run through `simulateTransaction`.
+    pub fn quote_route(ctx: Context<QuoteRoute>, args: QuoteRouteArgs) -> Result<RouteQuote> {
+        quote::quote_route(ctx, args)
     }
 
     pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
@@ -545,6 +1208,9 @@ pub mod swapback_router {
         config.buyburn_from_fees_bps = PLATFORM_FEE_BUYBURN_BPS;
         config.dynamic_slippage_enabled = false;
         config.npi_benchmarking_enabled = false;
+        config.pyth_require_full_verification = false;
+        config.pyth_min_signatures = DEFAULT_PYTH_MIN_SIGNATURES;
+        config.pyth_max_slot_lag = DEFAULT_PYTH_MAX_SLOT_LAG;
         config.bump = ctx.bumps.config;
         config.validate_percentages()?;
 
@@ -556,6 +1222,50 @@ pub mod swapback_router {
         state.treasury_from_fees_bps = config.treasury_from_fees_bps;
         state.buyburn_from_fees_bps = config.buyburn_from_fees_bps;
         state.dynamic_slippage_enabled = config.dynamic_slippage_enabled;
+        state.pyth_require_full_verification = config.pyth_require_full_verification;
+        state.pyth_min_signatures = config.pyth_min_signatures;
+        state.pyth_max_slot_lag = config.pyth_max_slot_lag;
+        Ok(())
+    }
+
+    /// Update the Pyth pull acceptance policy (verification level + slot lag)
+    pub fn update_oracle_policy(
+        ctx: Context<UpdateConfig>,
+        require_full_verification: Option<bool>,
+        min_signatures: Option<u8>,
+        max_slot_lag: Option<u64>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        if let Some(value) = require_full_verification {
+            config.pyth_require_full_verification = value;
+        }
+        if let Some(value) = min_signatures {
+            config.pyth_min_signatures = value;
+        }
+        if let Some(value) = max_slot_lag {
+            config.pyth_max_slot_lag = value;
+        }
+
+        require!(
+            config.pyth_max_slot_lag <= MAX_PYTH_SLOT_LAG,
+            ErrorCode::InvalidOraclePolicy
+        );
+
+        // Mirror into RouterState (read by swap_toc)
+        let state = &mut ctx.accounts.state;
+        state.pyth_require_full_verification = config.pyth_require_full_verification;
+        state.pyth_min_signatures = config.pyth_min_signatures;
+        state.pyth_max_slot_lag = config.pyth_max_slot_lag;
+
+        emit!(OraclePolicyUpdated {
+            authority: ctx.accounts.authority.key(),
+            require_full_verification: config.pyth_require_full_verification,
+            min_signatures: config.pyth_min_signatures,
+            max_slot_lag: config.pyth_max_slot_lag,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
@@ -847,80 +1557,498 @@ pub mod swapback_router {
             ErrorCode::RebateClaimTooEarly
         );
 
-        // Verify vault has sufficient balance
+        // Verify vault has sufficient balance
+        require!(
+            ctx.accounts.rebate_vault.amount >= user_rebate.unclaimed_rebate,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let claimed_amount = user_rebate.unclaimed_rebate;
+
+        // Transfer USDC from vault to user using PDA signer
+        let seeds = &[b"router_state".as_ref(), &[ctx.accounts.state.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.rebate_vault.to_account_info(),
+            to: ctx.accounts.user_usdc_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, claimed_amount)?;
+
+        // Update user rebate account
+        user_rebate.unclaimed_rebate = 0;
+        user_rebate.total_claimed = user_rebate
+            .total_claimed
+            .checked_add(claimed_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        user_rebate.last_claim_timestamp = Clock::get()?.unix_timestamp;
+
+        // Emit event
+        emit!(RewardsClaimed {
+            user: ctx.accounts.user.key(),
+            amount: claimed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("✅ Rewards claimed: {} USDC", claimed_amount / 1_000_000);
+
+        Ok(())
+    }
+
+    /// Create the oracle cache of a pair: `token_a < token_b` (canonical order, one
+    /// cache per pair) and `fallback_oracle` feeds the divergence check of the crank
+    pub fn initialize_oracle_cache(
+        ctx: Context<InitializeOracleCache>,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        fallback_oracle: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(token_a < token_b, ErrorCode::OracleCachePairMismatch);
+
+        let cache = &mut ctx.accounts.oracle_cache;
+        cache.token_pair = [token_a, token_b];
+        cache.primary_oracle = ctx.accounts.oracle.key();
+        cache.fallback_oracle = fallback_oracle.unwrap_or_default();
+        cache.cached_price = 0;
+        cache.cached_at = 0;
+        cache.cache_duration = 5; // 5 seconds default
+        cache.volatility_bps = 50; // Default 0.5% volatility
+        cache.reference_price = 0;
+        cache.reference_at = 0;
+        cache.halted_until = 0;
+        cache.max_price_move_bps = DEFAULT_PAIR_MAX_MOVE_BPS;
+        cache.price_move_window_secs = DEFAULT_PAIR_MOVE_WINDOW_SECS;
+        cache.halt_duration_secs = DEFAULT_PAIR_HALT_SECS;
+        cache.divergence_strikes = 0;
+        cache.max_divergence_strikes = DEFAULT_PAIR_DIVERGENCE_STRIKES;
+        cache.bump = ctx.bumps.oracle_cache;
+
+        msg!("📈 Oracle cache initialized for {}/{}", token_a, token_b);
+        Ok(())
+    }
+
+    /// Migrate an OracleCache created before the circuit breaker fields existed.
+    /// The cache moves to its pair-keyed PDA (the legacy oracle stays its primary
+    /// oracle) with the breaker defaults, and the legacy account is closed.
+    pub fn migrate_oracle_cache(
+        ctx: Context<MigrateOracleCache>,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        fallback_oracle: Option<Pubkey>,
+    ) -> Result<()> {
+        let legacy = ctx.accounts.legacy_oracle_cache.to_account_info();
+        let mut cache = oracle_cache::OracleCache::from_legacy(
+            &legacy.try_borrow_data()?,
+            ctx.accounts.oracle.key(),
+            fallback_oracle.unwrap_or_default(),
+        )
+        .ok_or(ErrorCode::OracleCacheAlreadyMigrated)?;
+        require!(
+            cache.token_pair == [token_a, token_b],
+            ErrorCode::OracleCachePairMismatch
+        );
+        cache.bump = ctx.bumps.oracle_cache;
+        ctx.accounts.oracle_cache.set_inner(cache);
+
+        // Fermeture du compte legacy: lamports rendus à l'authority
+        let authority = ctx.accounts.authority.to_account_info();
+        **authority.try_borrow_mut_lamports()? = authority
+            .lamports()
+            .checked_add(legacy.lamports())
+            .ok_or(ErrorCode::MathOverflow)?;
+        **legacy.try_borrow_mut_lamports()? = 0;
+        legacy.assign(&anchor_lang::system_program::ID);
+        legacy.realloc(0, false)?;
+
+        msg!("📈 Oracle cache migrated for {}/{}", token_a, token_b);
+        Ok(())
+    }
+
+    /// Refresh the oracle cache of a pair and evaluate its circuit breaker.
+    /// Permissionless (keeper crank): divergence strikes only persist through
+    /// successful transactions, so they are accumulated here rather than in swap_toc.
+    pub fn refresh_oracle_cache(
+        ctx: Context<RefreshOracleCache>,
+        max_staleness_override: Option<i64>,
+    ) -> Result<()> {
+        // Oracles liés à la paire: un feed arbitraire ne peut pas accumuler de strikes
+        let cache = &ctx.accounts.oracle_cache;
+        require_keys_eq!(
+            ctx.accounts.primary_oracle.key(),
+            cache.primary_oracle,
+            ErrorCode::InvalidOraclePrice
+        );
+        let fallback_oracle = match (cache.fallback(), ctx.accounts.fallback_oracle.as_ref()) {
+            (Some(expected_key), Some(account)) => {
+                require_keys_eq!(account.key(), expected_key, ErrorCode::InvalidOraclePrice);
+                Some(account)
+            }
+            (Some(_), None) => return err!(ErrorCode::MissingFallbackOracle),
+            (None, _) => None,
+        };
+
+        let clock = Clock::get()?;
+        let (observation, divergence_bps) = swap_toc_processor::read_oracle_pair(
+            &ctx.accounts.primary_oracle,
+            fallback_oracle,
+            &clock,
+            max_staleness_override,
+            &PythUpdatePolicy::from_state(&ctx.accounts.state),
+        )?;
+
+        let cache = &mut ctx.accounts.oracle_cache;
+        let now = clock.unix_timestamp;
+
+        if cache.is_halted(now) {
+            msg!("⛔ Pair halted until {}", cache.halted_until);
+            return Ok(());
+        }
+
+        let diverged = divergence_bps.is_some_and(|bps| bps > MAX_ORACLE_DIVERGENCE_BPS as u128);
+        if cache.record_divergence(diverged) {
+            swap_toc_processor::halt_pair(cache, "oracle_divergence", 0, now);
+        } else if !diverged {
+            if let Some(move_bps) = cache.observe_price(observation.price, now) {
+                swap_toc_processor::halt_pair(cache, "price_jump", move_bps, now);
+            }
+        } else {
+            msg!(
+                "⚠️ Oracle divergence strike {}/{}",
+                cache.divergence_strikes,
+                cache.max_divergence_strikes
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Create the venue registry with every built-in venue enabled and unrestricted
+    pub fn initialize_venue_registry(ctx: Context<InitializeVenueRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.venue_registry;
+        registry.bump = ctx.bumps.venue_registry;
+        registry.venues = Vec::with_capacity(VENUE_ADAPTERS.len());
+
+        for adapter in &VENUE_ADAPTERS {
+            registry.upsert(VenueEntry {
+                program_id: adapter.program_id,
+                enabled: true,
+                max_notional_per_swap: 0,
+                max_split_share_bps: 10_000,
+                account_count: adapter.min_accounts as u8,
+            })?;
+        }
+
+        msg!("🗂️ Venue registry initialized with {} venues", registry.venues.len());
+        Ok(())
+    }
+
+    /// Add or update a venue. The router must have a CPI adapter for it and
+    /// `account_count` cannot be below the adapter's minimum.
+    pub fn upsert_venue(ctx: Context<ManageVenueRegistry>, entry: VenueEntry) -> Result<()> {
+        let builtin_count = swap_toc_processor::min_account_count(&entry.program_id)?;
         require!(
-            ctx.accounts.rebate_vault.amount >= user_rebate.unclaimed_rebate,
-            ErrorCode::InsufficientVaultBalance
+            builtin_count > 0 && entry.account_count as usize >= builtin_count,
+            ErrorCode::InvalidVenueConfig
         );
 
-        let claimed_amount = user_rebate.unclaimed_rebate;
+        ctx.accounts.venue_registry.upsert(entry)?;
 
-        // Transfer USDC from vault to user using PDA signer
-        let seeds = &[b"router_state".as_ref(), &[ctx.accounts.state.bump]];
-        let signer = &[&seeds[..]];
+        emit!(VenueRegistryUpdated {
+            venue: entry.program_id,
+            enabled: entry.enabled,
+            max_notional_per_swap: entry.max_notional_per_swap,
+            max_split_share_bps: entry.max_split_share_bps,
+            account_count: entry.account_count,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        let cpi_accounts = token::Transfer {
-            from: ctx.accounts.rebate_vault.to_account_info(),
-            to: ctx.accounts.user_usdc_account.to_account_info(),
-            authority: ctx.accounts.state.to_account_info(),
-        };
+    /// Enable or disable a registered venue in one transaction
+    pub fn set_venue_enabled(
+        ctx: Context<ManageVenueRegistry>,
+        venue: Pubkey,
+        enabled: bool,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.venue_registry;
+        registry.set_enabled(&venue, enabled)?;
+        let entry = *registry
+            .entry(&venue)
+            .ok_or_else(|| error!(ErrorCode::VenueNotRegistered))?;
+
+        msg!("🗂️ Venue {} {}", venue, if enabled { "enabled" } else { "disabled" });
+        emit!(VenueRegistryUpdated {
+            venue,
+            enabled: entry.enabled,
+            max_notional_per_swap: entry.max_notional_per_swap,
+            max_split_share_bps: entry.max_split_share_bps,
+            account_count: entry.account_count,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    pub fn initialize_token_allowlist(ctx: Context<InitializeTokenAllowlist>) -> Result<()> {
+        let allowlist = &mut ctx.accounts.token_allowlist;
+        allowlist.bump = ctx.bumps.token_allowlist;
+        allowlist.mints = Vec::new();
 
-        token::transfer(cpi_ctx, claimed_amount)?;
+        msg!("🪙 Token-2022 allowlist initialized");
+        Ok(())
+    }
 
-        // Update user rebate account
-        user_rebate.unclaimed_rebate = 0;
-        user_rebate.total_claimed = user_rebate
-            .total_claimed
-            .checked_add(claimed_amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        user_rebate.last_claim_timestamp = Clock::get()?.unix_timestamp;
+    /// Accept (or stop accepting) a Token-2022 mint with a permanent delegate
+    /// or non-transferable extension
+    pub fn set_token_allowlisted(
+        ctx: Context<ManageTokenAllowlist>,
+        mint: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        ctx.accounts.token_allowlist.set(mint, allowed)?;
 
-        // Emit event
-        emit!(RewardsClaimed {
-            user: ctx.accounts.user.key(),
-            amount: claimed_amount,
+        msg!("🪙 Mint {} {}", mint, if allowed { "allowlisted" } else { "removed from allowlist" });
+        emit!(TokenAllowlistUpdated {
+            mint,
+            allowed,
+            authority: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
+        Ok(())
+    }
 
-        msg!("✅ Rewards claimed: {} USDC", claimed_amount / 1_000_000);
+    pub fn initialize_rfq_makers(ctx: Context<InitializeRfqMakers>) -> Result<()> {
+        let rfq_makers = &mut ctx.accounts.rfq_makers;
+        rfq_makers.bump = ctx.bumps.rfq_makers;
+        rfq_makers.makers = Vec::new();
 
+        msg!("🤝 RFQ maker whitelist initialized");
         Ok(())
     }
 
-    pub fn initialize_oracle_cache(
-        ctx: Context<InitializeOracleCache>,
-        token_in: Pubkey,
-        token_out: Pubkey,
+    /// Whitelist (or remove) a market maker whose signed quotes the RFQ venue accepts
+    pub fn set_rfq_maker(ctx: Context<ManageRfqMakers>, maker: Pubkey, allowed: bool) -> Result<()> {
+        ctx.accounts.rfq_makers.set(maker, allowed)?;
+
+        msg!("🤝 RFQ maker {} {}", maker, if allowed { "whitelisted" } else { "removed" });
+        emit!(RfqMakerUpdated {
+            maker,
+            allowed,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Update circuit breaker parameters for a pair (0 disables a trigger)
+    pub fn configure_pair_circuit_breaker(
+        ctx: Context<ManagePairCircuitBreaker>,
+        max_price_move_bps: Option<u16>,
+        price_move_window_secs: Option<i64>,
+        halt_duration_secs: Option<i64>,
+        max_divergence_strikes: Option<u8>,
     ) -> Result<()> {
         let cache = &mut ctx.accounts.oracle_cache;
-        cache.token_pair = [token_in, token_out];
-        cache.cached_price = 0;
-        cache.cached_at = 0;
-        cache.cache_duration = 5; // 5 seconds default
-        cache.volatility_bps = 50; // Default 0.5% volatility
-        cache.bump = ctx.bumps.oracle_cache;
 
-        msg!("📈 Oracle cache initialized for {}/{}", token_in, token_out);
+        if let Some(value) = max_price_move_bps {
+            require!(value <= 10_000, ErrorCode::InvalidCircuitBreakerConfig);
+            cache.max_price_move_bps = value;
+        }
+        if let Some(value) = price_move_window_secs {
+            require!(value > 0, ErrorCode::InvalidCircuitBreakerConfig);
+            cache.price_move_window_secs = value;
+        }
+        if let Some(value) = halt_duration_secs {
+            require!(
+                value > 0 && value <= MAX_PAIR_HALT_SECS,
+                ErrorCode::InvalidCircuitBreakerConfig
+            );
+            cache.halt_duration_secs = value;
+        }
+        if let Some(value) = max_divergence_strikes {
+            cache.max_divergence_strikes = value;
+        }
+
+        msg!(
+            "🧯 Pair breaker: move {} bps / {}s, halt {}s, strikes {}",
+            cache.max_price_move_bps,
+            cache.price_move_window_secs,
+            cache.halt_duration_secs,
+            cache.max_divergence_strikes
+        );
+        Ok(())
+    }
+
+    /// Authority override: halt a pair immediately or lift an active halt
+    pub fn set_pair_halt(ctx: Context<ManagePairCircuitBreaker>, halted: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let cache = &mut ctx.accounts.oracle_cache;
+
+        if halted {
+            swap_toc_processor::halt_pair(cache, "authority_override", 0, now);
+        } else {
+            cache.resume();
+            emit!(PairResumed {
+                oracle: cache.primary_oracle,
+                token_pair: cache.token_pair,
+                authority: ctx.accounts.authority.key(),
+                timestamp: now,
+            });
+            msg!("✅ Pair {}/{} resumed", cache.token_pair[0], cache.token_pair[1]);
+        }
+
         Ok(())
     }
 
-    pub fn initialize_venue_score(ctx: Context<InitializeVenueScore>) -> Result<()> {
+    /// Create the score account of a venue program, or of one pool of that venue
+    pub fn initialize_venue_score(
+        ctx: Context<InitializeVenueScore>,
+        venue: Pubkey,
+        pool: Option<Pubkey>,
+    ) -> Result<()> {
         let score = &mut ctx.accounts.venue_score;
-        score.venue = ctx.accounts.state.key();
-        score.venue_type = venue_scoring::VenueType::Unknown;
+        score.venue = venue;
+        score.pool = pool.unwrap_or_default();
+        score.venue_type = pubkey_to_venue_type(&venue);
+        score.bump = ctx.bumps.venue_score;
         score.total_swaps = 0;
+        score.total_failures = 0;
         score.total_volume = 0;
-        score.total_npi_generated = 0;
-        score.avg_latency_ms = 0;
-        score.avg_slippage_bps = 0;
+        score.avg_price_improvement_bps = 0;
         score.quality_score = 0;
         score.last_updated = Clock::get()?.unix_timestamp;
         score.window_start = Clock::get()?.unix_timestamp;
+        score.failure_strikes = 0;
+        score.strike_window_start = 0;
+        score.quarantined_until = 0;
+        score.max_failures = DEFAULT_VENUE_MAX_FAILURES;
+        score.failure_window_secs = DEFAULT_VENUE_FAILURE_WINDOW_SECS;
+        score.quarantine_secs = DEFAULT_VENUE_QUARANTINE_SECS;
+        score.max_adverse_bps = DEFAULT_VENUE_MAX_ADVERSE_BPS;
+        Ok(())
+    }
+
+    /// Update circuit breaker parameters for a venue (0 disables a trigger)
+    pub fn configure_venue_circuit_breaker(
+        ctx: Context<ManageVenueCircuitBreaker>,
+        max_failures: Option<u8>,
+        failure_window_secs: Option<i64>,
+        quarantine_secs: Option<i64>,
+        max_adverse_bps: Option<u16>,
+    ) -> Result<()> {
+        let score = &mut ctx.accounts.venue_score;
+
+        if let Some(value) = max_failures {
+            score.max_failures = value;
+        }
+        if let Some(value) = failure_window_secs {
+            require!(value > 0, ErrorCode::InvalidCircuitBreakerConfig);
+            score.failure_window_secs = value;
+        }
+        if let Some(value) = quarantine_secs {
+            require!(
+                value > 0 && value <= MAX_VENUE_QUARANTINE_SECS,
+                ErrorCode::InvalidCircuitBreakerConfig
+            );
+            score.quarantine_secs = value;
+        }
+        if let Some(value) = max_adverse_bps {
+            require!(value <= 10_000, ErrorCode::InvalidCircuitBreakerConfig);
+            score.max_adverse_bps = value;
+        }
+
+        msg!(
+            "🧯 Venue breaker {}: {} failures / {}s, quarantine {}s, adverse {} bps",
+            score.venue,
+            score.max_failures,
+            score.failure_window_secs,
+            score.quarantine_secs,
+            score.max_adverse_bps
+        );
+        Ok(())
+    }
+
+    /// Authority override: lift a venue (or pool) quarantine and clear its strikes
+    pub fn reset_venue_circuit_breaker(ctx: Context<ManageVenueCircuitBreaker>) -> Result<()> {
+        let score = &mut ctx.accounts.venue_score;
+        score.reset_breaker();
+        ctx.accounts.venue_registry.lift_pool_quarantine(&score.venue, &score.pool);
+
+        emit!(VenueBreakerReset {
+            venue: score.venue,
+            pool: score.pool,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("✅ Venue {} breaker reset", score.venue);
+        Ok(())
+    }
+
+    /// Migrate the router state to the layout carrying the Pyth update policy
+    pub fn migrate_router_state(ctx: Context<MigrateRouterState>) -> Result<()> {
+        let info = ctx.accounts.state.to_account_info();
+        let state = RouterState::from_legacy(&info.try_borrow_data()?)
+            .ok_or(ErrorCode::RouterAccountAlreadyMigrated)?;
+        require_keys_eq!(state.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+        swap_toc_processor::grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            RouterState::LEN,
+        )?;
+        state.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        msg!("🛠️ Router state migrated (default Pyth update policy)");
+        Ok(())
+    }
+
+    /// Migrate the router config to the layout carrying the Pyth update policy
+    pub fn migrate_router_config(ctx: Context<MigrateRouterConfig>) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        let config = RouterConfig::from_legacy(&info.try_borrow_data()?)
+            .ok_or(ErrorCode::RouterAccountAlreadyMigrated)?;
+        require_keys_eq!(config.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+        swap_toc_processor::grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            RouterConfig::LEN,
+        )?;
+        config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        msg!("🛠️ Router config migrated (default Pyth update policy)");
         Ok(())
     }
+
+    /// Create the caller's Phoenix maker escrows for a market and request their seat
+    pub fn initialize_phoenix_maker(ctx: Context<InitializePhoenixMaker>) -> Result<()> {
+        phoenix_maker::initialize(ctx)
+    }
+
+    /// Post a post-only limit order through the caller's Phoenix maker seat
+    pub fn place_phoenix_maker_order(
+        ctx: Context<PlacePhoenixMakerOrder>,
+        nonce: u64,
+        side: u8,
+        price_in_ticks: u64,
+        num_base_lots: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        phoenix_maker::place_order(ctx, nonce, side, price_in_ticks, num_base_lots, expires_at)
+    }
+
+    /// Cancel leftovers and pay out a maker order (anyone once expired)
+    pub fn settle_phoenix_maker_order(ctx: Context<SettlePhoenixMakerOrder>) -> Result<()> {
+        phoenix_maker::settle_order(ctx)
+    }
 }
 
 #[derive(Accounts)]
@@ -945,7 +2073,7 @@ pub struct CreatePlan<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + (4 + 10 * (32 + 2)) + (4 + 5 * (4 + 10 * (32 + 2) + 8)) + 8 + 8 + 1,
+        space = SwapPlan::LEN,
         seeds = [b"swap_plan", user.key().as_ref()],
         bump
     )]
@@ -967,6 +2095,8 @@ pub struct CreatePlanArgs {
     pub venues: Vec<VenueWeight>,
     pub fallback_plans: Vec<FallbackPlan>,
     pub expires_at: i64,
+    /// Last slot at which the plan may execute (0 = no slot limit)
+    pub max_slot: u64,
 }
 
 #[derive(Accounts)]
@@ -983,25 +2113,22 @@ pub struct SwapToC<'info> {
     /// CHECK: Optional fallback oracle (e.g. Pyth feed)
     pub fallback_oracle: Option<AccountInfo<'info>>,
 
-    /// User's token account A - with ownership validation (H1 FIX)
-    #[account(
-        mut,
-        constraint = user_token_account_a.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner
-    )]
-    pub user_token_account_a: Box<Account<'info, TokenAccount>>,
+    /// CHECK: User's token account A (input). May not exist yet when native SOL is
+    /// wrapped in the instruction; ownership (H1 FIX) and mint are validated by
+    /// `token_extensions::check_swap_mints` before any use
+    #[account(mut)]
+    pub user_token_account_a: UncheckedAccount<'info>,
 
-    /// User's token account B - with ownership validation (H1 FIX)
-    #[account(
-        mut,
-        constraint = user_token_account_b.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner
-    )]
-    pub user_token_account_b: Box<Account<'info, TokenAccount>>,
+    /// CHECK: User's token account B (output). May be created in the instruction
+    /// (idempotent ATA); validated like account A
+    #[account(mut)]
+    pub user_token_account_b: UncheckedAccount<'info>,
 
     #[account(mut)]
-    pub vault_token_account_a: Box<Account<'info, TokenAccount>>,
+    pub vault_token_account_a: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(mut)]
-    pub vault_token_account_b: Box<Account<'info, TokenAccount>>,
+    pub vault_token_account_b: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     /// CHECK: Optional swap plan account when using dynamic plans
     pub plan: Option<Box<Account<'info, SwapPlan>>>,
@@ -1020,7 +2147,7 @@ pub struct SwapToC<'info> {
 
     /// CHECK: Buyback USDC vault (validated in buyback program)
     #[account(mut)]
-    pub buyback_usdc_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub buyback_usdc_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
     /// CHECK: Buyback state account (validated in buyback program)
     #[account(mut)]
@@ -1028,7 +2155,7 @@ pub struct SwapToC<'info> {
 
     /// CHECK: User's rebate USDC account (for receiving boosted rebates)
     #[account(mut)]
-    pub user_rebate_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub user_rebate_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
     /// User's rebate tracking PDA - for deferred claim model
     /// Credits are stored here and claimable after 48h
@@ -1047,28 +2174,75 @@ pub struct SwapToC<'info> {
         seeds = [b"rebate_vault", state.key().as_ref()],
         bump
     )]
-    pub rebate_vault: Box<Account<'info, TokenAccount>>,
+    pub rebate_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    /// CHECK: Optional Oracle Cache for optimized price lookups
+    /// Oracle cache of the pair: always loaded so its circuit breaker cannot be bypassed
+    /// (pair and primary oracle checked against the swap in pre_swap_checks)
     #[account(
         mut,
-        seeds = [b"oracle_cache", primary_oracle.key().as_ref()],
-        bump
+        seeds = [b"oracle_cache", oracle_cache.token_pair[0].as_ref(), oracle_cache.token_pair[1].as_ref()],
+        bump = oracle_cache.bump
     )]
-    pub oracle_cache: Option<Account<'info, oracle_cache::OracleCache>>,
+    pub oracle_cache: Account<'info, oracle_cache::OracleCache>,
 
-    /// CHECK: Optional Venue Score for routing optimization
+    /// Optional Venue Score of the executed venue (or pool), updated after the swap
     #[account(
         mut,
-        seeds = [b"venue_score", state.key().as_ref()], // Using state as seed for now as venue is dynamic
-        bump
+        seeds = [b"venue_score", venue_score.venue.as_ref(), venue_score.pool.as_ref()],
+        bump = venue_score.bump
     )]
     pub venue_score: Option<Account<'info, venue_scoring::VenueScore>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Governed venue registry, checked before every DEX CPI (also records
+    /// venue quarantines tripped by a VenueScore)
+    #[account(
+        mut,
+        seeds = [b"venue_registry"],
+        bump = venue_registry.bump
+    )]
+    pub venue_registry: Box<Account<'info, VenueRegistry>>,
+
+    /// Mint of token A, required when user_token_account_a is a Token-2022 account
+    /// or when native SOL is wrapped (must match the account's mint)
+    pub input_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+
+    /// Mint of token B, required when user_token_account_b is a Token-2022 account
+    /// or when its ATA is created in the instruction (must match the account's mint)
+    pub output_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+
+    /// Token-2022 mints accepted despite risky extensions
+    #[account(
+        seeds = [b"token_allowlist"],
+        bump = token_allowlist.bump
+    )]
+    pub token_allowlist: Option<Box<Account<'info, TokenAllowlist>>>,
+
+    /// Required to create the WSOL / output ATAs in the instruction
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// CHECK: Instructions sysvar (address-checked), required by `sandwich_guard`
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Jito tip account selected by `jito_bundle.tip_account_index`, required
+    /// by `jito_bundle.pay_tip` (checked against `JITO_TIP_ACCOUNTS` in the handler)
+    #[account(mut)]
+    pub jito_tip_account: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+/// Read-only route quote context (venue accounts in remaining_accounts)
+#[derive(Accounts)]
+pub struct QuoteRoute<'info> {
+    #[account(
+        seeds = [b"venue_registry"],
+        bump = venue_registry.bump
+    )]
+    pub venue_registry: Box<Account<'info, VenueRegistry>>,
+}
+
 /// Claim accumulated rebates context
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
@@ -1260,6 +2434,12 @@ pub struct JitoBundleConfig {
     pub tip_lamports: u64,
     /// Selected tip account index (0-7)
     pub tip_account_index: u8,
+    /// Pay the clamped tip from `swap_toc` itself (system transfer to the tip
+    /// account, passed as `jito_tip_account`) instead of a separate instruction
+    pub pay_tip: bool,
+    /// Fund the in-instruction tip from the swap output: requires an unwrapped WSOL
+    /// output, and `min_out` is then enforced net of the tip
+    pub tip_from_output: bool,
 }
 
 impl Default for JitoBundleConfig {
@@ -1268,6 +2448,8 @@ impl Default for JitoBundleConfig {
             enabled: false,
             tip_lamports: DEFAULT_JITO_TIP_LAMPORTS,
             tip_account_index: 0,
+            pay_tip: false,
+            tip_from_output: false,
         }
     }
 }
@@ -1298,7 +2480,7 @@ pub struct SwapArgs {
     pub fallback_oracle_account: Option<Pubkey>, // Optional fallback oracle account
     /// Direct DEX venue program ID for native swaps (bypasses Jupiter)
     /// When Some(venue), executes CPI directly to the specified DEX
-    /// Supported: Orca Whirlpool, Raydium CLMM, Meteora, Phoenix, Lifinity, Sanctum, Saber
+    /// Supported: Orca Whirlpool, Raydium AMM/CLMM/CPMM, Meteora DLMM/DAMM, Phoenix, OpenBook v2, Lifinity, Sanctum, Saber
     pub direct_dex_venue: Option<Pubkey>,
     pub jupiter_route: Option<JupiterRouteParams>,
     /// Jupiter instruction data for CPI replay (provided by keeper/SDK)
@@ -1311,9 +2493,11 @@ pub struct SwapArgs {
     pub min_venue_score: Option<u16>,
     /// Per-venue slippage overrides (allows user to set max slippage per DEX)
     pub slippage_per_venue: Option<Vec<VenueSlippage>>,
-    /// Token A decimals (for accurate TVL calculation, default: 6)
+    /// Token A decimals (for accurate TVL calculation, default: 6).
+    /// Ignored when `input_mint` and `output_mint` are passed
     pub token_a_decimals: Option<u8>,
-    /// Token B decimals (for accurate TVL calculation, default: 6)
+    /// Token B decimals (for accurate TVL calculation, default: 6).
+    /// Ignored when `input_mint` and `output_mint` are passed
     pub token_b_decimals: Option<u8>,
     /// Max staleness override in seconds (for volatile markets, use 30-60s instead of default 300s)
     /// Must be between MIN_STALENESS_SECS and MAX_STALENESS_SECS
@@ -1321,6 +2505,124 @@ pub struct SwapArgs {
     /// Jito bundle configuration for MEV protection
     /// When enabled, transaction is sent via Jito's private mempool
     pub jito_bundle: Option<JitoBundleConfig>,
+    /// Native SOL handling (wrap input, unwrap output, create output ATA)
+    pub native_sol: Option<NativeSolOptions>,
+    /// Signed market maker quote, settled when `RFQ_VENUE_ID` is the swap venue
+    pub rfq: Option<RfqParams>,
+    /// Reject the swap if another instruction of the transaction writes one of its
+    /// pool accounts, and require the Jito tip transfer when `jito_bundle.enabled`
+    /// (needs the instructions sysvar account)
+    pub sandwich_guard: bool,
+    /// Reject the swap once the cluster clock passes this Unix timestamp
+    pub deadline_unix: Option<i64>,
+    /// Reject the swap once the cluster passes this slot
+    pub max_slot: Option<u64>,
+}
+
+/// Outcome of `swap_toc`, set as Borsh return data for CPI callers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Input actually debited from the user's account A
+    pub amount_in: u64,
+    /// Output actually credited to the user's account B
+    pub amount_out: u64,
+    /// Platform fee accounted on the output
+    pub platform_fee: u64,
+    /// Rebate credited to the user's `UserRebate` (claimable later)
+    pub rebate_credited: u64,
+    /// Venue programs that executed the swap
+    pub venues: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuoteRouteArgs {
+    pub amount_in: u64,
+    /// Mint sold, used to orient each pool
+    pub input_mint: Pubkey,
+    /// Same split as an `AtomicSwapPlan` (weights sum to 10_000)
+    pub venues: Vec<VenueWeight>,
+}
+
+/// Result of `quote_route`, set as Borsh return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteQuote {
+    pub amount_in: u64,
+    /// Sum of the supported venues' estimates
+    pub amount_out: u64,
+    pub venues: Vec<VenueQuote>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VenueQuote {
+    pub venue: Pubkey,
+    pub amount_in: u64,
+    /// Estimated gross output (before platform fee and Token-2022 transfer fees)
+    pub amount_out: u64,
+    /// False when the venue has no on-chain estimator (amount_out is then 0)
+    pub supported: bool,
+}
+
+/// Native SOL handling done inside `swap_toc`, saving the wallet's setup instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NativeSolOptions {
+    /// Wrap `amount_in` lamports into the user's WSOL ATA (created if missing,
+    /// closed back to SOL after the swap when created here)
+    pub wrap_input: bool,
+    /// Close a WSOL output account after the swap, crediting the user in SOL
+    pub unwrap_output: bool,
+    /// Create the user's output ATA if it does not exist (idempotent)
+    pub create_output_ata: bool,
+}
+
+/// Quote signed by a whitelisted market maker (ed25519 over `RFQ_MESSAGE_PREFIX || borsh(quote)`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RfqQuote {
+    pub maker: Pubkey,
+    /// Only this wallet may fill the quote
+    pub taker: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Exact input sold by the taker
+    pub amount_in: u64,
+    /// Exact output delivered by the maker
+    pub amount_out: u64,
+    /// Unix timestamp after which the quote is rejected
+    pub expiry: i64,
+    /// Single-use per maker (consumed through a `[b"rfq_nonce", maker, nonce]` PDA)
+    pub nonce: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RfqParams {
+    pub quote: RfqQuote,
+    /// AMM split the quote must beat, estimated from the pool accounts passed
+    /// after the RFQ accounts
+    pub benchmark_venues: Vec<VenueWeight>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapExactOutArgs {
+    /// Exact amount of token B the user must receive (net of Token-2022 transfer fees)
+    pub amount_out: u64,
+    /// Maximum amount of token A the user agrees to spend
+    pub max_in: u64,
+    /// Venue program ID. Native exact-out on Orca Whirlpool, Raydium CLMM and Phoenix
+    /// (buying base); venues with a pool-state estimator (Raydium AMM, Saber) are
+    /// emulated with an exact-input swap of the smallest sufficient input; others are rejected
+    pub venue: Pubkey,
+    /// Primary oracle account the pair's OracleCache (circuit breaker) is keyed on
+    pub primary_oracle_account: Pubkey,
+    /// Optional fallback oracle account (divergence check, as in `SwapArgs`)
+    pub fallback_oracle_account: Option<Pubkey>,
+    /// Max staleness override in seconds (as in `SwapArgs`)
+    pub max_staleness_override: Option<i64>,
+    /// Reject the swap if another instruction of the transaction writes one of its
+    /// pool accounts (needs the instructions sysvar account)
+    pub sandwich_guard: bool,
+    /// Reject the swap once the cluster clock passes this Unix timestamp
+    pub deadline_unix: Option<i64>,
+    /// Reject the swap once the cluster passes this slot
+    pub max_slot: Option<u64>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -1348,6 +2650,23 @@ pub struct SwapPlan {
     pub expires_at: i64,                   // Plan expiration timestamp
     pub created_at: i64,                   // Plan creation timestamp
     pub bump: u8,                          // PDA bump
+    pub max_slot: u64,                     // Last executable slot (0 = none)
+}
+
+impl SwapPlan {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + (4 + 10 * (32 + 2)) + (4 + 5 * (4 + 10 * (32 + 2) + 8)) + 8 + 8 + 1 + 8;
+    /// Taille des plans créés avant `max_slot`
+    pub const LEGACY_LEN: usize = Self::LEN - 8;
+
+    /// Plan créé avant `max_slot`: le champ manquant vaut 0 (pas de limite de slot)
+    pub fn from_legacy(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEGACY_LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let mut padded = data.to_vec();
+        padded.extend_from_slice(&[0u8; 8]);
+        Self::try_deserialize(&mut padded.as_slice()).ok()
+    }
 }
 
 // NOTE: RouterState and UserRebate have been moved to state/router_state.rs
@@ -1377,35 +2696,111 @@ pub struct UserRebate {
     pub last_claim_timestamp: i64,
     pub bump: u8,
 }
-*/
+*/
+
+/* Moved to state/router_state.rs
+impl UserRebate {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        8 +  // unclaimed_rebate
+        8 +  // total_claimed
+        8 +  // total_swaps
+        8 +  // last_swap_timestamp
+        8 +  // last_claim_timestamp
+        1;   // bump
+}
+*/
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum OracleType {
+    Switchboard,
+    Pyth,
+    SwitchboardOnDemand,
+}
+
+#[event]
+pub struct OracleChecked {
+    pub feed: Pubkey,
+    pub price: u64,
+    pub confidence: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub oracle_type: OracleType,
+}
+
+/// Emitted when the per-pair circuit breaker halts swaps
+#[event]
+pub struct PairHalted {
+    pub oracle: Pubkey,
+    pub token_pair: [Pubkey; 2],
+    pub reason: String, // "price_jump", "oracle_divergence" or "authority_override"
+    pub move_bps: u64,
+    pub halted_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PairResumed {
+    pub oracle: Pubkey,
+    pub token_pair: [Pubkey; 2],
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VenueQuarantined {
+    pub venue: Pubkey,
+    pub pool: Pubkey,
+    pub reason: String,
+    pub quarantined_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VenueBreakerReset {
+    pub venue: Pubkey,
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VenueRegistryUpdated {
+    pub venue: Pubkey,
+    pub enabled: bool,
+    pub max_notional_per_swap: u64,
+    pub max_split_share_bps: u16,
+    pub account_count: u8,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
 
-/* Moved to state/router_state.rs
-impl UserRebate {
-    pub const LEN: usize = 8 + // discriminator
-        32 + // user
-        8 +  // unclaimed_rebate
-        8 +  // total_claimed
-        8 +  // total_swaps
-        8 +  // last_swap_timestamp
-        8 +  // last_claim_timestamp
-        1;   // bump
+#[event]
+pub struct TokenAllowlistUpdated {
+    pub mint: Pubkey,
+    pub allowed: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
-*/
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub enum OracleType {
-    Switchboard,
-    Pyth,
+#[event]
+pub struct RfqMakerUpdated {
+    pub maker: Pubkey,
+    pub allowed: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct OracleChecked {
-    pub feed: Pubkey,
-    pub price: u64,
-    pub confidence: u64,
-    pub slot: u64,
+pub struct RfqFilled {
+    pub user: Pubkey,
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Meilleure sortie AMM estimée au moment du règlement
+    pub amm_benchmark_out: u64,
     pub timestamp: i64,
-    pub oracle_type: OracleType,
 }
 
 #[event]
@@ -1417,6 +2812,50 @@ pub struct VenueExecuted {
     pub fallback_used: bool,
 }
 
+#[event]
+pub struct PartialFill {
+    pub venue: Pubkey,
+    pub amount_in: u64,
+    pub spent: u64,
+    pub unspent: u64,
+}
+
+#[event]
+pub struct PhoenixMakerOrderPlaced {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub side: u8,
+    pub price_in_ticks: u64,
+    pub num_base_lots: u64,
+    pub amount_in: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct PhoenixMakerOrderSettled {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub filled_in: u64,
+    pub refunded: u64,
+    pub proceeds: u64,
+    pub settler: Pubkey,
+}
+
+#[event]
+pub struct ExactOutSwapExecuted {
+    pub user: Pubkey,
+    pub venue: Pubkey,
+    pub amount_out: u64,
+    pub max_in: u64,
+    pub spent: u64,
+    pub received: u64,
+    /// Input left on the user's source account (max_in - spent)
+    pub unspent_in: u64,
+    pub native: bool,
+}
+
 #[event]
 pub struct FallbackTriggered {
     pub plan_index: u8,
@@ -1509,6 +2948,15 @@ pub struct ConfigUpdated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct OraclePolicyUpdated {
+    pub authority: Pubkey,
+    pub require_full_verification: bool,
+    pub min_signatures: u8,
+    pub max_slot_lag: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct BuyburnDeposit {
     pub amount: u64,
@@ -1678,6 +3126,98 @@ pub enum ErrorCode {
     JupiterSpentTooHigh,
     #[msg("Invalid liquidity estimate (must be > 0)")]
     InvalidLiquidityEstimate,
+    #[msg("Trading halted for this pair (circuit breaker)")]
+    PairTradingHalted,
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreakerConfig,
+    #[msg("Oracle update does not meet the verification policy")]
+    InsufficientOracleVerification,
+    #[msg("Invalid oracle policy configuration")]
+    InvalidOraclePolicy,
+    #[msg("Invalid LST stake pool account")]
+    InvalidLstRateAccount,
+    #[msg("LST exchange rate deviates too much from oracle price")]
+    LstRateOracleMismatch,
+    #[msg("Venue is not registered in the venue registry")]
+    VenueNotRegistered,
+    #[msg("Venue is disabled")]
+    VenueDisabled,
+    #[msg("Amount exceeds the venue's max notional per swap")]
+    VenueNotionalExceeded,
+    #[msg("Venue weight exceeds its max share of a split")]
+    VenueShareExceeded,
+    #[msg("Venue registry is full")]
+    VenueRegistryFull,
+    #[msg("Invalid venue configuration")]
+    InvalidVenueConfig,
+    #[msg("Venue score account does not match the executed venue")]
+    VenueScoreMismatch,
+    #[msg("Venue is quarantined by its circuit breaker")]
+    VenueQuarantined,
+    #[msg("Pool account does not belong to the venue")]
+    InvalidPoolAccount,
+    #[msg("Venue did not spend any input")]
+    VenueNoInputSpent,
+    #[msg("Venue spent more input than requested")]
+    VenueInputOverspent,
+    #[msg("Phoenix maker order has not expired yet")]
+    PhoenixOrderNotExpired,
+    #[msg("Invalid Phoenix maker order or market")]
+    InvalidPhoenixOrder,
+    #[msg("Mint has a permanent delegate or non-transferable extension and is not allowlisted")]
+    DangerousMintExtension,
+    #[msg("Token-2022 accounts require their mint account")]
+    Token2022MintRequired,
+    #[msg("Token allowlist is full")]
+    TokenAllowlistFull,
+    #[msg("Venue does not support exact-output swaps in this direction")]
+    ExactOutNotSupported,
+    #[msg("Native SOL handling requires the mint and the associated token program accounts")]
+    NativeSolAccountMissing,
+    #[msg("RFQ maker is not whitelisted")]
+    RfqMakerNotAllowed,
+    #[msg("RFQ quote does not match the swap or its accounts")]
+    InvalidRfqQuote,
+    #[msg("RFQ quote has expired")]
+    RfqQuoteExpired,
+    #[msg("RFQ quote signature not found in an ed25519 instruction")]
+    RfqSignatureMissing,
+    #[msg("RFQ quote nonce already used")]
+    RfqNonceUsed,
+    #[msg("RFQ quote does not beat the AMM route")]
+    RfqQuoteWorseThanAmm,
+    #[msg("RFQ maker whitelist is full")]
+    RfqMakerListFull,
+    #[msg("Instructions sysvar account is required")]
+    InstructionsSysvarMissing,
+    #[msg("Another instruction in the transaction writes a pool account of this swap")]
+    SandwichDetected,
+    #[msg("Jito bundle enabled but the tip transfer is missing from the transaction")]
+    JitoTipMissing,
+    #[msg("Jito tip account is missing or is not the configured tip account")]
+    InvalidJitoTipAccount,
+    #[msg("Funding the Jito tip from output requires an unwrapped WSOL output")]
+    JitoTipFromOutputUnsupported,
+    #[msg("Swap deadline has passed")]
+    SwapDeadlineExceeded,
+    #[msg("Swap slot window has passed")]
+    SwapSlotExceeded,
+    #[msg("Oracle price moved beyond the circuit breaker threshold")]
+    OraclePriceJump,
+    #[msg("Oracle cache is not in the legacy layout")]
+    OracleCacheAlreadyMigrated,
+    #[msg("Swap plan is not in the legacy layout")]
+    SwapPlanAlreadyMigrated,
+    #[msg("Router account is not in the legacy layout")]
+    RouterAccountAlreadyMigrated,
+    #[msg("LST stake pool rate account missing or pool unavailable")]
+    LstRateUnavailable,
+    #[msg("LST stake pool not updated for the current epoch")]
+    LstRateStale,
+    #[msg("Sanctum LST routes must be executed as a direct venue")]
+    LstRouteRequiresDirectVenue,
+    #[msg("Oracle cache pair does not match the swapped mints or is not in canonical order")]
+    OracleCachePairMismatch,
 }
 
 pub mod create_plan_processor {
@@ -1709,6 +3249,7 @@ pub mod create_plan_processor {
         plan.expires_at = plan_data.expires_at;
         plan.created_at = clock.unix_timestamp;
         plan.bump = ctx.bumps.plan;
+        plan.max_slot = plan_data.max_slot;
 
         Ok(())
     }
@@ -1719,10 +3260,29 @@ pub mod swap_toc_processor {
     use crate::cpi_orca;
     use crate::oracle::{self, OracleObservation};
 
+    /// Exécute le swap et retourne son résultat, soldes utilisateur mesurés
+    /// (single, plan dynamique ou première tranche TWAP)
     pub fn process_swap_toc<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
+        args: SwapArgs,
+    ) -> Result<SwapResult> {
+        let source = ctx.accounts.user_token_account_a.to_account_info();
+        let destination = ctx.accounts.user_token_account_b.to_account_info();
+        let source_before = balance_delta::read_token_amount(&source)?;
+        let destination_before = balance_delta::read_token_amount(&destination)?;
+
+        let mut result = execute_swap_toc(ctx, args)?;
+
+        result.amount_in = source_before.saturating_sub(balance_delta::read_token_amount(&source)?);
+        result.amount_out =
+            balance_delta::read_token_amount(&destination)?.saturating_sub(destination_before);
+        Ok(result)
+    }
+
+    fn execute_swap_toc<'info>(
         mut ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
         args: SwapArgs,
-    ) -> Result<()> {
+    ) -> Result<SwapResult> {
         // ✅ SECURITY: Check if protocol is paused (circuit breaker)
         require!(!ctx.accounts.state.is_paused, error::SwapbackError::ProtocolPaused);
 
@@ -1743,6 +3303,21 @@ pub mod swap_toc_processor {
 
         let clock = Clock::get()?;
 
+        // ✅ SECURITY: a stale signed transaction cannot land in a moved market
+        check_deadline(args.deadline_unix, args.max_slot, &clock)?;
+
+        // ✅ SECURITY: Token-2022 mints with a permanent delegate / non-transferable
+        // extension are rejected unless allowlisted
+        token_extensions::check_swap_mints(&ctx)?;
+
+        // Décimales des mints si fournies; celles des arguments ne servent qu'au
+        // min_out de l'utilisateur, jamais au score des venues
+        let mint_decimals = token_extensions::swap_decimals(&ctx);
+        let (token_a_decimals, token_b_decimals) = mint_decimals.unwrap_or((
+            args.token_a_decimals.unwrap_or(6),
+            args.token_b_decimals.unwrap_or(6),
+        ));
+
         // Ensure UserRebate PDA is initialized (init_if_needed creates the account,
         // but does not populate fields).
         if ctx.accounts.user_rebate.user == Pubkey::default() {
@@ -1750,22 +3325,34 @@ pub mod swap_toc_processor {
             ctx.accounts.user_rebate.bump = ctx.bumps.user_rebate;
         }
 
-        // --- Phase 2: Oracle Cache Check ---
-        if let Some(cache) = &ctx.accounts.oracle_cache {
-            if cache.is_stale(clock.unix_timestamp) {
-                msg!("Warning: Oracle cache is stale");
+        // ✅ SECURITY: sandwich guard, per-pair circuit breaker, oracle read and price jump
+        let oracle_observation = pre_swap_checks(
+            &mut ctx,
+            PreSwapGuard {
+                primary_oracle_account: args.primary_oracle_account,
+                fallback_oracle_account: args.fallback_oracle_account,
+                max_staleness_override: args.max_staleness_override,
+                sandwich_guard: args.sandwich_guard,
+                jito_bundle: args.jito_bundle.as_ref(),
+            },
+            &clock,
+        )?;
+
+        // ✅ SECURITY: Per-venue circuit breaker (direct venue)
+        if let (Some(venue_score), Some(venue)) = (&ctx.accounts.venue_score, args.direct_dex_venue) {
+            if venue_score.venue == venue {
+                require!(
+                    !venue_score.is_quarantined(clock.unix_timestamp),
+                    ErrorCode::VenueQuarantined
+                );
             }
         }
 
         // --- Dynamic Slippage Calculation ---
         // Uses real TVL estimation from remaining accounts when available
-        // Priority: args.volatility_bps > oracle_cache > default
+        // Priority: args.volatility_bps > oracle_cache
         // Priority: args.liquidity_estimate > pool estimation > default
         let slippage_bps_effective = if ctx.accounts.state.dynamic_slippage_enabled {
-            // Get token decimals from args or use defaults
-            let token_a_decimals = args.token_a_decimals.unwrap_or(6);
-            let token_b_decimals = args.token_b_decimals.unwrap_or(6);
-
             // Priority 1: Use liquidity_estimate from args if provided (off-chain data)
             // Priority 2: Estimate from remaining_accounts (on-chain pool data)
             // Priority 3: Default fallback
@@ -1779,14 +3366,9 @@ pub mod swap_toc_processor {
 
             // Priority 1: Use volatility_bps from args if provided (off-chain/keeper data)
             // Priority 2: Use oracle_cache volatility
-            // Priority 3: Default to 50 bps
-            let volatility_bps = args.volatility_bps.unwrap_or_else(|| {
-                ctx.accounts
-                    .oracle_cache
-                    .as_ref()
-                    .map(|c| c.volatility_bps)
-                    .unwrap_or(50u16)
-            });
+            let volatility_bps = args
+                .volatility_bps
+                .unwrap_or(ctx.accounts.oracle_cache.volatility_bps);
 
             let result = crate::slippage::calculate_dynamic_slippage_with_breakdown(
                 args.amount_in,
@@ -1810,43 +3392,46 @@ pub mod swap_toc_processor {
             None
         };
 
-        if ctx.accounts.primary_oracle.key() != args.primary_oracle_account {
-            return err!(ErrorCode::InvalidOraclePrice);
+        if args.use_dynamic_plan {
+            // Sortie implicite du mid oracle: référence du price improvement de chaque venue
+            // (sans mints, décimales non vérifiées: aucun score mis à jour)
+            let oracle_expected_out = mint_decimals
+                .map(|(decimals_in, decimals_out)| {
+                    calculate_expected_output_with_decimals(
+                        args.amount_in,
+                        oracle_observation.price,
+                        decimals_in,
+                        decimals_out,
+                    )
+                })
+                .transpose()?;
+            return process_dynamic_plan_swap(&mut ctx, args, oracle_expected_out, &clock);
         }
 
-        let fallback_account = match (
-            args.fallback_oracle_account,
-            ctx.accounts.fallback_oracle.as_ref(),
-        ) {
-            (Some(expected_key), Some(account)) => {
-                if account.key() != expected_key {
-                    return err!(ErrorCode::InvalidOraclePrice);
-                }
-                Some(account)
-            }
-            (Some(_), None) => return err!(ErrorCode::MissingFallbackOracle),
-            (None, Some(account)) => Some(account),
-            (None, None) => None,
+        // Sanctum routes: the stake pool exchange rate is the expected price,
+        // the USD oracle only bounds it
+        let expected_price = if args.direct_dex_venue == Some(SANCTUM_PROGRAM_ID) {
+            lst_expected_price(&ctx, oracle_observation.price, &clock)?
+        } else {
+            oracle_observation.price
         };
 
-        if args.use_dynamic_plan {
-            return process_dynamic_plan_swap(&mut ctx, args, &clock);
-        }
-
-        let oracle_observation =
-            get_oracle_price(&ctx.accounts.primary_oracle, fallback_account, &clock, args.max_staleness_override)?;
-        
         // Calculate expected output with proper decimal handling
         // Oracle price is in 8 decimals (e.g., $130.95 = 13_095_000_000)
         // We need to convert from token_a_decimals to token_b_decimals
-        let token_a_decimals = args.token_a_decimals.unwrap_or(6);
-        let token_b_decimals = args.token_b_decimals.unwrap_or(6);
-        let expected_out = calculate_expected_output_with_decimals(
-            args.amount_in,
-            oracle_observation.price,
+        // Transfer fees Token-2022: la venue reçoit l'input net, l'utilisateur l'output net
+        let input_mint = ctx.accounts.input_mint.as_ref().map(|m| m.to_account_info());
+        let output_mint = ctx.accounts.output_mint.as_ref().map(|m| m.to_account_info());
+        let net_amount_in =
+            token_extensions::amount_after_fee(input_mint.as_ref(), clock.epoch, args.amount_in)?;
+        let gross_expected_out = calculate_expected_output_with_decimals(
+            net_amount_in,
+            expected_price,
             token_a_decimals,
             token_b_decimals,
         )?;
+        let expected_out =
+            token_extensions::amount_after_fee(output_mint.as_ref(), clock.epoch, gross_expected_out)?;
 
         // Calculate min_out: Use dynamic slippage if enabled, else user-provided tolerance
         let min_out = if let Some(dynamic_slippage) = slippage_bps_effective {
@@ -1872,48 +3457,135 @@ pub mod swap_toc_processor {
             }
         }
 
+        // Cotation de la venue avant son CPI: référence des échecs imputables à la venue
+        let quoted_out = args
+            .direct_dex_venue
+            .filter(|venue| *venue != RFQ_VENUE_ID)
+            .and_then(|venue| {
+                let accounts = direct_venue_accounts(&ctx, &venue).ok()?;
+                pre_trade_quote(&ctx, &venue, accounts, args.amount_in, &clock)
+            });
+
         // Execute real swap via native DEX or Jupiter fallback
-        let start_time = clock.unix_timestamp;
-        let amount_out = process_single_swap(
+        let result = process_single_swap(
             &mut ctx,
             args.amount_in,
             min_out,
             args.direct_dex_venue,
+            args.rfq.as_ref(),
             args.jupiter_route.as_ref(),
         )?;
 
-        // Calculate actual metrics for VenueScore
-        let end_time = Clock::get()?.unix_timestamp;
-        let latency_ms = ((end_time - start_time) * 1000).max(1) as u32;
+        // Update Venue Score with real metrics (score of the executed venue/pool, by PDA):
+        // sortie brute (avant frais plateforme) vs cotation de la venue et mid oracle,
+        // ce dernier seulement s'il est calculé avec les décimales des mints
+        if let Some(venue) = args.direct_dex_venue {
+            let remaining_accounts = ctx.remaining_accounts;
+            score_fill(
+                &mut ctx,
+                &venue,
+                remaining_accounts,
+                (args.amount_in, result.amount_out),
+                quoted_out,
+                mint_decimals.map(|_| expected_out),
+                &clock,
+            )?;
+        }
 
-        // Calculate actual slippage: (expected - actual) / expected * 10000
-        let actual_slippage_bps = if expected_out > 0 && amount_out < expected_out {
-            ((expected_out - amount_out) as u128 * 10_000 / expected_out as u128) as u16
-        } else {
-            0
-        };
+        Ok(result)
+    }
 
-        // Calculate NPI (Net Positive Impact) = amount_out - min_out (if positive)
-        let npi = if amount_out > min_out {
-            (amount_out - min_out) as i64
-        } else {
-            0
+    /// Paramètres des contrôles communs à `swap_toc` et `swap_exact_out`
+    struct PreSwapGuard<'a> {
+        primary_oracle_account: Pubkey,
+        fallback_oracle_account: Option<Pubkey>,
+        max_staleness_override: Option<i64>,
+        sandwich_guard: bool,
+        jito_bundle: Option<&'a JitoBundleConfig>,
+    }
+
+    /// Contrôles avant tout swap (exact-in comme exact-out): garde anti-sandwich,
+    /// disjoncteur de la paire, lecture de l'oracle et saut de prix
+    fn pre_swap_checks(
+        ctx: &mut Context<SwapToC>,
+        guard: PreSwapGuard,
+        clock: &Clock,
+    ) -> Result<OracleObservation> {
+        // ✅ SECURITY: MEV protection enforced on-chain (same-tx pool writes, Jito tip)
+        if guard.sandwich_guard {
+            mev_guard::enforce(ctx, guard.jito_bundle)?;
+        }
+
+        // --- Phase 2: Oracle Cache Check ---
+        if ctx.accounts.oracle_cache.is_stale(clock.unix_timestamp) {
+            msg!("Warning: Oracle cache is stale");
+        }
+
+        // ✅ SECURITY: Per-pair circuit breaker
+        require!(
+            !ctx.accounts.oracle_cache.is_halted(clock.unix_timestamp),
+            ErrorCode::PairTradingHalted
+        );
+
+        if ctx.accounts.primary_oracle.key() != guard.primary_oracle_account
+            || ctx.accounts.primary_oracle.key() != ctx.accounts.oracle_cache.primary_oracle
+        {
+            return err!(ErrorCode::InvalidOraclePrice);
+        }
+
+        // La cache (donc le disjoncteur) doit être celle de la paire échangée
+        let (input_mint, output_mint) = token_extensions::swap_mints(ctx)?;
+        require!(
+            ctx.accounts.oracle_cache.covers(&input_mint, &output_mint),
+            ErrorCode::OracleCachePairMismatch
+        );
+
+        let fallback_account = match (
+            guard.fallback_oracle_account,
+            ctx.accounts.fallback_oracle.as_ref(),
+        ) {
+            (Some(expected_key), Some(account)) => {
+                if account.key() != expected_key {
+                    return err!(ErrorCode::InvalidOraclePrice);
+                }
+                Some(account)
+            }
+            (Some(_), None) => return err!(ErrorCode::MissingFallbackOracle),
+            (None, Some(account)) => Some(account),
+            (None, None) => None,
         };
 
-        // Update Venue Score with real metrics
-        if let Some(venue_score) = &mut ctx.accounts.venue_score {
-            venue_score.update_stats(args.amount_in, npi, latency_ms, actual_slippage_bps, &clock);
+        let oracle_observation =
+            get_oracle_price(
+                &ctx.accounts.primary_oracle,
+                fallback_account,
+                clock,
+                guard.max_staleness_override,
+                &PythUpdatePolicy::from_state(&ctx.accounts.state),
+            )?;
+
+        // --- Circuit breaker: a price jump within the window rejects the swap ---
+        // Observed on every path (dynamic plans and exact-out included). The error rolls
+        // this transaction back: the halt itself is persisted by refresh_oracle_cache.
+        if let Some(move_bps) = ctx
+            .accounts
+            .oracle_cache
+            .observe_price(oracle_observation.price, clock.unix_timestamp)
+        {
+            msg!("⛔ Oracle price moved {} bps within the window", move_bps);
+            return err!(ErrorCode::OraclePriceJump);
         }
 
-        Ok(())
+        Ok(oracle_observation)
     }
 
     fn process_dynamic_plan_swap(
         ctx: &mut Context<SwapToC>,
         args: SwapArgs,
+        oracle_expected_out: Option<u64>,
         clock: &Clock,
-    ) -> Result<()> {
-        let (plan_user, plan_amount_in, plan_min_out, mut plan_venues, plan_fallbacks, plan_expires_at) = {
+    ) -> Result<SwapResult> {
+        let (plan_user, plan_amount_in, plan_min_out, mut plan_venues, plan_fallbacks, plan_expires_at, plan_max_slot) = {
             let plan = ctx
                 .accounts
                 .plan
@@ -1926,6 +3598,7 @@ pub mod swap_toc_processor {
                 plan.venues.clone(),
                 plan.fallback_plans.clone(),
                 plan.expires_at,
+                plan.max_slot,
             )
         };
 
@@ -1942,7 +3615,8 @@ pub mod swap_toc_processor {
             });
         }
 
-        emit_priority_fee_hint(args.amount_in, plan_venues.len(), plan_fallbacks.len());
+        let has_clob = plan_venues.iter().any(|v| is_clob_venue(&v.venue));
+        emit_priority_fee_hint(args.amount_in, plan_venues.len(), plan_fallbacks.len(), has_clob);
 
         if plan_venues.len() > MAX_VENUES {
             return err!(ErrorCode::InvalidPlanWeights);
@@ -1956,6 +3630,9 @@ pub mod swap_toc_processor {
         if clock.unix_timestamp > plan_expires_at {
             return err!(ErrorCode::PlanExpired);
         }
+        if plan_max_slot > 0 && clock.slot > plan_max_slot {
+            return err!(ErrorCode::SwapSlotExceeded);
+        }
         if plan_amount_in != args.amount_in {
             return err!(ErrorCode::PlanAmountMismatch);
         }
@@ -1972,59 +3649,78 @@ pub mod swap_toc_processor {
             }
         }
 
+        // Les routes LST sont cotées au taux du stake pool, seulement en venue directe
+        let has_sanctum = plan_venues
+            .iter()
+            .chain(plan_fallbacks.iter().flat_map(|plan| plan.venues.iter()))
+            .any(|v| v.venue == SANCTUM_PROGRAM_ID);
+        require!(!has_sanctum, ErrorCode::LstRouteRequiresDirectVenue);
+
         // Ajuster les poids des venues en fonction des VenueScores (si disponibles)
-        let scores = parse_venue_scores_by_pubkey(ctx.remaining_accounts);
+        // Les venues en quarantaine ont un score de 0 et sont exclues
+        let mut scores = parse_venue_scores_by_pubkey(ctx.remaining_accounts, clock.unix_timestamp);
+        if let Some(venue_score) = &ctx.accounts.venue_score {
+            if venue_score.is_quarantined(clock.unix_timestamp) {
+                scores.insert(venue_score.venue, 0);
+            }
+        }
         if !scores.is_empty() {
             let min_score = args.min_venue_score.unwrap_or(MIN_QUALITY_SCORE_DEFAULT);
             adjust_venue_weights_with_scores(&mut plan_venues, &scores, min_score);
         }
 
-        let primary_result = execute_venues_swap(
-            ctx,
-            &plan_venues,
-            args.amount_in,
-            plan_min_out,
-            ctx.remaining_accounts,
-            false,
-            args.jupiter_route.as_ref(),
-            args.slippage_per_venue.as_deref(),
-        );
-
-        let mut failure_reason = match primary_result {
-            Ok(amount_out) if amount_out >= plan_min_out => return Ok(()),
-            Ok(_) => String::from("Primary venues slippage"),
-            Err(_) => String::from("Primary venues execution failure"),
-        };
-
-        for (index, fallback_plan) in plan_fallbacks.iter().enumerate() {
-            emit!(FallbackTriggered {
-                plan_index: index as u8,
-                reason: failure_reason.clone(),
-            });
-
-            let fallback_result = execute_venues_swap(
-                ctx,
-                &fallback_plan.venues,
-                args.amount_in,
-                fallback_plan.min_out,
+        // Sélection pré-vol: un CPI en échec annule toute la transaction, donc le plan
+        // (principal puis fallbacks) est choisi sur l'état décodé des pools avant tout CPI
+        let (input_mint, _) = token_extensions::swap_mints(ctx)?;
+        let candidates = std::iter::once((&plan_venues, plan_min_out))
+            .chain(plan_fallbacks.iter().map(|plan| (&plan.venues, plan.min_out)));
+        let mut selected = None;
+        let mut first_unestimated = None;
+        for (index, (venues, min_out)) in candidates.enumerate() {
+            let shortfall = quote::plan_shortfall(
+                &ctx.accounts.venue_registry,
                 ctx.remaining_accounts,
-                true,
-                args.jupiter_route.as_ref(),
-                args.slippage_per_venue.as_deref(),
+                venues,
+                &input_mint,
+                args.amount_in,
+                min_out,
+                clock,
             );
-
-            match fallback_result {
-                Ok(amount_out) if amount_out >= fallback_plan.min_out => return Ok(()),
-                Ok(_) => {
-                    failure_reason = String::from("Fallback slippage");
+            let reason = match shortfall {
+                None => {
+                    selected = Some((index, venues, min_out));
+                    break;
                 }
-                Err(_) => {
-                    failure_reason = String::from("Fallback execution failure");
+                // Viabilité inconnue: retenu seulement si aucun plan entièrement coté ne passe
+                Some(quote::Shortfall::Unestimated(venue)) => {
+                    first_unestimated.get_or_insert((index, venues, min_out));
+                    format!("{} has no estimator", venue)
                 }
+                // min_out choisi par l'utilisateur: jamais compté comme un échec des venues
+                Some(quote::Shortfall::BelowMinOut(reason)) => reason,
+            };
+            msg!("⚠️ Plan {} skipped: {}", index, reason);
+            if index < plan_fallbacks.len() {
+                emit!(FallbackTriggered {
+                    plan_index: index as u8,
+                    reason,
+                });
             }
         }
 
-        err!(ErrorCode::SlippageExceeded)
+        // Aucun plan entièrement coté n'est viable: premier plan non estimable,
+        // son min_out reste imposé à l'exécution (échec du CPI = transaction annulée)
+        let selected = selected.or(first_unestimated);
+        let (index, venues, min_out) = selected.ok_or(ErrorCode::SlippageExceeded)?;
+        execute_venues_swap(
+            ctx,
+            venues,
+            args.amount_in,
+            min_out,
+            oracle_expected_out,
+            index > 0,
+            args.slippage_per_venue.as_deref(),
+        )
     }
 
     fn execute_venues_swap(
@@ -2032,13 +3728,16 @@ pub mod swap_toc_processor {
         venues: &[VenueWeight],
         total_amount_in: u64,
         min_out: u64,
-        remaining_accounts: &[AccountInfo],
+        oracle_expected_out: Option<u64>,
         is_fallback: bool,
-        jupiter_route: Option<&JupiterRouteParams>,
         venue_slippage: Option<&[VenueSlippage]>,
-    ) -> Result<u64> {
+    ) -> Result<SwapResult> {
+        let clock = Clock::get()?;
+        let remaining_accounts = ctx.remaining_accounts;
+        let mut total_spent: u64 = 0;
         let mut total_amount_out: u64 = 0;
         let mut account_cursor: usize = 0;
+        let mut venues_used = Vec::with_capacity(venues.len());
 
         for (venue_index, venue_weight) in venues.iter().enumerate() {
             // Jupiter est interdit tant que le routeur natif n'est pas explicitement validé.
@@ -2047,6 +3746,12 @@ pub mod swap_toc_processor {
                 ErrorCode::DexNotImplemented
             );
 
+            if venues.len() > 1 {
+                ctx.accounts
+                    .venue_registry
+                    .check_split_share(&venue_weight.venue, venue_weight.weight)?;
+            }
+
             let amount_in = (total_amount_in as u128)
                 .checked_mul(venue_weight.weight as u128)
                 .ok_or(ErrorCode::InvalidOraclePrice)?
@@ -2093,7 +3798,7 @@ pub mod swap_toc_processor {
             let required_accounts = if venue_weight.venue == METEORA_DLMM_PROGRAM_ID {
                 let remaining_for_venue = remaining_accounts.len().saturating_sub(account_cursor);
                 require!(
-                    remaining_for_venue >= cpi_meteora::METEORA_SWAP_ACCOUNT_COUNT,
+                    remaining_for_venue >= registered_account_len(ctx, &venue_weight.venue)?,
                     ErrorCode::DexExecutionFailed
                 );
                 require!(
@@ -2102,13 +3807,9 @@ pub mod swap_toc_processor {
                 );
                 remaining_for_venue
             } else {
-                required_account_len_for_dex(&venue_weight.venue)?
+                registered_account_len(ctx, &venue_weight.venue)?
             };
-            if account_cursor
-                .checked_add(required_accounts)
-                .unwrap_or(usize::MAX)
-                > remaining_accounts.len()
-            {
+            if account_cursor.saturating_add(required_accounts) > remaining_accounts.len() {
                 emit!(VenueExecuted {
                     venue: venue_weight.venue,
                     amount_in,
@@ -2123,19 +3824,38 @@ pub mod swap_toc_processor {
                 &remaining_accounts[account_cursor..account_cursor + required_accounts];
             account_cursor += required_accounts;
 
-            let amount_out = execute_dex_swap(
+            let quoted_out = pre_trade_quote(ctx, &venue_weight.venue, account_slice, amount_in, &clock);
+            let delta = execute_dex_swap(
                 ctx,
                 venue_weight.venue,
                 amount_in,
                 min_out_per_venue,
                 account_slice,
                 is_fallback,
-                jupiter_route,
+                None, // Jupiter interdit dans les plans (voir plus haut)
             )?;
 
+            total_spent = total_spent
+                .checked_add(delta.spent)
+                .ok_or(ErrorCode::SlippageExceeded)?;
             total_amount_out = total_amount_out
-                .checked_add(amount_out)
+                .checked_add(delta.received)
                 .ok_or(ErrorCode::SlippageExceeded)?;
+            venues_used.push(venue_weight.venue);
+
+            // Chaque venue du split met à jour son propre score (retrouvé par PDA)
+            let venue_expected_out = oracle_expected_out.map(|oracle_expected_out| {
+                (oracle_expected_out as u128 * venue_weight.weight as u128 / 10_000) as u64
+            });
+            score_fill(
+                ctx,
+                &venue_weight.venue,
+                account_slice,
+                (delta.spent, delta.received),
+                quoted_out,
+                venue_expected_out,
+                &clock,
+            )?;
         }
 
         if total_amount_out < min_out {
@@ -2230,186 +3950,357 @@ pub mod swap_toc_processor {
             timestamp: now,
         });
 
-        // Deposit to buyback if accounts are provided and amount > 0
-        if buyburn_fee_allocation > 0
-            && ctx.accounts.buyback_program.is_some()
-            && ctx.accounts.buyback_usdc_vault.is_some()
-            && ctx.accounts.buyback_state.is_some()
-        {
-            deposit_to_buyback(ctx, buyburn_fee_allocation)?;
+        // Deposit to buyback if accounts are provided and amount > 0
+        if buyburn_fee_allocation > 0
+            && ctx.accounts.buyback_program.is_some()
+            && ctx.accounts.buyback_usdc_vault.is_some()
+            && ctx.accounts.buyback_state.is_some()
+        {
+            deposit_to_buyback(ctx, buyburn_fee_allocation)?;
+
+            emit!(SwapCompleted {
+                user: ctx.accounts.user.key(),
+                amount_in: total_spent,
+                amount_out: total_amount_out,
+                platform_fee,
+                routing_profit,
+                buyburn_deposit: buyburn_fee_allocation,
+                user_boost,
+                rebate_amount: total_rebate,
+            });
+        }
+
+        Ok(SwapResult {
+            amount_in: total_spent,
+            amount_out: total_amount_out,
+            platform_fee,
+            rebate_credited: total_rebate,
+            venues: venues_used,
+        })
+    }
+
+    fn execute_dex_swap(
+        ctx: &Context<SwapToC>,
+        dex_program: Pubkey,
+        amount_in: u64,
+        min_out: u64,
+        account_slice: &[AccountInfo],
+        is_fallback: bool,
+        _jupiter_route: Option<&JupiterRouteParams>,
+    ) -> Result<balance_delta::BalanceDelta> {
+        // Governance gate: the venue must be registered, enabled and within its
+        // notional cap, and none of its accounts a quarantined pool
+        ctx.accounts.venue_registry.check_execution(&dex_program, amount_in)?;
+        ctx.accounts
+            .venue_registry
+            .check_pools(&dex_program, account_slice, Clock::get()?.unix_timestamp)?;
+
+        // Pool, vaults et comptes auxiliaires doivent appartenir à la venue
+        pool_validation::validate_venue_accounts(&dex_program, account_slice)?;
+
+        // Jupiter est STRICTEMENT désactivé tant que le routeur natif n'est pas validé
+        // (aucun adaptateur). NOTE: on garde les types/IDL pour compat.
+        let Some(adapter) = venue_adapter(&dex_program) else {
+            emit!(VenueExecuted {
+                venue: dex_program,
+                amount_in,
+                amount_out: 0,
+                success: false,
+                fallback_used: is_fallback,
+            });
+            return err!(ErrorCode::DexNotImplemented);
+        };
+
+        // min_out s'applique au montant net crédité; la venue raisonne en brut
+        // (output avant transfer fee Token-2022)
+        let output_mint = ctx.accounts.output_mint.as_ref().map(|m| m.to_account_info());
+        let venue_min_out =
+            token_extensions::amount_before_fee(output_mint.as_ref(), Clock::get()?.epoch, min_out)?;
+
+        // Les adaptateurs ne font qu'invoquer la venue; les deltas de solde sont
+        // mesurés et vérifiés ici pour toutes les venues.
+        let source = ctx.accounts.user_token_account_a.to_account_info();
+        let destination = ctx.accounts.user_token_account_b.to_account_info();
+        let delta = balance_delta::with_balance_deltas(
+            &source,
+            &destination,
+            amount_in,
+            min_out,
+            || (adapter.swap)(ctx, account_slice, amount_in, venue_min_out),
+        )?;
+
+        emit!(VenueExecuted {
+            venue: dex_program,
+            amount_in: delta.spent,
+            amount_out: delta.received,
+            success: true,
+            fallback_used: is_fallback,
+        });
+
+        // Fill partiel: l'input non consommé reste sur le compte source de l'utilisateur
+        if delta.unspent > 0 {
+            msg!("⚠️ Partial fill on {}: {} input unspent", dex_program, delta.unspent);
+            emit!(PartialFill {
+                venue: dex_program,
+                amount_in,
+                spent: delta.spent,
+                unspent: delta.unspent,
+            });
+        }
+
+        msg!("✅ Venue swap: spent {}, received {}", delta.spent, delta.received);
+        Ok(delta)
+    }
+
+    /// CPI account count for a venue, as configured in the venue registry
+    fn registered_account_len(ctx: &Context<SwapToC>, program_id: &Pubkey) -> Result<usize> {
+        Ok(ctx.accounts.venue_registry.enabled_entry(program_id)?.account_count as usize)
+    }
+
+    /// Minimum CPI account count a registry entry may declare (0 = not executable):
+    /// the venue's adapter, or the RFQ settlement accounts
+    pub(crate) fn min_account_count(program_id: &Pubkey) -> Result<usize> {
+        if let Some(adapter) = venue_adapter(program_id) {
+            Ok(adapter.min_accounts)
+        } else if *program_id == RFQ_VENUE_ID {
+            Ok(rfq::RFQ_ACCOUNT_COUNT)
+        } else if *program_id == JUPITER_PROGRAM_ID {
+            // Jupiter est désactivé: ne pas permettre le slicing de comptes.
+            err!(ErrorCode::DexNotImplemented)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn get_oracle_price<'info>(
+        primary_oracle: &AccountInfo<'info>,
+        fallback_oracle: Option<&AccountInfo<'info>>,
+        clock: &Clock,
+        max_staleness_override: Option<i64>,
+        pyth_policy: &PythUpdatePolicy,
+    ) -> Result<OracleObservation> {
+        let (observation, divergence_bps) =
+            read_oracle_pair(primary_oracle, fallback_oracle, clock, max_staleness_override, pyth_policy)?;
+
+        if let Some(divergence_bps) = divergence_bps {
+            if divergence_bps > MAX_ORACLE_DIVERGENCE_BPS as u128 {
+                return err!(ErrorCode::OracleDivergenceTooHigh);
+            }
+        }
+
+        emit!(OracleChecked {
+            feed: observation.feed,
+            price: observation.price,
+            confidence: observation.confidence,
+            slot: observation.slot,
+            timestamp: observation.publish_time,
+            oracle_type: observation.oracle_type,
+        });
+
+        Ok(observation)
+    }
 
-            emit!(SwapCompleted {
-                user: ctx.accounts.user.key(),
-                amount_in: total_amount_in,
-                amount_out: total_amount_out,
-                platform_fee,
-                routing_profit,
-                buyburn_deposit: buyburn_fee_allocation,
-                user_boost,
-                rebate_amount: total_rebate,
-            });
+    /// Expected LST price for a Sanctum route (out per in, 8 decimals).
+    /// Rate accounts follow the Sanctum CPI accounts (registry `account_count`) in
+    /// remaining_accounts and are required: a missing or stale rate fails the swap
+    /// instead of using the oracle.
+    fn lst_expected_price(ctx: &Context<SwapToC>, oracle_price: u64, clock: &Clock) -> Result<u64> {
+        let remaining_accounts = ctx.remaining_accounts;
+        // Même découpage que direct_venue_accounts
+        let sanctum_account_count = registered_account_len(ctx, &SANCTUM_PROGRAM_ID)?;
+        require!(
+            remaining_accounts.len() >= sanctum_account_count,
+            ErrorCode::LstRateUnavailable
+        );
+        let (sanctum_accounts, rate_accounts) = remaining_accounts.split_at(sanctum_account_count);
+
+        // Taux absent ou périmé: erreur, jamais de repli silencieux sur l'oracle
+        let (mint_in, mint_out) = token_extensions::swap_mints(ctx)?;
+        let lst_price =
+            lst_pricing::read_sanctum_price(sanctum_accounts, rate_accounts, &mint_in, &mint_out, clock)?;
+
+        let deviation_bps = oracle_cache::price_move_bps(lst_price, oracle_price);
+        if deviation_bps > lst_pricing::MAX_LST_ORACLE_DEVIATION_BPS {
+            msg!(
+                "❌ LST rate {} vs oracle {}: {} bps deviation (max: {})",
+                lst_price,
+                oracle_price,
+                deviation_bps,
+                lst_pricing::MAX_LST_ORACLE_DEVIATION_BPS
+            );
+            return err!(ErrorCode::LstRateOracleMismatch);
         }
 
-        Ok(total_amount_out)
+        msg!("🥩 LST exchange rate price: {} (oracle: {})", lst_price, oracle_price);
+        Ok(lst_price)
     }
 
-    fn execute_dex_swap(
+    /// Net output quoted for a venue from its pool state before the CPI
+    /// (None: no estimator or the quote failed)
+    fn pre_trade_quote(
         ctx: &Context<SwapToC>,
-        dex_program: Pubkey,
+        venue: &Pubkey,
+        accounts: &[AccountInfo],
         amount_in: u64,
-        min_out: u64,
-        account_slice: &[AccountInfo],
-        is_fallback: bool,
-        _jupiter_route: Option<&JupiterRouteParams>,
-    ) -> Result<u64> {
-        match dex_program {
-            RAYDIUM_AMM_PROGRAM_ID => {
-                let amount_out = cpi_raydium::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            ORCA_WHIRLPOOL_PROGRAM_ID => {
-                let amount_out = cpi_orca::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            // Jupiter est STRICTEMENT désactivé tant que le routeur natif n'est pas validé.
-            // NOTE: on garde les types/IDL pour compat, mais on refuse l'exécution.
-            JUPITER_PROGRAM_ID => {
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out: 0,
-                    success: false,
-                    fallback_used: is_fallback,
-                });
-                err!(ErrorCode::DexNotImplemented)
-            }
-            // ============================================
-            // NEW DEX INTEGRATIONS (Dec 2025)
-            // ============================================
-            RAYDIUM_CLMM_PROGRAM_ID => {
-                let amount_out = cpi_raydium_clmm::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            METEORA_DLMM_PROGRAM_ID => {
-                let amount_out = cpi_meteora::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            PHOENIX_PROGRAM_ID => {
-                let amount_out = cpi_phoenix::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            LIFINITY_PROGRAM_ID => {
-                let amount_out = cpi_lifinity::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            SANCTUM_PROGRAM_ID => {
-                let amount_out = cpi_sanctum::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            SABER_PROGRAM_ID => {
-                let amount_out = cpi_saber::swap(ctx, account_slice, amount_in, min_out)?;
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out,
-                    success: true,
-                    fallback_used: is_fallback,
-                });
-                Ok(amount_out)
-            }
-            _ => {
-                emit!(VenueExecuted {
-                    venue: dex_program,
-                    amount_in,
-                    amount_out: 0,
-                    success: false,
-                    fallback_used: is_fallback,
-                });
-                err!(ErrorCode::DexNotImplemented)
+        clock: &Clock,
+    ) -> Option<u64> {
+        let (input_mint, _) = token_extensions::swap_mints(ctx).ok()?;
+        let input_mint_info = ctx.accounts.input_mint.as_ref().map(|m| m.to_account_info());
+        let output_mint_info = ctx.accounts.output_mint.as_ref().map(|m| m.to_account_info());
+        let net_amount_in =
+            token_extensions::amount_after_fee(input_mint_info.as_ref(), clock.epoch, amount_in).ok()?;
+        let gross_out = quote::venue_estimate(venue, accounts, &input_mint, net_amount_in, clock).ok()??;
+        token_extensions::amount_after_fee(output_mint_info.as_ref(), clock.epoch, gross_out).ok()
+    }
+
+    /// Record an executed fill on the venue's score: below its own quote it counts
+    /// as a venue failure; otherwise its deviation from the oracle mid, when known
+    fn score_fill(
+        ctx: &mut Context<SwapToC>,
+        venue: &Pubkey,
+        accounts: &[AccountInfo],
+        (spent, received): (u64, u64),
+        quoted_out: Option<u64>,
+        oracle_expected_out: Option<u64>,
+        clock: &Clock,
+    ) -> Result<()> {
+        if let Some(quoted_out) = quoted_out.filter(|quoted_out| venue_scoring::fill_below_quote(received, *quoted_out)) {
+            msg!("⚠️ Venue {} filled {} below its quote {}", venue, received, quoted_out);
+            return update_venue_score(ctx, venue, accounts, "fill_below_quote", clock, |score| {
+                score.record_failure(clock)
+            });
+        }
+        let Some(oracle_expected_out) = oracle_expected_out else {
+            return Ok(());
+        };
+        let price_improvement_bps = venue_scoring::price_improvement_bps(received, oracle_expected_out);
+        update_venue_score(ctx, venue, accounts, "adverse_fill", clock, |score| {
+            score.update_stats(spent, price_improvement_bps, clock)
+        })
+    }
+
+    /// Apply `update` to the VenueScore of an executed venue, found by PDA among
+    /// `venue_score` and remaining_accounts (no score passed: nothing to record).
+    /// `update` returns true when the score's breaker trips.
+    fn update_venue_score(
+        ctx: &mut Context<SwapToC>,
+        venue: &Pubkey,
+        accounts: &[AccountInfo],
+        reason: &str,
+        clock: &Clock,
+        update: impl FnOnce(&mut venue_scoring::VenueScore) -> bool,
+    ) -> Result<()> {
+        // Compte typé: son PDA est déjà vérifié par les seeds de SwapToC
+        if let Some(venue_score) = ctx.accounts.venue_score.as_mut() {
+            if venue_scoring::tracks_execution(venue_score, venue, accounts) {
+                if update(venue_score) {
+                    quarantine_score(&mut ctx.accounts.venue_registry, venue_score, reason, clock.unix_timestamp);
+                }
+                return Ok(());
             }
         }
+
+        let remaining_accounts = ctx.remaining_accounts;
+        let Some((info, mut score)) = venue_scoring::find_venue_score(remaining_accounts, venue, accounts) else {
+            return Ok(());
+        };
+        if update(&mut score) {
+            quarantine_score(&mut ctx.accounts.venue_registry, &score, reason, clock.unix_timestamp);
+        }
+        score.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
     }
 
-    fn required_account_len_for_dex(program_id: &Pubkey) -> Result<usize> {
-        if *program_id == ORCA_WHIRLPOOL_PROGRAM_ID {
-            Ok(cpi_orca::ORCA_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == RAYDIUM_AMM_PROGRAM_ID {
-            Ok(cpi_raydium::RAYDIUM_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == RAYDIUM_CLMM_PROGRAM_ID {
-            Ok(cpi_raydium_clmm::RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == METEORA_DLMM_PROGRAM_ID {
-            Ok(cpi_meteora::METEORA_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == PHOENIX_PROGRAM_ID {
-            Ok(cpi_phoenix::PHOENIX_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == LIFINITY_PROGRAM_ID {
-            Ok(cpi_lifinity::LIFINITY_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == SANCTUM_PROGRAM_ID {
-            Ok(cpi_sanctum::SANCTUM_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == SABER_PROGRAM_ID {
-            Ok(cpi_saber::SABER_SWAP_ACCOUNT_COUNT)
-        } else if *program_id == JUPITER_PROGRAM_ID {
-            // Jupiter est désactivé: ne pas permettre le slicing de comptes.
-            err!(ErrorCode::DexNotImplemented)
-        } else {
-            Ok(0)
+    /// Once a venue score's breaker trips: a pool score quarantines its pool in the
+    /// registry (enforced on every swap path); a venue-wide score only excludes
+    /// itself, blocking the whole program stays an authority decision (`set_venue_enabled`)
+    fn quarantine_score(
+        registry: &mut VenueRegistry,
+        venue_score: &venue_scoring::VenueScore,
+        reason: &str,
+        now: i64,
+    ) {
+        if venue_score.pool != Pubkey::default() {
+            registry.quarantine_pool(&venue_score.venue, &venue_score.pool, venue_score.quarantined_until, now);
+        }
+        msg!(
+            "🧯 Venue {} quarantined until {} ({})",
+            venue_score.venue,
+            venue_score.quarantined_until,
+            reason
+        );
+        emit!(VenueQuarantined {
+            venue: venue_score.venue,
+            pool: venue_score.pool,
+            reason: reason.to_string(),
+            quarantined_until: venue_score.quarantined_until,
+            timestamp: now,
+        });
+    }
+
+    /// Realloc a program account in place, the payer topping up the rent
+    pub fn grow_account<'info>(
+        account: &AccountInfo<'info>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        new_len: usize,
+    ) -> Result<()> {
+        let missing_rent = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(account.lamports());
+        if missing_rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                missing_rent,
+            )?;
         }
+        account.realloc(new_len, false)?;
+        Ok(())
     }
 
-    fn get_oracle_price<'info>(
+    /// Halt a pair through its OracleCache and emit PairHalted
+    pub fn halt_pair(
+        cache: &mut oracle_cache::OracleCache,
+        reason: &str,
+        move_bps: u64,
+        now: i64,
+    ) {
+        cache.halt(now);
+
+        emit!(PairHalted {
+            oracle: cache.primary_oracle,
+            token_pair: cache.token_pair,
+            reason: reason.to_string(),
+            move_bps,
+            halted_until: cache.halted_until,
+            timestamp: now,
+        });
+
+        msg!(
+            "⛔ Pair {}/{} halted until {} ({}, move {} bps)",
+            cache.token_pair[0],
+            cache.token_pair[1],
+            cache.halted_until,
+            reason,
+            move_bps
+        );
+    }
+
+    /// Read primary and optional fallback oracles.
+    /// Returns the freshest observation and, when both feeds answered,
+    /// their divergence in bps (the caller decides what to do with it).
+    pub fn read_oracle_pair<'info>(
         primary_oracle: &AccountInfo<'info>,
         fallback_oracle: Option<&AccountInfo<'info>>,
         clock: &Clock,
         max_staleness_override: Option<i64>,
-    ) -> Result<OracleObservation> {
+        pyth_policy: &PythUpdatePolicy,
+    ) -> Result<(OracleObservation, Option<u128>)> {
         // Use override if provided, otherwise use default MAX_STALENESS_SECS
         // Clamp to safe range: MIN_STALENESS_SECS <= value <= MAX_STALENESS_SECS
         let effective_staleness = match max_staleness_override {
@@ -2427,9 +4318,9 @@ pub mod swap_toc_processor {
             None => MAX_STALENESS_SECS,
         };
 
-        let primary_result = oracle::read_price_with_staleness(primary_oracle, clock, effective_staleness);
+        let primary_result = oracle::read_price_with_staleness(primary_oracle, clock, effective_staleness, pyth_policy);
         let fallback_observation =
-            fallback_oracle.and_then(|account| match oracle::read_price_with_staleness(account, clock, effective_staleness) {
+            fallback_oracle.and_then(|account| match oracle::read_price_with_staleness(account, clock, effective_staleness, pyth_policy) {
                 Ok(observation) => Some(observation),
                 Err(_) => {
                     msg!("⚠️ Fallback oracle read failed");
@@ -2451,36 +4342,24 @@ pub mod swap_toc_processor {
                     .and_then(|value| value.checked_div(low))
                     .ok_or(ErrorCode::InvalidOraclePrice)?;
 
-                if divergence_bps > MAX_ORACLE_DIVERGENCE_BPS as u128 {
-                    return err!(ErrorCode::OracleDivergenceTooHigh);
-                }
-
-                if primary.publish_time >= fallback.publish_time {
+                let freshest = if primary.publish_time >= fallback.publish_time {
                     primary
                 } else {
                     fallback
-                }
+                };
+                (freshest, Some(divergence_bps))
             }
-            (Ok(primary), None) => primary,
+            (Ok(primary), None) => (primary, None),
             (Err(_), Some(fallback)) => {
                 msg!(
                     "⚠️ Primary oracle unavailable, using fallback feed {}",
                     fallback.feed
                 );
-                fallback
+                (fallback, None)
             }
             (Err(err), None) => return Err(err),
         };
 
-        emit!(OracleChecked {
-            feed: observation.feed,
-            price: observation.price,
-            confidence: observation.confidence,
-            slot: observation.slot,
-            timestamp: observation.publish_time,
-            oracle_type: observation.oracle_type,
-        });
-
         Ok(observation)
     }
 
@@ -2544,12 +4423,12 @@ pub mod swap_toc_processor {
         priority_fee.clamp(5_000, 1_000_000)
     }
     
-    fn emit_priority_fee_hint(amount_in: u64, venue_count: usize, fallback_count: usize) {
+    fn emit_priority_fee_hint(amount_in: u64, venue_count: usize, fallback_count: usize, has_clob: bool) {
         // Use dynamic estimation
         let cu_limit = estimate_compute_units(
             venue_count,
             fallback_count,
-            false, // Default no CLOB
+            has_clob,
             false, // Default no multi-hop
             false, // Default no MEV protection
         );
@@ -2596,21 +4475,15 @@ pub mod swap_toc_processor {
         });
     }
 
-    /// Emit Jito bundle configuration hint for off-chain processing
-    fn emit_jito_bundle_hint(jito_config: &Option<JitoBundleConfig>) {
-        let config = jito_config.clone().unwrap_or_default();
-        if config.enabled {
-            let tip = config.clamped_tip();
-            let tip_account = config.get_tip_account();
-            
-            msg!("🛡️ MEV Protection enabled: Jito bundle with {} lamports tip", tip);
-            
-            emit!(JitoBundleConfigured {
-                enabled: true,
-                tip_lamports: tip,
-                tip_account,
-            });
+    /// Deadline et fenêtre de slots signées avec la transaction
+    pub(crate) fn check_deadline(deadline_unix: Option<i64>, max_slot: Option<u64>, clock: &Clock) -> Result<()> {
+        if let Some(deadline) = deadline_unix {
+            require!(clock.unix_timestamp <= deadline, ErrorCode::SwapDeadlineExceeded);
         }
+        if let Some(max_slot) = max_slot {
+            require!(clock.slot <= max_slot, ErrorCode::SwapSlotExceeded);
+        }
+        Ok(())
     }
 
     fn calculate_expected_output(amount_in: u64, oracle_price: u64) -> Result<u64> {
@@ -2687,51 +4560,244 @@ pub mod swap_toc_processor {
         amount_in: u64,
         min_out: u64,
         direct_dex_venue: Option<Pubkey>,
+        rfq_params: Option<&RfqParams>,
         _jupiter_route: Option<&JupiterRouteParams>, // DISABLED - kept for IDL compatibility
-    ) -> Result<u64> {
-        let remaining_accounts = ctx.remaining_accounts;
-        
+    ) -> Result<SwapResult> {
         // ===== NATIVE DEX SWAP MODE (REQUIRED) =====
         // Jupiter is DISABLED until native router is validated by user confirmation.
         // direct_dex_venue MUST be specified with a valid DEX program ID.
         let dex_program = direct_dex_venue.ok_or_else(|| {
             msg!("ERROR: direct_dex_venue is required. Jupiter fallback is DISABLED.");
-            msg!("Supported DEX programs: Orca Whirlpool, Raydium CLMM, Meteora, Phoenix, Lifinity, Sanctum, Saber");
+            msg!("Supported DEX programs: Orca Whirlpool, Raydium AMM/CLMM/CPMM, Meteora DLMM/DAMM, Phoenix, OpenBook v2, Lifinity, Sanctum, Saber");
             ErrorCode::DexExecutionFailed
         })?;
 
         require!(dex_program != JUPITER_PROGRAM_ID, ErrorCode::DexNotImplemented);
 
+        let account_slice = direct_venue_accounts(ctx, &dex_program)?;
+
+        // Les frais et le SwapResult portent sur l'input réellement dépensé
+        let (spent, amount_out) = if dex_program == RFQ_VENUE_ID {
+            // Devis signé réglé par le router (transferts directs, pas de CPI DEX)
+            let params = rfq_params.ok_or(ErrorCode::InvalidRfqQuote)?;
+            (amount_in, rfq::settle(ctx, params, account_slice, amount_in, min_out)?)
+        } else {
+            // Execute direct CPI to the specified DEX
+            let delta = execute_dex_swap(
+                ctx,
+                dex_program,
+                amount_in,
+                min_out,
+                account_slice,
+                false, // not a fallback
+                None,  // no Jupiter route needed
+            )?;
+            (delta.spent, delta.received)
+        };
+
+        process_swap_fees_and_rebates(ctx, spent, amount_out, min_out, dex_program)
+    }
+
+    /// CPI accounts of a direct venue, taken from remaining_accounts
+    fn direct_venue_accounts<'c, 'info>(
+        ctx: &Context<'_, '_, 'c, 'info, SwapToC<'info>>,
+        dex_program: &Pubkey,
+    ) -> Result<&'c [AccountInfo<'info>]> {
+        let remaining_accounts = ctx.remaining_accounts;
+        let required_accounts = registered_account_len(ctx, dex_program)?;
+
         // En mode direct, certaines venues ont des comptes dynamiques (ex: Meteora DLMM bin arrays).
         // - Phoenix/Lifinity/etc: slice strict sur ABI fixe.
         // - Meteora DLMM: passer tous les comptes fournis (>= minimum requis).
-        let account_slice: &[AccountInfo] = if dex_program == METEORA_DLMM_PROGRAM_ID {
+        if *dex_program == METEORA_DLMM_PROGRAM_ID {
             require!(
-                remaining_accounts.len() >= cpi_meteora::METEORA_SWAP_ACCOUNT_COUNT,
+                remaining_accounts.len() >= required_accounts,
                 ErrorCode::DexExecutionFailed
             );
-            remaining_accounts
+            Ok(remaining_accounts)
         } else {
-            let required_accounts = required_account_len_for_dex(&dex_program)?;
             require!(
                 required_accounts > 0 && remaining_accounts.len() >= required_accounts,
                 ErrorCode::DexExecutionFailed
             );
-            &remaining_accounts[0..required_accounts]
+            Ok(&remaining_accounts[0..required_accounts])
+        }
+    }
+
+    /// Exact-output swap: `amount_out` net livré à l'utilisateur, au plus `max_in` prélevé
+    ///
+    /// Natif sur Orca Whirlpool, Raydium CLMM et Phoenix (achat de base); ailleurs
+    /// émulé en exact-in avec le plus petit input suffisant d'après l'état du pool
+    /// (venues sans estimateur refusées). L'input non consommé ne quitte jamais le
+    /// compte source et reste à l'utilisateur.
+    pub fn process_swap_exact_out<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, SwapToC<'info>>,
+        args: SwapExactOutArgs,
+    ) -> Result<SwapResult> {
+        require!(!ctx.accounts.state.is_paused, error::SwapbackError::ProtocolPaused);
+        require!(args.amount_out > 0 && args.max_in > 0, ErrorCode::InvalidAmount);
+        require!(
+            args.max_in <= MAX_SINGLE_SWAP_LAMPORTS,
+            ErrorCode::SwapAmountExceedsMaximum
+        );
+        require!(args.venue != JUPITER_PROGRAM_ID, ErrorCode::DexNotImplemented);
+
+        let clock = Clock::get()?;
+        check_deadline(args.deadline_unix, args.max_slot, &clock)?;
+        token_extensions::check_swap_mints(&ctx)?;
+
+        if ctx.accounts.user_rebate.user == Pubkey::default() {
+            ctx.accounts.user_rebate.user = ctx.accounts.user.key();
+            ctx.accounts.user_rebate.bump = ctx.bumps.user_rebate;
+        }
+
+        // ✅ SECURITY: same sandwich guard, pair circuit breaker and oracle checks as swap_toc
+        pre_swap_checks(
+            &mut ctx,
+            PreSwapGuard {
+                primary_oracle_account: args.primary_oracle_account,
+                fallback_oracle_account: args.fallback_oracle_account,
+                max_staleness_override: args.max_staleness_override,
+                sandwich_guard: args.sandwich_guard,
+                jito_bundle: None,
+            },
+            &clock,
+        )?;
+
+        // ✅ SECURITY: venue circuit breaker
+        if let Some(venue_score) = &ctx.accounts.venue_score {
+            if venue_score.venue == args.venue {
+                require!(
+                    !venue_score.is_quarantined(clock.unix_timestamp),
+                    ErrorCode::VenueQuarantined
+                );
+            }
+        }
+
+        let account_slice = direct_venue_accounts(&ctx, &args.venue)?;
+        let source = ctx.accounts.user_token_account_a.to_account_info();
+        let source_before = balance_delta::read_token_amount(&source)?;
+
+        let native = supports_native_exact_out(&ctx, &args.venue, account_slice);
+        let received = if native {
+            execute_dex_swap_exact_out(&ctx, args.venue, args.amount_out, args.max_in, account_slice)?
+        } else {
+            let amount_in = emulated_exact_out_input(&ctx, &args, account_slice, &clock)?;
+            msg!("🎯 Exact-out emulated on {}: input sized at {}", args.venue, amount_in);
+            execute_dex_swap(&ctx, args.venue, amount_in, args.amount_out, account_slice, false, None)?
+                .received
         };
 
-        // Execute direct CPI to the specified DEX
-        let amount_out = execute_dex_swap(
-            ctx,
-            dex_program,
-            amount_in,
-            min_out,
-            account_slice,
-            false, // not a fallback
-            None,  // no Jupiter route needed
+        let spent = source_before.saturating_sub(balance_delta::read_token_amount(&source)?);
+        emit!(ExactOutSwapExecuted {
+            user: ctx.accounts.user.key(),
+            venue: args.venue,
+            amount_out: args.amount_out,
+            max_in: args.max_in,
+            spent,
+            received,
+            unspent_in: args.max_in.saturating_sub(spent),
+            native,
+        });
+        msg!("🎯 Exact-out: received {} (target {}), spent {} of max {}", received, args.amount_out, spent, args.max_in);
+
+        process_swap_fees_and_rebates(&mut ctx, spent, received, args.amount_out, args.venue)
+    }
+
+    /// Venues dont le programme accepte un montant de sortie spécifié
+    fn supports_native_exact_out(ctx: &Context<SwapToC>, venue: &Pubkey, account_slice: &[AccountInfo]) -> bool {
+        match *venue {
+            ORCA_WHIRLPOOL_PROGRAM_ID | RAYDIUM_CLMM_PROGRAM_ID => true,
+            // Phoenix: budget en base lots uniquement, donc output = base
+            PHOENIX_PROGRAM_ID => account_slice
+                .get(cpi_phoenix::BASE_ACCOUNT_INDEX)
+                .is_some_and(|base| base.key() == ctx.accounts.user_token_account_b.key()),
+            _ => false,
+        }
+    }
+
+    /// Native exact-output CPI under the same governance gate and balance-delta checks
+    fn execute_dex_swap_exact_out(
+        ctx: &Context<SwapToC>,
+        dex_program: Pubkey,
+        amount_out: u64,
+        max_in: u64,
+        account_slice: &[AccountInfo],
+    ) -> Result<u64> {
+        ctx.accounts.venue_registry.check_execution(&dex_program, max_in)?;
+        ctx.accounts
+            .venue_registry
+            .check_pools(&dex_program, account_slice, Clock::get()?.unix_timestamp)?;
+        pool_validation::validate_venue_accounts(&dex_program, account_slice)?;
+
+        // La venue livre le brut; l'utilisateur doit recevoir amount_out net de transfer fee
+        let output_mint = ctx.accounts.output_mint.as_ref().map(|m| m.to_account_info());
+        let venue_amount_out =
+            token_extensions::amount_before_fee(output_mint.as_ref(), Clock::get()?.epoch, amount_out)?;
+
+        let source = ctx.accounts.user_token_account_a.to_account_info();
+        let destination = ctx.accounts.user_token_account_b.to_account_info();
+        let delta = balance_delta::with_balance_deltas(
+            &source,
+            &destination,
+            max_in,
+            amount_out,
+            || match dex_program {
+                ORCA_WHIRLPOOL_PROGRAM_ID => {
+                    cpi_orca::swap_exact_out(ctx, account_slice, venue_amount_out, max_in)
+                }
+                RAYDIUM_CLMM_PROGRAM_ID => {
+                    cpi_raydium_clmm::swap_exact_out(ctx, account_slice, venue_amount_out, max_in)
+                }
+                PHOENIX_PROGRAM_ID => {
+                    cpi_phoenix::swap_exact_out(ctx, account_slice, venue_amount_out, max_in)
+                }
+                _ => err!(ErrorCode::ExactOutNotSupported),
+            },
         )?;
-        
-        process_swap_fees_and_rebates(ctx, amount_in, amount_out, min_out, dex_program)
+
+        emit!(VenueExecuted {
+            venue: dex_program,
+            amount_in: delta.spent,
+            amount_out: delta.received,
+            success: true,
+            fallback_used: false,
+        });
+        msg!("✅ Venue exact-out swap: spent {}, received {}", delta.spent, delta.received);
+        Ok(delta.received)
+    }
+
+    /// Input d'un exact-out émulé: plus petit input dont la sortie estimée sur l'état du pool
+    /// (transfer fees incluses) atteint amount_out, plafonné à max_in. L'input non requis
+    /// n'est jamais prélevé; sans estimateur pour la venue, l'émulation est refusée.
+    fn emulated_exact_out_input(
+        ctx: &Context<SwapToC>,
+        args: &SwapExactOutArgs,
+        account_slice: &[AccountInfo],
+        clock: &Clock,
+    ) -> Result<u64> {
+        let (input_mint_key, _) = token_extensions::swap_mints(ctx)?;
+        let input_mint = ctx.accounts.input_mint.as_ref().map(|m| m.to_account_info());
+        let output_mint = ctx.accounts.output_mint.as_ref().map(|m| m.to_account_info());
+        let venue_out =
+            token_extensions::amount_before_fee(output_mint.as_ref(), clock.epoch, args.amount_out)?;
+        let venue_max_in =
+            token_extensions::amount_after_fee(input_mint.as_ref(), clock.epoch, args.max_in)?;
+
+        let Some(venue_in) = quote::exact_out_input(
+            &args.venue,
+            account_slice,
+            &input_mint_key,
+            venue_out,
+            venue_max_in,
+            clock,
+        )?
+        else {
+            msg!("Exact-out: {} has neither native exact-out nor an estimator", args.venue);
+            return err!(ErrorCode::ExactOutNotSupported);
+        };
+        let amount_in = token_extensions::amount_before_fee(input_mint.as_ref(), clock.epoch, venue_in)?;
+        Ok(amount_in.min(args.max_in))
     }
 
     /// Process fees, rebates, and state updates after a successful swap
@@ -2741,8 +4807,8 @@ pub mod swap_toc_processor {
         amount_in: u64,
         amount_out: u64,
         min_out: u64,
-        _venue: Pubkey,
-    ) -> Result<u64> {
+        venue: Pubkey,
+    ) -> Result<SwapResult> {
         // Calculate and distribute fees/rebates
         let user_boost = ctx
             .accounts
@@ -2767,6 +4833,7 @@ pub mod swap_toc_processor {
         };
 
         // Rebate calculation with boost
+        let mut rebate_credited = 0;
         if routing_profit > 0 {
             let base_rebate = calculate_fee(routing_profit, ctx.accounts.state.rebate_percentage)?;
             let treasury_from_npi =
@@ -2784,6 +4851,7 @@ pub mod swap_toc_processor {
 
             // Pay rebate to user
             pay_rebate_to_user_with_amount(ctx, routing_profit, user_boost, total_rebate)?;
+            rebate_credited = total_rebate;
 
             // Update state totals
             let state = &mut ctx.accounts.state;
@@ -2844,7 +4912,13 @@ pub mod swap_toc_processor {
             },
         });
 
-        Ok(amount_out)
+        Ok(SwapResult {
+            amount_in,
+            amount_out,
+            platform_fee,
+            rebate_credited,
+            venues: vec![venue],
+        })
     }
 
     /// Process TWAP swap - emits event for keeper orchestration
@@ -2856,7 +4930,7 @@ pub mod swap_toc_processor {
         total_min_out: u64,
         twap_slices: u8,
         clock: &Clock,
-    ) -> Result<()> {
+    ) -> Result<SwapResult> {
         let slice_amount = args.amount_in / twap_slices as u64;
         if slice_amount == 0 {
             return err!(ErrorCode::TwapSliceTooSmall);
@@ -2865,11 +4939,12 @@ pub mod swap_toc_processor {
         let slice_min_out = total_min_out / twap_slices as u64;
 
         // For TWAP, execute first slice immediately, emit event for keeper to handle rest
-        let amount_out = process_single_swap(
+        let first_slice = process_single_swap(
             ctx,
             slice_amount,
             slice_min_out,
             args.direct_dex_venue,
+            args.rfq.as_ref(),
             args.jupiter_route.as_ref(),
         )?;
 
@@ -2882,7 +4957,7 @@ pub mod swap_toc_processor {
             remaining_slices: twap_slices - 1,
             interval_seconds: 60, // 1 minute between slices
             first_slice_executed: true,
-            first_slice_output: amount_out,
+            first_slice_output: first_slice.amount_out,
             timestamp: clock.unix_timestamp,
         });
 
@@ -2892,7 +4967,7 @@ pub mod swap_toc_processor {
             twap_slices - 1
         );
 
-        Ok(())
+        Ok(first_slice)
     }
 
     /// Calculate fee based on amount and basis points
@@ -3073,6 +5148,123 @@ pub mod swap_toc_processor {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_swap_plan_from_legacy() {
+        // Plan plein (10 venues, 5 fallbacks de 10 venues): aucun octet libre après bump
+        let venues = vec![VenueWeight { venue: Pubkey::new_unique(), weight: 1_000 }; 10];
+        let plan = SwapPlan {
+            plan_id: [7u8; 32],
+            user: Pubkey::new_unique(),
+            token_in: Pubkey::new_unique(),
+            token_out: Pubkey::new_unique(),
+            amount_in: 1_000,
+            min_out: 990,
+            venues: venues.clone(),
+            fallback_plans: vec![FallbackPlan { venues, min_out: 980 }; 5],
+            expires_at: 1_700_000_000,
+            created_at: 1_600_000_000,
+            bump: 254,
+            max_slot: 0,
+        };
+        let mut data = Vec::new();
+        plan.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), SwapPlan::LEN);
+
+        let legacy = &data[..SwapPlan::LEGACY_LEN];
+        assert!(SwapPlan::try_deserialize(&mut &legacy[..]).is_err());
+        let migrated = SwapPlan::from_legacy(legacy).unwrap();
+        assert_eq!(migrated.user, plan.user);
+        assert_eq!(migrated.fallback_plans.len(), 5);
+        assert_eq!(migrated.bump, 254);
+        assert_eq!(migrated.max_slot, 0);
+
+        // Déjà au format courant
+        assert!(SwapPlan::from_legacy(&data).is_none());
+    }
+
+    #[test]
+    fn test_router_state_from_legacy() {
+        // pending_authority renseigné: aucun octet libre après bump
+        let state = RouterState {
+            authority: Pubkey::new_unique(),
+            pending_authority: Some(Pubkey::new_unique()),
+            is_paused: false,
+            paused_at: 0,
+            rebate_percentage: DEFAULT_REBATE_BPS,
+            treasury_percentage: TREASURY_FROM_NPI_BPS,
+            boost_vault_percentage: BOOST_VAULT_BPS,
+            treasury_from_fees_bps: PLATFORM_FEE_TREASURY_BPS,
+            buyburn_from_fees_bps: PLATFORM_FEE_BUYBURN_BPS,
+            treasury_wallet: Pubkey::new_unique(),
+            boost_vault_wallet: Pubkey::new_unique(),
+            buyback_wallet: Pubkey::new_unique(),
+            npi_vault_wallet: Pubkey::new_unique(),
+            total_volume: 42,
+            total_npi: 0,
+            total_rebates_paid: 0,
+            total_treasury_from_npi: 0,
+            total_boost_vault: 0,
+            dynamic_slippage_enabled: true,
+            total_treasury_from_fees: 0,
+            total_buyburn: 0,
+            bump: 253,
+            pyth_require_full_verification: true,
+            pyth_min_signatures: 13,
+            pyth_max_slot_lag: 1,
+        };
+        let mut data = Vec::new();
+        state.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), RouterState::LEN);
+
+        // Le layout legacy s'arrête à bump
+        let legacy = &data[..RouterState::LEGACY_LEN];
+        assert!(RouterState::try_deserialize(&mut &legacy[..]).is_err());
+        let migrated = RouterState::from_legacy(legacy).unwrap();
+        assert_eq!(migrated.authority, state.authority);
+        assert_eq!(migrated.total_volume, 42);
+        assert_eq!(migrated.bump, 253);
+        assert!(!migrated.pyth_require_full_verification);
+        assert_eq!(migrated.pyth_min_signatures, DEFAULT_PYTH_MIN_SIGNATURES);
+        assert_eq!(migrated.pyth_max_slot_lag, DEFAULT_PYTH_MAX_SLOT_LAG);
+
+        assert!(RouterState::from_legacy(&data).is_none());
+    }
+
+    #[test]
+    fn test_router_config_from_legacy() {
+        let config = RouterConfig {
+            authority: Pubkey::new_unique(),
+            pending_authority: Some(Pubkey::new_unique()),
+            rebate_bps: DEFAULT_REBATE_BPS,
+            treasury_bps: TREASURY_FROM_NPI_BPS,
+            boost_vault_bps: BOOST_VAULT_BPS,
+            treasury_from_fees_bps: PLATFORM_FEE_TREASURY_BPS,
+            buyburn_from_fees_bps: PLATFORM_FEE_BUYBURN_BPS,
+            dynamic_slippage_enabled: false,
+            npi_benchmarking_enabled: true,
+            max_venues_per_swap: Some(4),
+            bump: 252,
+            pyth_require_full_verification: true,
+            pyth_min_signatures: 13,
+            pyth_max_slot_lag: 1,
+        };
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), RouterConfig::LEN);
+
+        let legacy = &data[..RouterConfig::LEGACY_LEN];
+        assert!(RouterConfig::try_deserialize(&mut &legacy[..]).is_err());
+        let migrated = RouterConfig::from_legacy(legacy).unwrap();
+        assert_eq!(migrated.authority, config.authority);
+        assert_eq!(migrated.max_venues_per_swap, Some(4));
+        assert_eq!(migrated.bump, 252);
+        assert!(!migrated.pyth_require_full_verification);
+        assert_eq!(migrated.pyth_min_signatures, DEFAULT_PYTH_MIN_SIGNATURES);
+        assert_eq!(migrated.pyth_max_slot_lag, DEFAULT_PYTH_MAX_SLOT_LAG);
+
+        assert!(RouterConfig::from_legacy(&data).is_none());
+    }
+
     #[test]
     fn test_calculate_boosted_rebate_no_boost() {
         // NPI: 10 USDC (10_000_000 avec 6 decimals)
@@ -3153,6 +5345,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_swap_result_return_data_layout() {
+        // Layout lu par les programmes appelants: 4 x u64 LE puis Vec<Pubkey> (u32 + 32 * n)
+        let venue = Pubkey::new_unique();
+        let result = SwapResult {
+            amount_in: 1,
+            amount_out: 2,
+            platform_fee: 3,
+            rebate_credited: 4,
+            venues: vec![venue],
+        };
+        let data = result.try_to_vec().unwrap();
+        assert_eq!(data.len(), 4 * 8 + 4 + 32);
+        assert_eq!(u64::from_le_bytes(data[8..16].try_into().unwrap()), 2);
+        assert_eq!(&data[36..68], venue.as_ref());
+        assert_eq!(SwapResult::try_from_slice(&data).unwrap(), result);
+    }
+
+    #[test]
+    fn test_check_deadline() {
+        let clock = Clock {
+            slot: 1_000,
+            unix_timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+        assert!(swap_toc_processor::check_deadline(None, None, &clock).is_ok());
+        assert!(swap_toc_processor::check_deadline(Some(1_700_000_000), Some(1_000), &clock).is_ok());
+        assert!(swap_toc_processor::check_deadline(Some(1_699_999_999), None, &clock).is_err());
+        assert!(swap_toc_processor::check_deadline(None, Some(999), &clock).is_err());
+    }
+
     #[test]
     fn test_calculate_fee() {
         // Test platform fee calculation (0.3% = 30 BP)
@@ -0,0 +1,305 @@
+//! LST Pricing - Taux de change stake pool pour les routes Sanctum
+//!
+//! Le prix LST/SOL est déterministe (lamports du pool / supply du LST): il est
+//! lu on-chain depuis l'état SPL stake pool ou Sanctum Infinity au lieu de
+//! passer par deux feeds USD. L'oracle reste utilisé comme borne de cohérence.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token::state::Mint as SplMint;
+
+use crate::cpi_sanctum::{DESTINATION_LST_MINT_INDEX, SOURCE_LST_MINT_INDEX};
+use crate::{ErrorCode, SANCTUM_PROGRAM_ID};
+
+/// Mint wSOL (taux fixe de 1 SOL)
+pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// Déploiements du programme SPL stake pool (SPL, Sanctum SPL, Sanctum SPL Multi)
+pub const SPL_STAKE_POOL_PROGRAM_IDS: [Pubkey; 3] = [
+    pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy"),
+    pubkey!("SP12tWFxD9oJsVWNavTTBZvMbA6gkAmxtVgxdqvyvhY"),
+    pubkey!("SPMBzsVUuoHA4Jm6KunbsotaahvVikZs1JyTW6iJvbn"),
+];
+
+/// Taux exprimés en lamports par token LST (9 décimales)
+pub const LST_RATE_SCALE: u128 = 1_000_000_000;
+
+/// Écart max entre le taux stake pool et l'oracle USD (3%)
+pub const MAX_LST_ORACLE_DEVIATION_BPS: u64 = 300;
+
+// spl_stake_pool::state::StakePool (Borsh)
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+const STAKE_POOL_MINT_OFFSET: usize = 162;
+const STAKE_POOL_TOTAL_LAMPORTS_OFFSET: usize = 258;
+const STAKE_POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+const STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+const STAKE_POOL_MIN_LEN: usize = STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 8;
+
+// s_controller_lib::PoolState (bytemuck, sans discriminator)
+const INFINITY_POOL_STATE_SEED: &[u8] = b"state";
+const INFINITY_TOTAL_SOL_VALUE_OFFSET: usize = 0;
+const INFINITY_IS_DISABLED_OFFSET: usize = 13;
+const INFINITY_IS_REBALANCING_OFFSET: usize = 14;
+const INFINITY_LP_MINT_OFFSET: usize = 144;
+const INFINITY_POOL_STATE_LEN: usize = INFINITY_LP_MINT_OFFSET + 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakePoolRate {
+    pub pool_mint: Pubkey,
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+    pub last_update_epoch: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InfinityPoolState {
+    pub total_sol_value: u64,
+    pub lp_token_mint: Pubkey,
+    pub is_disabled: bool,
+    pub is_rebalancing: bool,
+}
+
+pub fn parse_spl_stake_pool(data: &[u8]) -> Option<StakePoolRate> {
+    if data.len() < STAKE_POOL_MIN_LEN || data[0] != STAKE_POOL_ACCOUNT_TYPE {
+        return None;
+    }
+    Some(StakePoolRate {
+        pool_mint: Pubkey::try_from(&data[STAKE_POOL_MINT_OFFSET..STAKE_POOL_MINT_OFFSET + 32]).ok()?,
+        total_lamports: read_u64(data, STAKE_POOL_TOTAL_LAMPORTS_OFFSET)?,
+        pool_token_supply: read_u64(data, STAKE_POOL_TOKEN_SUPPLY_OFFSET)?,
+        last_update_epoch: read_u64(data, STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET)?,
+    })
+}
+
+pub fn parse_infinity_pool_state(data: &[u8]) -> Option<InfinityPoolState> {
+    if data.len() < INFINITY_POOL_STATE_LEN {
+        return None;
+    }
+    Some(InfinityPoolState {
+        total_sol_value: read_u64(data, INFINITY_TOTAL_SOL_VALUE_OFFSET)?,
+        lp_token_mint: Pubkey::try_from(&data[INFINITY_LP_MINT_OFFSET..INFINITY_LP_MINT_OFFSET + 32]).ok()?,
+        is_disabled: data[INFINITY_IS_DISABLED_OFFSET] != 0,
+        is_rebalancing: data[INFINITY_IS_REBALANCING_OFFSET] != 0,
+    })
+}
+
+/// Lamports par token (LST_RATE_SCALE) à partir de la valeur totale et de la supply
+pub fn lamports_per_token(total_lamports: u64, supply: u64) -> Option<u128> {
+    if supply == 0 || total_lamports == 0 {
+        return None;
+    }
+    (total_lamports as u128)
+        .checked_mul(LST_RATE_SCALE)?
+        .checked_div(supply as u128)
+}
+
+/// Prix out/in en 8 décimales à partir des deux taux en lamports
+pub fn lst_pair_price(rate_in: u128, rate_out: u128) -> Option<u64> {
+    if rate_out == 0 {
+        return None;
+    }
+    let price = rate_in.checked_mul(100_000_000)?.checked_div(rate_out)?;
+    u64::try_from(price).ok().filter(|price| *price > 0)
+}
+
+/// Prix attendu d'une route Sanctum (out par in, 8 décimales).
+///
+/// `sanctum_accounts` est la slice CPI Sanctum (mints source/destination aux
+/// index habituels); `rate_accounts` contient, dans l'ordre, un compte de taux
+/// par côté non-wSOL: SPL stake pool, ou pool state Infinity pour le LP INF.
+/// Un taux inexploitable (compte absent, pool non mis à jour pour l'epoch
+/// courante, Infinity désactivé) est une erreur: pas de repli sur l'oracle.
+pub fn read_sanctum_price<'info>(
+    sanctum_accounts: &[AccountInfo<'info>],
+    rate_accounts: &[AccountInfo<'info>],
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    clock: &Clock,
+) -> Result<u64> {
+    let source_mint = sanctum_accounts
+        .get(SOURCE_LST_MINT_INDEX)
+        .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount))?;
+    let destination_mint = sanctum_accounts
+        .get(DESTINATION_LST_MINT_INDEX)
+        .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount))?;
+    require_keys_eq!(source_mint.key(), *mint_in, ErrorCode::InvalidLstRateAccount);
+    require_keys_eq!(destination_mint.key(), *mint_out, ErrorCode::InvalidLstRateAccount);
+
+    let mut rate_accounts = rate_accounts.iter();
+    let rate_in = sol_rate(source_mint, &mut rate_accounts, clock)?;
+    let rate_out = sol_rate(destination_mint, &mut rate_accounts, clock)?;
+
+    lst_pair_price(rate_in, rate_out).ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount))
+}
+
+/// Taux en lamports d'un mint: 1 SOL pour wSOL, sinon le prochain compte de taux
+fn sol_rate<'info>(
+    mint_account: &AccountInfo<'info>,
+    rate_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    clock: &Clock,
+) -> Result<u128> {
+    if mint_account.key() == WSOL_MINT {
+        return Ok(LST_RATE_SCALE);
+    }
+
+    let Some(rate_account) = rate_accounts.next() else {
+        msg!("❌ No stake pool account for LST {}", mint_account.key());
+        return err!(ErrorCode::LstRateUnavailable);
+    };
+
+    if SPL_STAKE_POOL_PROGRAM_IDS.contains(rate_account.owner) {
+        let pool = parse_spl_stake_pool(&rate_account.try_borrow_data()?)
+            .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount))?;
+        require_keys_eq!(pool.pool_mint, mint_account.key(), ErrorCode::InvalidLstRateAccount);
+
+        // total_lamports n'est fiable qu'après UpdateStakePoolBalance pour l'epoch
+        if pool.last_update_epoch != clock.epoch {
+            msg!(
+                "❌ Stake pool {} not updated for epoch {} (last: {})",
+                rate_account.key(),
+                clock.epoch,
+                pool.last_update_epoch
+            );
+            return err!(ErrorCode::LstRateStale);
+        }
+        return lamports_per_token(pool.total_lamports, pool.pool_token_supply)
+            .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount));
+    }
+
+    if *rate_account.owner == SANCTUM_PROGRAM_ID {
+        // Seul le PDA ["state"] du S controller porte le PoolState
+        require_keys_eq!(rate_account.key(), infinity_pool_state_address(), ErrorCode::InvalidLstRateAccount);
+
+        let state = parse_infinity_pool_state(&rate_account.try_borrow_data()?)
+            .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount))?;
+        require_keys_eq!(state.lp_token_mint, mint_account.key(), ErrorCode::InvalidLstRateAccount);
+        require_keys_eq!(*mint_account.owner, anchor_spl::token::ID, ErrorCode::InvalidLstRateAccount);
+
+        if state.is_disabled || state.is_rebalancing {
+            msg!("❌ Infinity pool disabled or rebalancing, LST rate unavailable");
+            return err!(ErrorCode::LstRateUnavailable);
+        }
+        let supply = SplMint::unpack(&mint_account.try_borrow_data()?)
+            .map_err(|_| error!(ErrorCode::InvalidLstRateAccount))?
+            .supply;
+        return lamports_per_token(state.total_sol_value, supply)
+            .ok_or_else(|| error!(ErrorCode::InvalidLstRateAccount));
+    }
+
+    msg!("❌ Unsupported LST rate account owner: {}", rate_account.owner);
+    err!(ErrorCode::InvalidLstRateAccount)
+}
+
+/// PDA ["state"] du S controller (Infinity PoolState)
+pub(crate) fn infinity_pool_state_address() -> Pubkey {
+    Pubkey::find_program_address(&[INFINITY_POOL_STATE_SEED], &SANCTUM_PROGRAM_ID).0
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spl_stake_pool() {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0u8; 611];
+        data[0] = STAKE_POOL_ACCOUNT_TYPE;
+        data[STAKE_POOL_MINT_OFFSET..STAKE_POOL_MINT_OFFSET + 32].copy_from_slice(mint.as_ref());
+        data[STAKE_POOL_TOTAL_LAMPORTS_OFFSET..STAKE_POOL_TOTAL_LAMPORTS_OFFSET + 8]
+            .copy_from_slice(&1_150_000_000_000u64.to_le_bytes());
+        data[STAKE_POOL_TOKEN_SUPPLY_OFFSET..STAKE_POOL_TOKEN_SUPPLY_OFFSET + 8]
+            .copy_from_slice(&1_000_000_000_000u64.to_le_bytes());
+        data[STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET..STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 8]
+            .copy_from_slice(&700u64.to_le_bytes());
+
+        let pool = parse_spl_stake_pool(&data).unwrap();
+        assert_eq!(pool.pool_mint, mint);
+        assert_eq!(pool.last_update_epoch, 700);
+        assert_eq!(
+            lamports_per_token(pool.total_lamports, pool.pool_token_supply),
+            Some(1_150_000_000)
+        );
+
+        data[0] = 2; // ValidatorList
+        assert_eq!(parse_spl_stake_pool(&data), None);
+    }
+
+    #[test]
+    fn test_parse_infinity_pool_state() {
+        let lp_mint = Pubkey::new_unique();
+        let mut data = vec![0u8; INFINITY_POOL_STATE_LEN];
+        data[0..8].copy_from_slice(&5_000_000_000u64.to_le_bytes());
+        data[INFINITY_IS_REBALANCING_OFFSET] = 1;
+        data[INFINITY_LP_MINT_OFFSET..INFINITY_LP_MINT_OFFSET + 32].copy_from_slice(lp_mint.as_ref());
+
+        let state = parse_infinity_pool_state(&data).unwrap();
+        assert_eq!(state.total_sol_value, 5_000_000_000);
+        assert_eq!(state.lp_token_mint, lp_mint);
+        assert!(state.is_rebalancing);
+        assert!(!state.is_disabled);
+    }
+
+    #[test]
+    fn test_read_sanctum_price_requires_fresh_rate() {
+        let lst_mint = Pubkey::new_unique();
+        let mut data = vec![0u8; STAKE_POOL_MIN_LEN];
+        data[0] = STAKE_POOL_ACCOUNT_TYPE;
+        data[STAKE_POOL_MINT_OFFSET..STAKE_POOL_MINT_OFFSET + 32].copy_from_slice(lst_mint.as_ref());
+        data[STAKE_POOL_TOTAL_LAMPORTS_OFFSET..STAKE_POOL_TOTAL_LAMPORTS_OFFSET + 8]
+            .copy_from_slice(&1_150_000_000_000u64.to_le_bytes());
+        data[STAKE_POOL_TOKEN_SUPPLY_OFFSET..STAKE_POOL_TOKEN_SUPPLY_OFFSET + 8]
+            .copy_from_slice(&1_000_000_000_000u64.to_le_bytes());
+        data[STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET..STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 8]
+            .copy_from_slice(&700u64.to_le_bytes());
+
+        // Slice Sanctum: seuls les mints source (LST) et destination (wSOL) sont lus
+        let keys: Vec<Pubkey> = (0..=DESTINATION_LST_MINT_INDEX)
+            .map(|index| match index {
+                SOURCE_LST_MINT_INDEX => lst_mint,
+                DESTINATION_LST_MINT_INDEX => WSOL_MINT,
+                _ => Pubkey::new_unique(),
+            })
+            .collect();
+        let (owner, pool_key, stake_pool_program) =
+            (Pubkey::default(), Pubkey::new_unique(), SPL_STAKE_POOL_PROGRAM_IDS[0]);
+        let mut lamports = vec![0u64; keys.len()];
+        let mut datas = vec![Vec::<u8>::new(); keys.len()];
+        let sanctum_accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut().zip(datas.iter_mut()))
+            .map(|(key, (lamports, data))| AccountInfo::new(key, false, false, lamports, data, &owner, false, 0))
+            .collect();
+        let mut pool_lamports = 0;
+        let pool = AccountInfo::new(&pool_key, false, false, &mut pool_lamports, &mut data, &stake_pool_program, false, 0);
+
+        let clock = |epoch| Clock { epoch, ..Clock::default() };
+        let price = read_sanctum_price(&sanctum_accounts, std::slice::from_ref(&pool), &lst_mint, &WSOL_MINT, &clock(700));
+        assert_eq!(price.unwrap(), 115_000_000);
+
+        // Pool non mis à jour pour l'epoch courante: erreur, pas de repli sur l'oracle
+        let stale = read_sanctum_price(&sanctum_accounts, std::slice::from_ref(&pool), &lst_mint, &WSOL_MINT, &clock(701));
+        assert_eq!(stale.unwrap_err(), error!(ErrorCode::LstRateStale));
+
+        // Compte de taux absent
+        let missing = read_sanctum_price(&sanctum_accounts, &[], &lst_mint, &WSOL_MINT, &clock(700));
+        assert_eq!(missing.unwrap_err(), error!(ErrorCode::LstRateUnavailable));
+    }
+
+    #[test]
+    fn test_lst_pair_price() {
+        // jitoSOL (1.15 SOL) -> SOL
+        assert_eq!(lst_pair_price(1_150_000_000, LST_RATE_SCALE), Some(115_000_000));
+        // SOL -> jitoSOL
+        assert_eq!(lst_pair_price(LST_RATE_SCALE, 1_150_000_000), Some(86_956_521));
+        // mSOL-like (1.25) -> jitoSOL (1.15)
+        assert_eq!(lst_pair_price(1_250_000_000, 1_150_000_000), Some(108_695_652));
+        assert_eq!(lst_pair_price(1, 0), None);
+        assert_eq!(lamports_per_token(1, 0), None);
+    }
+}
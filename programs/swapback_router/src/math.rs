@@ -76,10 +76,55 @@ pub fn renormalize_weights_to_10000(weights: &mut Vec<u16>) {
     }
 }
 
+/// floor(a * b / c) with a 256-bit intermediate product.
+/// Returns None if c == 0 or the result does not fit in u128.
+pub fn mul_div_u128(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+    const LO: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & LO);
+    let (b1, b0) = (b >> 64, b & LO);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & LO) + (p10 & LO);
+    let lo = (p00 & LO) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    if hi >= c {
+        return None;
+    }
+
+    // Long division of (hi, lo) by c, one bit at a time
+    let (mut rem, mut quotient) = (hi, 0u128);
+    for bit in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || rem >= c {
+            rem = rem.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // =========================================================================
+    // MUL DIV TESTS
+    // =========================================================================
+
+    #[test]
+    fn test_mul_div_u128() {
+        assert_eq!(mul_div_u128(10, 20, 3), Some(66));
+        assert_eq!(mul_div_u128(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        // 2^100 * 2^90 / 2^64 = 2^126 (produit hors u128)
+        assert_eq!(mul_div_u128(1 << 100, 1 << 90, 1 << 64), Some(1 << 126));
+        assert_eq!(mul_div_u128(1 << 100, 1 << 90, 1 << 30), None);
+        assert_eq!(mul_div_u128(1, 1, 0), None);
+    }
+
     // =========================================================================
     // SPLIT AMOUNT TESTS
     // =========================================================================
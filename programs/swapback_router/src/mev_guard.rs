@@ -0,0 +1,324 @@
+//! Garde anti-sandwich par introspection du sysvar instructions
+//!
+//! - Aucune autre instruction de la transaction ne doit écrire un compte de pool
+//!   du swap (comptes writable de remaining_accounts, hors comptes de l'utilisateur).
+//! - Avec `jito_bundle.enabled`, le tip Jito (transfert système vers le tip account
+//!   choisi, au moins `clamped_tip()`) doit figurer dans la même transaction.
+//!
+//! Une transaction ne peut pas sandwicher depuis une autre transaction; ce garde
+//! rend vérifiable on-chain que la transaction signée ne contient ni front-run ni
+//! back-run sur les mêmes pools, et que le bundle promis paie bien son tip.
+//!
+//! Avec `jito_bundle.pay_tip`, le tip est payé par `swap_toc` lui-même après le swap
+//! (`PendingTip`), éventuellement financé par l'output WSOL débouclé.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program;
+
+use crate::{ErrorCode, JitoBundleConfig, JitoBundleConfigured, SwapArgs, SwapResult, SwapToC};
+
+/// Tip Jito à payer par l'instruction une fois le swap exécuté
+pub struct PendingTip<'info> {
+    user: AccountInfo<'info>,
+    tip_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    lamports: u64,
+    from_output: bool,
+}
+
+impl<'info> PendingTip<'info> {
+    /// Paie le tip (après le débouclage WSOL de `native_sol`)
+    pub fn finish(self, result: &SwapResult, min_out: u64) -> Result<()> {
+        if self.from_output {
+            // L'utilisateur doit recevoir min_out net du tip prélevé sur l'output
+            require!(
+                result.amount_out.saturating_sub(self.lamports) >= min_out,
+                ErrorCode::SlippageExceeded
+            );
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program,
+                system_program::Transfer {
+                    from: self.user,
+                    to: self.tip_account.clone(),
+                },
+            ),
+            self.lamports,
+        )?;
+
+        msg!("🛡️ Jito tip of {} lamports paid to {}", self.lamports, self.tip_account.key);
+        emit!(JitoBundleConfigured {
+            enabled: true,
+            tip_lamports: self.lamports,
+            tip_account: self.tip_account.key(),
+        });
+        Ok(())
+    }
+}
+
+/// Valide le compte de tip avant le swap (None si le tip n'est pas payé ici)
+pub fn prepare_tip<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    args: &SwapArgs,
+) -> Result<Option<PendingTip<'info>>> {
+    let Some(config) = args
+        .jito_bundle
+        .as_ref()
+        .filter(|config| config.enabled && config.pay_tip)
+    else {
+        return Ok(None);
+    };
+
+    let tip_account = ctx
+        .accounts
+        .jito_tip_account
+        .as_ref()
+        .ok_or(ErrorCode::InvalidJitoTipAccount)?;
+    require_keys_eq!(
+        tip_account.key(),
+        config.get_tip_account(),
+        ErrorCode::InvalidJitoTipAccount
+    );
+    // unwrap_output garantit déjà (native_sol::prepare) un output WSOL
+    require!(
+        !config.tip_from_output || args.native_sol.is_some_and(|options| options.unwrap_output),
+        ErrorCode::JitoTipFromOutputUnsupported
+    );
+
+    Ok(Some(PendingTip {
+        user: ctx.accounts.user.to_account_info(),
+        tip_account: tip_account.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        lamports: config.clamped_tip(),
+        from_output: config.tip_from_output,
+    }))
+}
+
+/// Applique le garde avant toute exécution du swap
+pub fn enforce(ctx: &Context<SwapToC>, jito_bundle: Option<&JitoBundleConfig>) -> Result<()> {
+    let instructions = ctx
+        .accounts
+        .instructions_sysvar
+        .as_ref()
+        .ok_or(ErrorCode::InstructionsSysvarMissing)?
+        .to_account_info();
+
+    let user_accounts = [
+        ctx.accounts.user.key(),
+        ctx.accounts.user_token_account_a.key(),
+        ctx.accounts.user_token_account_b.key(),
+    ];
+    let protected: Vec<Pubkey> = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|account| account.is_writable && !user_accounts.contains(account.key))
+        .map(|account| account.key())
+        .collect();
+
+    // Tip payé par l'instruction elle-même: rien à chercher dans la transaction
+    let tip = jito_bundle
+        .filter(|config| config.enabled && !config.pay_tip)
+        .map(|config| (config.get_tip_account(), config.clamped_tip()));
+
+    check_transaction(&instructions, &protected, tip)?;
+    msg!("🛡️ Sandwich guard: {} pool accounts exclusive to this swap", protected.len());
+    Ok(())
+}
+
+/// Parcourt les autres instructions de la transaction: aucune ne doit écrire un
+/// compte protégé et, si `tip` est demandé, l'une d'elles doit payer le tip.
+///
+/// En CPI, `current_index` désigne l'instruction de l'appelant: elle est exclue
+/// comme le serait `swap_toc` appelé directement.
+pub fn check_transaction(
+    instructions: &AccountInfo,
+    protected: &[Pubkey],
+    tip: Option<(Pubkey, u64)>,
+) -> Result<()> {
+    let mut tip_found = false;
+
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let instruction_count = instruction_count(instructions)?;
+    for index in (0..instruction_count).filter(|index| *index != current_index) {
+        let instruction = load_instruction_at_checked(index, instructions)?;
+        if let Some(account) = writes_any(&instruction, protected) {
+            msg!("🥪 Instruction {} writes pool account {}", index, account);
+            return err!(ErrorCode::SandwichDetected);
+        }
+        if let Some((tip_account, min_lamports)) = tip {
+            tip_found |= is_tip_transfer(&instruction, &tip_account, min_lamports);
+        }
+    }
+
+    if let Some((tip_account, min_lamports)) = tip {
+        require!(tip_found, ErrorCode::JitoTipMissing);
+        msg!("🛡️ Jito tip of {} lamports to {} verified", min_lamports, tip_account);
+    }
+    Ok(())
+}
+
+/// Nombre d'instructions de la transaction (u16 en tête du sysvar)
+fn instruction_count(instructions: &AccountInfo) -> Result<usize> {
+    let data = instructions.try_borrow_data()?;
+    let count = data
+        .get(0..2)
+        .ok_or(ErrorCode::InstructionsSysvarMissing)?;
+    Ok(u16::from_le_bytes([count[0], count[1]]) as usize)
+}
+
+/// Premier compte protégé que l'instruction référence en écriture
+pub fn writes_any(instruction: &Instruction, protected: &[Pubkey]) -> Option<Pubkey> {
+    instruction
+        .accounts
+        .iter()
+        .find(|meta| meta.is_writable && protected.contains(&meta.pubkey))
+        .map(|meta| meta.pubkey)
+}
+
+/// Transfert système d'au moins `min_lamports` vers `tip_account`
+pub fn is_tip_transfer(instruction: &Instruction, tip_account: &Pubkey, min_lamports: u64) -> bool {
+    instruction.program_id == anchor_lang::system_program::ID
+        && transfer_lamports(&instruction.data).is_some_and(|lamports| lamports >= min_lamports)
+        && instruction
+            .accounts
+            .get(1)
+            .is_some_and(|destination| destination.pubkey == *tip_account)
+}
+
+/// Lamports d'un `SystemInstruction::Transfer` (tag u32 = 2 puis u64, bincode)
+fn transfer_lamports(data: &[u8]) -> Option<u64> {
+    const TRANSFER_TAG: [u8; 4] = 2u32.to_le_bytes();
+    if data.len() != 12 || data[0..4] != TRANSFER_TAG {
+        return None;
+    }
+    Some(u64::from_le_bytes(data[4..12].try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::instruction::AccountMeta;
+    use anchor_lang::solana_program::system_instruction;
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+    };
+
+    /// Données du sysvar instructions pour `transaction`, exécutée à `current_index`
+    fn instructions_data(transaction: &[Instruction], current_index: u16) -> Vec<u8> {
+        let borrowed: Vec<BorrowedInstruction> = transaction
+            .iter()
+            .map(|instruction| BorrowedInstruction {
+                program_id: &instruction.program_id,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|meta| BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: &instruction.data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, current_index);
+        data
+    }
+
+    fn check(
+        transaction: &[Instruction],
+        current_index: u16,
+        protected: &[Pubkey],
+        tip: Option<(Pubkey, u64)>,
+    ) -> Result<()> {
+        let key = anchor_lang::solana_program::sysvar::instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let mut lamports = 0;
+        let mut data = instructions_data(transaction, current_index);
+        let sysvar = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        check_transaction(&sysvar, protected, tip)
+    }
+
+    fn swap_instruction(program_id: Pubkey, pool: Pubkey) -> Instruction {
+        Instruction::new_with_bytes(program_id, &[], vec![AccountMeta::new(pool, false)])
+    }
+
+    #[test]
+    fn test_check_transaction_sysvar() {
+        let user = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let tip_account = crate::JITO_TIP_ACCOUNTS[1];
+        let swap = swap_instruction(crate::ID, pool);
+        let tip = system_instruction::transfer(&user, &tip_account, 10_000);
+
+        // Swap seul, puis swap + tip: autorisé
+        assert!(check(std::slice::from_ref(&swap), 0, &[pool], None).is_ok());
+        assert!(check(&[swap.clone(), tip.clone()], 0, &[pool], Some((tip_account, 10_000))).is_ok());
+
+        // (a) Une autre instruction écrit le pool: sandwich
+        let front_run = swap_instruction(Pubkey::new_unique(), pool);
+        let err = check(&[front_run.clone(), swap.clone()], 1, &[pool], None).unwrap_err();
+        assert_eq!(err, ErrorCode::SandwichDetected.into());
+        let err = check(&[swap.clone(), front_run], 0, &[pool], None).unwrap_err();
+        assert_eq!(err, ErrorCode::SandwichDetected.into());
+        // Lecture seule du pool par une autre instruction: autorisé
+        let reader = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(pool, false)],
+        );
+        assert!(check(&[reader, swap.clone()], 1, &[pool], None).is_ok());
+
+        // (b) Tip absent ou insuffisant
+        let err = check(std::slice::from_ref(&swap), 0, &[pool], Some((tip_account, 10_000))).unwrap_err();
+        assert_eq!(err, ErrorCode::JitoTipMissing.into());
+        let err = check(&[swap.clone(), tip], 0, &[pool], Some((tip_account, 10_001))).unwrap_err();
+        assert_eq!(err, ErrorCode::JitoTipMissing.into());
+
+        // (c) CPI: current_index désigne l'instruction de l'appelant, qui écrit le pool
+        let caller = swap_instruction(Pubkey::new_unique(), pool);
+        let compute_budget = Instruction::new_with_bytes(Pubkey::new_unique(), &[0], vec![]);
+        assert!(check(&[compute_budget.clone(), caller.clone()], 1, &[pool], None).is_ok());
+        // ... mais une autre instruction de l'appelant qui écrit le pool reste un sandwich
+        let err = check(&[caller.clone(), compute_budget, caller], 1, &[pool], None).unwrap_err();
+        assert_eq!(err, ErrorCode::SandwichDetected.into());
+    }
+
+    #[test]
+    fn test_writes_any() {
+        let pool = Pubkey::new_unique();
+        let instruction = |is_writable| Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta {
+                pubkey: pool,
+                is_signer: false,
+                is_writable,
+            }],
+            data: vec![],
+        };
+        assert_eq!(writes_any(&instruction(true), &[pool]), Some(pool));
+        // Lecture seule ou compte non protégé: autorisé
+        assert_eq!(writes_any(&instruction(false), &[pool]), None);
+        assert_eq!(writes_any(&instruction(true), &[Pubkey::new_unique()]), None);
+    }
+
+    #[test]
+    fn test_is_tip_transfer() {
+        let user = Pubkey::new_unique();
+        let tip_account = crate::JITO_TIP_ACCOUNTS[3];
+        let tip = system_instruction::transfer(&user, &tip_account, 10_000);
+        assert!(is_tip_transfer(&tip, &tip_account, 10_000));
+        assert!(!is_tip_transfer(&tip, &tip_account, 10_001));
+        assert!(!is_tip_transfer(&tip, &crate::JITO_TIP_ACCOUNTS[0], 1_000));
+
+        let other = system_instruction::assign(&user, &tip_account);
+        assert!(!is_tip_transfer(&other, &tip_account, 0));
+    }
+}
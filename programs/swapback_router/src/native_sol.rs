@@ -0,0 +1,169 @@
+//! SOL natif dans `swap_toc`
+//!
+//! - Wrap: l'ATA WSOL de l'utilisateur (créée si absente) est alimentée jusqu'à
+//!   `amount_in` lamports puis `sync_native`. Créée ici, elle est temporaire:
+//!   refermée après le swap (reliquat d'input + rente rendus en SOL).
+//! - Unwrap: un compte de sortie WSOL est refermé vers l'utilisateur après le swap.
+//! - ATA de sortie créée de façon idempotente pour la mint de sortie.
+//!
+//! Tout s'exécute avant `check_swap_mints`, qui valide ensuite les comptes A/B.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::token_interface;
+
+use crate::balance_delta::read_token_amount;
+use crate::lst_pricing::WSOL_MINT;
+use crate::token_extensions::user_token_account;
+use crate::{ErrorCode, NativeSolOptions, SwapToC};
+
+/// Comptes WSOL à refermer vers l'utilisateur une fois le swap terminé
+pub struct PendingUnwrap<'info> {
+    accounts: Vec<AccountInfo<'info>>,
+    user: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+}
+
+impl<'info> PendingUnwrap<'info> {
+    pub fn finish(self) -> Result<()> {
+        for account in self.accounts {
+            let lamports = account.lamports();
+            token_interface::close_account(CpiContext::new(
+                self.token_program.clone(),
+                token_interface::CloseAccount {
+                    account: account.clone(),
+                    destination: self.user.clone(),
+                    authority: self.user.clone(),
+                },
+            ))?;
+            msg!("🔓 Unwrapped {} lamports from {}", lamports, account.key);
+        }
+        Ok(())
+    }
+}
+
+/// Wrap de l'input et création des ATA avant le swap
+pub fn prepare<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    options: Option<NativeSolOptions>,
+    amount_in: u64,
+) -> Result<PendingUnwrap<'info>> {
+    let options = options.unwrap_or_default();
+    let accounts = &ctx.accounts;
+    let mut pending = PendingUnwrap {
+        accounts: Vec::new(),
+        user: accounts.user.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+    };
+
+    if options.wrap_input {
+        let input = accounts.user_token_account_a.to_account_info();
+        let mint = accounts
+            .input_mint
+            .as_ref()
+            .ok_or(ErrorCode::NativeSolAccountMissing)?
+            .to_account_info();
+        require_keys_eq!(*mint.key, WSOL_MINT, ErrorCode::InvalidTokenAccount);
+
+        let created = create_ata_idempotent(ctx, &input, &mint)?;
+        let shortfall = amount_in.saturating_sub(read_token_amount(&input)?);
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.user.to_account_info(),
+                        to: input.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+            token_interface::sync_native(CpiContext::new(
+                accounts.token_program.to_account_info(),
+                token_interface::SyncNative { account: input.clone() },
+            ))?;
+        }
+        msg!("🔒 Wrapped {} lamports into {} (temporary: {})", shortfall, input.key, created);
+        if created {
+            pending.accounts.push(input);
+        }
+    }
+
+    let output = accounts.user_token_account_b.to_account_info();
+    if options.create_output_ata {
+        let mint = accounts
+            .output_mint
+            .as_ref()
+            .ok_or(ErrorCode::NativeSolAccountMissing)?
+            .to_account_info();
+        if create_ata_idempotent(ctx, &output, &mint)? {
+            msg!("🆕 Created output ATA {} for mint {}", output.key, mint.key);
+        }
+    }
+
+    if options.unwrap_output {
+        require_keys_eq!(
+            user_token_account(&output)?.mint,
+            WSOL_MINT,
+            ErrorCode::InvalidTokenAccount
+        );
+        pending.accounts.push(output);
+    }
+
+    Ok(pending)
+}
+
+/// Crée l'ATA (user, mint) si elle n'existe pas; retourne `true` si créée
+///
+/// Une ATA existante est laissée telle quelle (validée par `check_swap_mints`).
+fn create_ata_idempotent<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    ata: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+) -> Result<bool> {
+    let accounts = &ctx.accounts;
+    require_keys_eq!(*mint.owner, accounts.token_program.key(), ErrorCode::InvalidTokenAccount);
+    require_ata(ata.key, &accounts.user.key(), mint.key, mint.owner)?;
+    if !ata.data_is_empty() {
+        return Ok(false);
+    }
+
+    let associated_token_program = accounts
+        .associated_token_program
+        .as_ref()
+        .ok_or(ErrorCode::NativeSolAccountMissing)?;
+    associated_token::create_idempotent(CpiContext::new(
+        associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: accounts.user.to_account_info(),
+            associated_token: ata.clone(),
+            authority: accounts.user.to_account_info(),
+            mint: mint.clone(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        },
+    ))?;
+    Ok(true)
+}
+
+fn require_ata(ata: &Pubkey, user: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Result<()> {
+    let expected = get_associated_token_address_with_program_id(user, mint, token_program);
+    require_keys_eq!(*ata, expected, ErrorCode::InvalidTokenAccount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_ata() {
+        let user = Pubkey::new_unique();
+        let ata = get_associated_token_address_with_program_id(&user, &WSOL_MINT, &anchor_spl::token::ID);
+        assert!(require_ata(&ata, &user, &WSOL_MINT, &anchor_spl::token::ID).is_ok());
+        // Même mint, autre programme token ou autre propriétaire: adresse différente
+        assert!(require_ata(&ata, &user, &WSOL_MINT, &anchor_spl::token_2022::ID).is_err());
+        assert!(require_ata(&ata, &Pubkey::new_unique(), &WSOL_MINT, &anchor_spl::token::ID).is_err());
+    }
+}
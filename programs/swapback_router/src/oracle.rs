@@ -1,128 +1,138 @@
-//! Oracle Module - Wrapper pour oracle_v2
+//! Oracle Module - Sélection du décodeur par feed
 //! 
-//! Ce module délègue maintenant vers oracle_v2.rs qui utilise Pyth V2.
-//! Conservé pour compatibilité avec le code existant.
+//! Chaque compte oracle est lu avec le décodeur de son programme propriétaire
+//! (Pyth V2 via oracle_v2.rs, Switchboard On-Demand, Switchboard V2 legacy
+//! derrière la feature `switchboard`).
 
 use anchor_lang::prelude::*;
+use swapback_price_source::{self as price_source, PriceSourceKind, SwitchboardOnDemand};
 
 use crate::{ErrorCode, OracleType};
-use crate::oracle_v2;
+use crate::oracle_v2::{self, PythUpdatePolicy};
 
 #[cfg(feature = "switchboard")]
-use switchboard_solana::AggregatorAccountData;
+use swapback_price_source::SwitchboardV2;
 
 // Re-export OracleObservation depuis oracle_v2
 pub use oracle_v2::OracleObservation;
 
-// Confidence maximale autorisée (5% = 500 bps) - Pyth seulement
-#[allow(dead_code)]
-const MAX_CONFIDENCE_BPS: u128 = 500;
-
 /// Read oracle price with configurable staleness threshold
 /// For volatile markets, use lower values (30-60s)
 /// Default: MAX_STALENESS_SECS (300s)
 /// 
-/// Cette fonction délègue maintenant vers oracle_v2 qui supporte Pyth V2
+/// Le décodeur est choisi par feed d'après le programme propriétaire du
+/// compte: un feed Switchboard invalide n'est plus relu comme un feed Pyth.
 pub fn read_price_with_staleness(
     oracle_account: &AccountInfo,
     clock: &Clock,
     max_staleness_secs: i64,
+    pyth_policy: &PythUpdatePolicy,
 ) -> Result<OracleObservation> {
     if oracle_account.key() == Pubkey::default() {
         return err!(ErrorCode::InvalidOraclePrice);
     }
 
-    // Strategy: Try Switchboard first, fallback to Pyth V2 if it fails
-    // This ensures maximum availability across oracle providers
-
-    // Primary: Switchboard Oracle (si feature activée)
-    #[cfg(feature = "switchboard")]
-    {
-        match try_read_switchboard(oracle_account, clock, max_staleness_secs) {
-            Ok(observation) => {
-                msg!("✅ Switchboard oracle used (staleness limit: {}s)", max_staleness_secs);
-                return Ok(observation);
-            }
-            Err(e) => {
-                msg!("⚠️ Switchboard failed: {:?}, attempting Pyth V2 fallback", e);
-            }
+    match price_source::detect_kind(oracle_account.owner) {
+        Some(PriceSourceKind::SwitchboardOnDemand) => {
+            read_switchboard_on_demand(oracle_account, clock, max_staleness_secs)
+        }
+        #[cfg(feature = "switchboard")]
+        Some(PriceSourceKind::SwitchboardV2) => {
+            let observation = try_read_switchboard(oracle_account, clock, max_staleness_secs)?;
+            msg!("✅ Switchboard oracle used (staleness limit: {}s)", max_staleness_secs);
+            Ok(observation)
         }
+        // Pyth V2 (oracle_v2 vérifie lui-même le propriétaire)
+        _ => oracle_v2::read_price_with_staleness(oracle_account, clock, max_staleness_secs, pyth_policy),
     }
+}
 
-    // Fallback: Pyth V2 via oracle_v2 module
-    oracle_v2::read_price_with_staleness(oracle_account, clock, max_staleness_secs)
+/// Convertit une staleness en secondes vers un écart de slots (~400ms/slot)
+pub fn max_slot_lag_for(max_staleness_secs: i64) -> u64 {
+    (max_staleness_secs.max(0) as u64).saturating_mul(5) / 2
 }
 
-#[cfg(feature = "switchboard")]
-fn try_read_switchboard(
+/// Switchboard On-Demand: médiane du résultat courant, écart-type comme
+/// confidence, staleness mesurée en slots.
+fn read_switchboard_on_demand(
     oracle_account: &AccountInfo,
     clock: &Clock,
     max_staleness_secs: i64,
 ) -> Result<OracleObservation> {
-    // Check if account is a Switchboard aggregator
-    let is_switchboard = {
-        if let Ok(data) = oracle_account.try_borrow_data() {
-            AggregatorAccountData::new_from_bytes(&data).is_ok()
-        } else {
-            false
+    let (feed, observation) = {
+        let data = oracle_account
+            .try_borrow_data()
+            .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
+        let feed = SwitchboardOnDemand::parse(&data);
+        (feed, price_source::read::<SwitchboardOnDemand>(oracle_account.owner, &data))
+    };
+    let (feed, observation) = match (feed, observation) {
+        (Ok(feed), Ok(observation)) => (feed, observation),
+        (_, Err(e)) | (Err(e), _) => {
+            msg!("⚠️ Invalid Switchboard On-Demand feed: {:?}", e);
+            return err!(ErrorCode::InvalidOraclePrice);
         }
     };
 
-    if !is_switchboard {
-        return err!(ErrorCode::InvalidOraclePrice);
+    // La limite configurée sur le feed s'applique si elle est plus stricte
+    let mut max_slot_lag = max_slot_lag_for(max_staleness_secs);
+    if feed.max_staleness_slots > 0 {
+        max_slot_lag = max_slot_lag.min(feed.max_staleness_slots as u64);
     }
-
-    let data = oracle_account
-        .try_borrow_data()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-
-    let aggregator = AggregatorAccountData::new_from_bytes(&data)
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-
-    let round = &aggregator.latest_confirmed_round;
-    let timestamp = round.round_open_timestamp;
-
-    // Vérifier staleness avec seuil configurable
-    let staleness = clock.unix_timestamp - timestamp;
-    if staleness > max_staleness_secs {
+    if observation.check_slot_lag(clock.slot, max_slot_lag).is_err() {
         msg!(
-            "⚠️ Switchboard data too old: {}s (max: {}s)",
-            staleness,
-            max_staleness_secs
+            "⚠️ Switchboard On-Demand result too old: slot {} (current: {}, max lag: {})",
+            observation.slot,
+            clock.slot,
+            max_slot_lag
         );
         return err!(ErrorCode::StaleOracleData);
     }
 
-    // get_result() retourne Result<SwitchboardDecimal, Error> dans v0.30.4
-    let result = aggregator
-        .get_result()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
+    oracle_v2::check_observation(&observation, clock, max_staleness_secs, "Switchboard On-Demand")?;
 
-    // Convertir SwitchboardDecimal en f64
-    let value: f64 = result
-        .try_into()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
+    msg!(
+        "✅ Switchboard On-Demand price: {} (confidence: {} bps, samples: {})",
+        observation.price,
+        observation.confidence_bps(),
+        feed.num_samples
+    );
 
-    if value.is_sign_negative() || value == 0.0 {
-        msg!("❌ Invalid Switchboard price: {}", value);
-        return err!(ErrorCode::InvalidOraclePrice);
+    Ok(OracleObservation::from_source(
+        &observation,
+        OracleType::SwitchboardOnDemand,
+        oracle_account.key(),
+        clock,
+    ))
+}
+
+#[cfg(feature = "switchboard")]
+fn try_read_switchboard(
+    oracle_account: &AccountInfo,
+    clock: &Clock,
+    max_staleness_secs: i64,
+) -> Result<OracleObservation> {
+    // Décodage manuel de AggregatorAccountData (entier, sans conversion f64)
+    let observation = {
+        let data = oracle_account
+            .try_borrow_data()
+            .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
+        price_source::read::<SwitchboardV2>(oracle_account.owner, &data)
     }
+    .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
 
-    // Convertir en format 8 décimales (prix * 10^8)
-    let price_scaled = (value * 100_000_000_f64) as u64;
+    oracle_v2::check_observation(&observation, clock, max_staleness_secs, "Switchboard")?;
 
     msg!(
-        "✅ Switchboard price read: ${} ({} lamports)",
-        value,
-        price_scaled
+        "✅ Switchboard price read: {} (confidence: {} bps)",
+        observation.price,
+        observation.confidence_bps()
     );
 
-    Ok(OracleObservation {
-        price: price_scaled,
-        confidence: 0, // Switchboard n'expose pas confidence directement
-        publish_time: timestamp,
-        slot: clock.slot,
-        oracle_type: OracleType::Switchboard,
-        feed: oracle_account.key(),
-    })
+    Ok(OracleObservation::from_source(
+        &observation,
+        OracleType::Switchboard,
+        oracle_account.key(),
+        clock,
+    ))
 }
@@ -1,24 +1,98 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
+use crate::{
+    DEFAULT_PAIR_DIVERGENCE_STRIKES, DEFAULT_PAIR_HALT_SECS, DEFAULT_PAIR_MAX_MOVE_BPS,
+    DEFAULT_PAIR_MOVE_WINDOW_SECS,
+};
+
+/// Cache oracle d'une paire, PDA `[b"oracle_cache", token_pair[0], token_pair[1]]`
+/// (mints triées: une seule cache, donc un seul disjoncteur, par paire)
 #[account]
 pub struct OracleCache {
     pub token_pair: [Pubkey; 2],
+    pub primary_oracle: Pubkey,  // Feed lu par les swaps et le crank de la paire
+    pub fallback_oracle: Pubkey, // Feed de divergence (Pubkey::default() = aucun)
     pub cached_price: u64,
     pub cached_at: i64,
     pub cache_duration: i64, // 5 seconds default
     pub volatility_bps: u16, // Market volatility in basis points
+
+    // Circuit breaker (par paire)
+    pub reference_price: u64,        // Prix d'ancrage de la fenêtre courante
+    pub reference_at: i64,           // Début de la fenêtre courante
+    pub halted_until: i64,           // Swaps refusés tant que now < halted_until
+    pub max_price_move_bps: u16,     // Mouvement max dans la fenêtre (0 = désactivé)
+    pub price_move_window_secs: i64, // Durée de la fenêtre de mesure
+    pub halt_duration_secs: i64,     // Durée du halt une fois déclenché
+    pub divergence_strikes: u8,      // Divergences primary/fallback consécutives
+    pub max_divergence_strikes: u8,  // Seuil de déclenchement (0 = désactivé)
+
     pub bump: u8,
 }
 
 impl OracleCache {
     pub const LEN: usize = 8 + // discriminator
         32 * 2 + // token_pair
+        32 + // primary_oracle
+        32 + // fallback_oracle
         8 + // cached_price
         8 + // cached_at
         8 + // cache_duration
         2 + // volatility_bps
+        8 + // reference_price
+        8 + // reference_at
+        8 + // halted_until
+        2 + // max_price_move_bps
+        8 + // price_move_window_secs
+        8 + // halt_duration_secs
+        1 + // divergence_strikes
+        1 + // max_divergence_strikes
         1; // bump
 
+    /// Layout d'avant le circuit breaker: token_pair, cached_price, cached_at,
+    /// cache_duration, volatility_bps, bump (migré par `migrate_oracle_cache`)
+    pub const LEGACY_LEN: usize = 8 + 32 * 2 + 8 + 8 + 8 + 2 + 1;
+
+    /// Relit un cache au layout legacy; le circuit breaker prend les valeurs par défaut
+    /// et la paire est remise dans l'ordre canonique
+    pub fn from_legacy(data: &[u8], primary_oracle: Pubkey, fallback_oracle: Pubkey) -> Option<Self> {
+        if data.len() != Self::LEGACY_LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let key_at = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        Some(Self {
+            token_pair: canonical_pair(key_at(8), key_at(40)),
+            primary_oracle,
+            fallback_oracle,
+            cached_price: u64_at(72),
+            cached_at: u64_at(80) as i64,
+            cache_duration: u64_at(88) as i64,
+            volatility_bps: u16::from_le_bytes([data[96], data[97]]),
+            reference_price: 0,
+            reference_at: 0,
+            halted_until: 0,
+            max_price_move_bps: DEFAULT_PAIR_MAX_MOVE_BPS,
+            price_move_window_secs: DEFAULT_PAIR_MOVE_WINDOW_SECS,
+            halt_duration_secs: DEFAULT_PAIR_HALT_SECS,
+            divergence_strikes: 0,
+            max_divergence_strikes: DEFAULT_PAIR_DIVERGENCE_STRIKES,
+            bump: data[98],
+        })
+    }
+
+    /// Whether the swap input/output mints (either direction) are this cache's pair
+    pub fn covers(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> bool {
+        self.token_pair == canonical_pair(*input_mint, *output_mint)
+    }
+
+    /// Configured fallback oracle, if any
+    pub fn fallback(&self) -> Option<Pubkey> {
+        (self.fallback_oracle != Pubkey::default()).then_some(self.fallback_oracle)
+    }
+
     pub fn is_stale(&self, current_time: i64) -> bool {
         current_time - self.cached_at > self.cache_duration
     }
@@ -29,4 +103,209 @@ impl OracleCache {
         self.volatility_bps = volatility_bps;
         self.cached_at = current_time;
     }
+
+    /// Whether swaps on this pair are currently halted by the circuit breaker
+    pub fn is_halted(&self, current_time: i64) -> bool {
+        current_time < self.halted_until
+    }
+
+    /// Record a new oracle price and check it against the window reference.
+    /// Returns `Some(move_bps)` when the move exceeds `max_price_move_bps`
+    /// (the cache is left untouched so the caller can halt the pair).
+    pub fn observe_price(&mut self, price: u64, current_time: i64) -> Option<u64> {
+        let window_expired =
+            current_time.saturating_sub(self.reference_at) > self.price_move_window_secs;
+        if self.reference_price == 0 || window_expired {
+            self.reference_price = price;
+            self.reference_at = current_time;
+        } else {
+            let move_bps = price_move_bps(self.reference_price, price);
+            if self.max_price_move_bps > 0 && move_bps > self.max_price_move_bps as u64 {
+                return Some(move_bps);
+            }
+        }
+
+        self.update(price, self.volatility_bps, current_time);
+        None
+    }
+
+    /// Count consecutive primary/fallback divergences.
+    /// Returns true when the strike threshold is reached.
+    pub fn record_divergence(&mut self, diverged: bool) -> bool {
+        if !diverged {
+            self.divergence_strikes = 0;
+            return false;
+        }
+
+        self.divergence_strikes = self.divergence_strikes.saturating_add(1);
+        self.max_divergence_strikes > 0 && self.divergence_strikes >= self.max_divergence_strikes
+    }
+
+    /// Halt the pair for `halt_duration_secs` and restart the measurement window
+    pub fn halt(&mut self, current_time: i64) {
+        self.halted_until = current_time.saturating_add(self.halt_duration_secs);
+        self.divergence_strikes = 0;
+        self.reference_price = 0;
+        self.reference_at = 0;
+    }
+
+    /// Clear any halt (authority override)
+    pub fn resume(&mut self) {
+        self.halted_until = 0;
+        self.divergence_strikes = 0;
+        self.reference_price = 0;
+        self.reference_at = 0;
+    }
+}
+
+/// Paire dans l'ordre des seeds de l'OracleCache (A→B et B→A partagent la cache)
+pub fn canonical_pair(mint_a: Pubkey, mint_b: Pubkey) -> [Pubkey; 2] {
+    if mint_a <= mint_b {
+        [mint_a, mint_b]
+    } else {
+        [mint_b, mint_a]
+    }
+}
+
+/// Absolute price move between two prices, in basis points of `reference`
+pub fn price_move_bps(reference: u64, price: u64) -> u64 {
+    if reference == 0 {
+        return 0;
+    }
+    let diff = reference.abs_diff(price) as u128;
+    (diff * 10_000 / reference as u128).min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> OracleCache {
+        OracleCache {
+            token_pair: [Pubkey::default(); 2],
+            primary_oracle: Pubkey::default(),
+            fallback_oracle: Pubkey::default(),
+            cached_price: 0,
+            cached_at: 0,
+            cache_duration: 5,
+            volatility_bps: 50,
+            reference_price: 0,
+            reference_at: 0,
+            halted_until: 0,
+            max_price_move_bps: 1_000,
+            price_move_window_secs: 60,
+            halt_duration_secs: 300,
+            divergence_strikes: 0,
+            max_divergence_strikes: 3,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_price_move_bps() {
+        assert_eq!(price_move_bps(100_000_000, 110_000_000), 1_000);
+        assert_eq!(price_move_bps(100_000_000, 90_000_000), 1_000);
+        assert_eq!(price_move_bps(0, 90_000_000), 0);
+    }
+
+    #[test]
+    fn test_price_jump_trips_within_window() {
+        let mut c = cache();
+        assert_eq!(c.observe_price(100_000_000, 1_000), None);
+        assert_eq!(c.observe_price(105_000_000, 1_010), None);
+        // -12% vs reference at t=1000
+        assert_eq!(c.observe_price(88_000_000, 1_030), Some(1_200));
+        assert_eq!(c.cached_price, 105_000_000, "tripping price must not be cached");
+
+        c.halt(1_030);
+        assert!(c.is_halted(1_329));
+        assert!(!c.is_halted(1_330));
+    }
+
+    #[test]
+    fn test_window_rollover_resets_reference() {
+        let mut c = cache();
+        assert_eq!(c.observe_price(100_000_000, 1_000), None);
+        // Same move but after the window: new reference, no trip
+        assert_eq!(c.observe_price(88_000_000, 1_061), None);
+        assert_eq!(c.reference_price, 88_000_000);
+    }
+
+    #[test]
+    fn test_disabled_breaker_never_trips() {
+        let mut c = cache();
+        c.max_price_move_bps = 0;
+        c.max_divergence_strikes = 0;
+        assert_eq!(c.observe_price(100_000_000, 1_000), None);
+        assert_eq!(c.observe_price(10_000_000, 1_001), None);
+        assert!(!c.record_divergence(true));
+        assert!(!c.record_divergence(true));
+    }
+
+    #[test]
+    fn test_divergence_strikes_must_be_consecutive() {
+        let mut c = cache();
+        assert!(!c.record_divergence(true));
+        assert!(!c.record_divergence(true));
+        assert!(!c.record_divergence(false));
+        assert!(!c.record_divergence(true));
+        assert!(!c.record_divergence(true));
+        assert!(c.record_divergence(true));
+    }
+
+    #[test]
+    fn test_from_legacy_layout() {
+        let pair = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = OracleCache::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(pair[0].as_ref());
+        data.extend_from_slice(pair[1].as_ref());
+        data.extend_from_slice(&150_000_000u64.to_le_bytes());
+        data.extend_from_slice(&1_000i64.to_le_bytes());
+        data.extend_from_slice(&5i64.to_le_bytes());
+        data.extend_from_slice(&75u16.to_le_bytes());
+        data.push(254);
+        assert_eq!(data.len(), OracleCache::LEGACY_LEN);
+
+        let (primary, fallback) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let migrated = OracleCache::from_legacy(&data, primary, fallback).unwrap();
+        assert_eq!(migrated.token_pair, canonical_pair(pair[1], pair[0]));
+        assert_eq!(migrated.primary_oracle, primary);
+        assert_eq!(migrated.fallback(), Some(fallback));
+        assert_eq!(migrated.cached_price, 150_000_000);
+        assert_eq!(migrated.cached_at, 1_000);
+        assert_eq!(migrated.cache_duration, 5);
+        assert_eq!(migrated.volatility_bps, 75);
+        assert_eq!(migrated.bump, 254);
+        assert_eq!(migrated.max_price_move_bps, DEFAULT_PAIR_MAX_MOVE_BPS);
+        assert!(!migrated.is_halted(1_000));
+
+        // Cache déjà migré ou autre compte: refusé
+        let mut current = Vec::new();
+        migrated.try_serialize(&mut current).unwrap();
+        assert_eq!(current.len(), OracleCache::LEN);
+        assert!(OracleCache::from_legacy(&current, primary, fallback).is_none());
+        data[0] ^= 1;
+        assert!(OracleCache::from_legacy(&data, primary, fallback).is_none());
+    }
+
+    #[test]
+    fn test_pair_is_canonical() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut c = cache();
+        c.token_pair = canonical_pair(a, b);
+        assert_eq!(canonical_pair(a, b), canonical_pair(b, a));
+        assert!(c.covers(&a, &b));
+        assert!(c.covers(&b, &a));
+        assert!(!c.covers(&a, &Pubkey::new_unique()));
+        assert_eq!(c.fallback(), None);
+    }
+
+    #[test]
+    fn test_resume_clears_halt() {
+        let mut c = cache();
+        c.halt(1_000);
+        assert!(c.is_halted(1_100));
+        c.resume();
+        assert!(!c.is_halted(1_100));
+    }
 }
@@ -1,11 +1,14 @@
 //! Oracle Module V2 - Compatible Pyth V2 Pull Oracle
 //! 
 //! Ce module lit les prix des oracles Pyth V2 (PriceUpdateV2 accounts).
-//! Utilise une désérialisation manuelle pour éviter les conflits de version Anchor.
+//! Le décodage des comptes est partagé avec le client via
+//! `swapback_price_source`; ce module applique la politique du router.
 
 use anchor_lang::prelude::*;
+use swapback_price_source::pyth_pull::{PriceUpdateV2, VerificationLevel};
+use swapback_price_source::{self as price_source, PriceObservation, PriceSourceError, PythPull};
 
-use crate::{ErrorCode, OracleType};
+use crate::{ErrorCode, OracleType, RouterState};
 
 /// Observation d'oracle normalisée
 #[derive(Clone, Copy)]
@@ -19,11 +22,7 @@ pub struct OracleObservation {
 }
 
 // Confidence maximale autorisée (5% = 500 bps)
-const MAX_CONFIDENCE_BPS: u128 = 500;
-
-// Pyth Pull Oracle Program ID (mainnet)
-// rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ
-const PYTH_RECEIVER_PROGRAM_ID: &str = "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ";
+const MAX_CONFIDENCE_BPS: u64 = 500;
 
 // Pyth Feed IDs (hex) pour les tokens supportés
 // Voir: https://docs.pyth.network/price-feeds/core/price-feeds
@@ -40,258 +39,217 @@ pub mod feed_ids {
     pub const WIF_USD: &str = "0x4ca4beeca86f0d164160323817a4e42b10010a724c2217c6ee41b54cd4cc61fc";
 }
 
+/// Politique d'acceptation des mises à jour Pyth pull.
+/// Configurée dans RouterConfig et recopiée dans RouterState pour les swaps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PythUpdatePolicy {
+    /// Refuse toute mise à jour Partial
+    pub require_full_verification: bool,
+    /// Signatures Wormhole minimales pour une mise à jour Partial
+    pub min_signatures: u8,
+    /// Écart max entre posted_slot et Clock::slot (0 = désactivé)
+    pub max_slot_lag: u64,
+}
+
+impl PythUpdatePolicy {
+    pub fn from_state(state: &RouterState) -> Self {
+        Self {
+            require_full_verification: state.pyth_require_full_verification,
+            min_signatures: state.pyth_min_signatures,
+            max_slot_lag: state.pyth_max_slot_lag,
+        }
+    }
+
+    /// Vérifie le niveau de vérification et la fraîcheur en slots d'une mise à jour
+    pub fn check(&self, update: &PriceUpdateV2, current_slot: u64) -> Result<()> {
+        match update.verification_level {
+            VerificationLevel::Full => {}
+            VerificationLevel::Partial { num_signatures } => {
+                if self.require_full_verification || num_signatures < self.min_signatures {
+                    msg!(
+                        "❌ Pyth V2 update partially verified: {} signatures (min: {}, full required: {})",
+                        num_signatures,
+                        self.min_signatures,
+                        self.require_full_verification
+                    );
+                    return err!(ErrorCode::InsufficientOracleVerification);
+                }
+            }
+        }
+
+        if self.max_slot_lag > 0 {
+            let slot_lag = current_slot.saturating_sub(update.posted_slot);
+            if slot_lag > self.max_slot_lag {
+                msg!(
+                    "⚠️ Pyth V2 update too old: posted at slot {} ({} slots ago, max: {})",
+                    update.posted_slot,
+                    slot_lag,
+                    self.max_slot_lag
+                );
+                return err!(ErrorCode::StaleOracleData);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Lit le prix d'un compte Pyth V2 (PriceUpdateV2) depuis un AccountInfo brut.
 /// Compatible avec les Push Feed Accounts sponsorisés par Pyth Data Association.
+/// Le décodage est délégué à `swapback_price_source::PythPull`.
 /// 
 /// # Arguments
 /// * `oracle_account` - AccountInfo du compte PriceUpdateV2 (owné par Pyth Receiver)
 /// * `clock` - Clock sysvar
 /// * `max_staleness_secs` - Âge maximum du prix en secondes
+/// * `policy` - Vérification Wormhole et écart de slots exigés
 pub fn read_price_with_staleness(
     oracle_account: &AccountInfo,
     clock: &Clock,
     max_staleness_secs: i64,
+    policy: &PythUpdatePolicy,
 ) -> Result<OracleObservation> {
     if oracle_account.key() == Pubkey::default() {
         return err!(ErrorCode::InvalidOraclePrice);
     }
 
-    // Vérifier que le compte est owné par le Pyth Pull Oracle program
-    let expected_owner = PYTH_RECEIVER_PROGRAM_ID.parse::<Pubkey>()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    if oracle_account.owner != &expected_owner {
-        msg!("⚠️ Account not owned by Pyth Receiver program (owner: {})", oracle_account.owner);
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
-    
-    // Deserialize the PriceUpdateV2 account data
-    let data = oracle_account.try_borrow_data()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    // PriceUpdateV2 structure from pyth_solana_receiver_sdk:
-    // - 8 bytes: Anchor discriminator
-    // - 32 bytes: write_authority (Pubkey, NOT Option!)
-    // - VerificationLevel enum (Borsh):
-    //     * variant 0 => Partial { num_signatures: u8 } (2 bytes total)
-    //     * variant 1 => Full (1 byte total)
-    // - PriceFeedMessage fields:
-    //   - 32 bytes: feed_id [u8; 32]
-    //   - 8 bytes: price (i64)
-    //   - 8 bytes: conf (u64)
-    //   - 4 bytes: exponent (i32)
-    //   - 8 bytes: publish_time (i64)
-    //   - 8 bytes: prev_publish_time (i64)
-    //   - 8 bytes: ema_price (i64)
-    //   - 8 bytes: ema_conf (u64)
-    // - 8 bytes: posted_slot (u64)
-    
-    const DISCRIMINATOR_LEN: usize = 8;
-    const WRITE_AUTHORITY_LEN: usize = 32;
-    const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8 + 8 + 8 + 8;
-    const POSTED_SLOT_LEN: usize = 8;
+    let decoded = {
+        let data = oracle_account
+            .try_borrow_data()
+            .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
+        price_source::read::<PythPull>(oracle_account.owner, &data)
+            .and_then(|observation| Ok((observation, PythPull::parse(&data)?)))
+    };
+    let (observation, update) = decoded.map_err(|e| {
+        if e == PriceSourceError::InvalidOwner {
+            msg!("⚠️ Account not owned by Pyth Receiver program (owner: {})", oracle_account.owner);
+        } else {
+            msg!("⚠️ Invalid PriceUpdateV2 account: {:?}", e);
+        }
+        error!(ErrorCode::InvalidOraclePrice)
+    })?;
 
-    if data.len() < DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN + 1 + PRICE_MESSAGE_LEN {
-        msg!(
-            "⚠️ PriceUpdateV2 data too short: {} bytes",
-            data.len()
-        );
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
+    policy.check(&update, clock.slot)?;
+    let staleness = check_observation(&observation, clock, max_staleness_secs, "Pyth V2")?;
 
-    // Skip discriminator + write authority
-    let mut msg_offset = DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN;
+    msg!(
+        "✅ Pyth V2 price: {} (confidence: {} bps, staleness: {}s)",
+        observation.price,
+        observation.confidence_bps(),
+        staleness
+    );
 
-    // Parse VerificationLevel enum (Borsh encoded)
-    let verification_variant = *data
-        .get(msg_offset)
-        .ok_or_else(|| error!(ErrorCode::InvalidOraclePrice))?;
-    msg_offset += 1;
+    Ok(OracleObservation::from_source(
+        &observation,
+        OracleType::Pyth,
+        oracle_account.key(),
+        clock,
+    ))
+}
 
-    match verification_variant {
-        0 => {
-            // Partial verification: read num_signatures byte
-            let _num_signatures = *data
-                .get(msg_offset)
-                .ok_or_else(|| error!(ErrorCode::InvalidOraclePrice))?;
-            msg_offset += 1;
-        }
-        1 => {
-            // Full verification: nothing more to read
+/// Politique commune du router (staleness + confidence) sur une observation
+/// décodée. Retourne l'âge du prix en secondes.
+pub(crate) fn check_observation(
+    observation: &PriceObservation,
+    clock: &Clock,
+    max_staleness_secs: i64,
+    label: &str,
+) -> Result<i64> {
+    let staleness = observation.age(clock.unix_timestamp);
+    match observation.check_staleness(clock.unix_timestamp, max_staleness_secs) {
+        Ok(()) => {}
+        Err(PriceSourceError::StalePrice) => {
+            msg!(
+                "⚠️ {} data too old: {}s (max: {}s)",
+                label,
+                staleness,
+                max_staleness_secs
+            );
+            return err!(ErrorCode::StaleOracleData);
         }
-        other => {
-            msg!("⚠️ Unknown Pyth verification variant: {}", other);
+        Err(_) => {
+            msg!("⚠️ {} timestamp is in the future: {}s", label, -staleness);
             return err!(ErrorCode::InvalidOraclePrice);
         }
     }
 
-    let min_required_len = msg_offset + PRICE_MESSAGE_LEN;
-    if data.len() < min_required_len {
-        msg!(
-            "⚠️ PriceUpdateV2 data too short for message fields: {} < {}",
-            data.len(),
-            min_required_len
-        );
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
-    
-    // Read price message fields
-    // feed_id: [u8; 32] at msg_offset
-    #[allow(unused_variables)]
-    let mut feed_id = [0u8; 32];
-    feed_id.copy_from_slice(&data[msg_offset..msg_offset + 32]);
-    
-    // price: i64 (little-endian) at msg_offset + 32
-    let price_bytes: [u8; 8] = data[msg_offset + 32..msg_offset + 40].try_into()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    let price_raw = i64::from_le_bytes(price_bytes);
-    
-    // conf: u64 (little-endian) at msg_offset + 40
-    let conf_bytes: [u8; 8] = data[msg_offset + 40..msg_offset + 48].try_into()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    let conf_raw = u64::from_le_bytes(conf_bytes);
-    
-    // exponent: i32 (little-endian) at msg_offset + 48
-    let exp_bytes: [u8; 4] = data[msg_offset + 48..msg_offset + 52].try_into()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    let exponent = i32::from_le_bytes(exp_bytes);
-    
-    // publish_time: i64 (little-endian) at msg_offset + 52
-    let pub_time_bytes: [u8; 8] = data[msg_offset + 52..msg_offset + 60].try_into()
-        .map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    let publish_time = i64::from_le_bytes(pub_time_bytes);
-
-    // posted_slot (optional - not used but validates layout)
-    let slot_offset = msg_offset + PRICE_MESSAGE_LEN;
-    if data.len() < slot_offset + POSTED_SLOT_LEN {
-        msg!("⚠️ Missing posted_slot in PriceUpdateV2 account");
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
-    
-    // Drop borrow before any further operations
-    drop(data);
-    
-    // Vérifier staleness
-    let staleness = clock.unix_timestamp - publish_time;
-    if staleness > max_staleness_secs {
+    if observation.check_confidence(MAX_CONFIDENCE_BPS).is_err() {
         msg!(
-            "⚠️ Pyth V2 data too old: {}s (max: {}s)",
-            staleness,
-            max_staleness_secs
-        );
-        return err!(ErrorCode::StaleOracleData);
-    }
-    
-    // Reject negative staleness (future timestamp)
-    if staleness < -60 {
-        msg!("⚠️ Pyth V2 timestamp is in the future: {}s", -staleness);
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
-    
-    // Normaliser le prix en 8 décimales
-    let price_scaled = normalize_decimal(price_raw as i128, exponent)
-        .ok_or_else(|| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    if price_scaled == 0 {
-        msg!("❌ Pyth V2 price is zero");
-        return err!(ErrorCode::InvalidOraclePrice);
-    }
-    
-    let confidence_scaled = normalize_decimal(conf_raw as i128, exponent)
-        .ok_or_else(|| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    // Vérifier confidence
-    let confidence_bps = confidence_scaled
-        .checked_mul(10_000)
-        .and_then(|value| value.checked_div(price_scaled))
-        .ok_or_else(|| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    if confidence_bps > MAX_CONFIDENCE_BPS {
-        msg!(
-            "❌ Pyth V2 confidence too wide: {} bps (max: {})",
-            confidence_bps,
+            "❌ {} confidence too wide: {} bps (max: {})",
+            label,
+            observation.confidence_bps(),
             MAX_CONFIDENCE_BPS
         );
         return err!(ErrorCode::InvalidOraclePrice);
     }
-    
-    let normalized_price =
-        u64::try_from(price_scaled).map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    let normalized_confidence =
-        u64::try_from(confidence_scaled).map_err(|_| error!(ErrorCode::InvalidOraclePrice))?;
-    
-    msg!(
-        "✅ Pyth V2 price: {} (confidence: {} bps, staleness: {}s)",
-        normalized_price,
-        confidence_bps,
-        staleness
-    );
-    
-    Ok(OracleObservation {
-        price: normalized_price,
-        confidence: normalized_confidence,
-        publish_time,
-        slot: clock.slot,
-        oracle_type: OracleType::Pyth,
-        feed: oracle_account.key(),
-    })
-}
-
-/// Normalise un nombre décimal vers 8 décimales (format standard Pyth/Switchboard)
-fn normalize_decimal(value: i128, expo: i32) -> Option<u128> {
-    let target_exponent = -8;
-    let exponent_diff = target_exponent - expo;
-    let mut scaled = value;
 
-    if exponent_diff >= 0 {
-        scaled = scaled.checked_mul(pow10_i128(exponent_diff as u32)?)?;
-    } else {
-        scaled = scaled.checked_div(pow10_i128((-exponent_diff) as u32)?)?;
-    }
-
-    if scaled < 0 {
-        None
-    } else {
-        Some(scaled as u128)
-    }
+    Ok(staleness)
 }
 
-fn pow10_i128(exp: u32) -> Option<i128> {
-    let mut value: i128 = 1;
-    for _ in 0..exp {
-        value = value.checked_mul(10)?;
+impl OracleObservation {
+    /// Convertit une observation du crate partagé vers le type du router.
+    /// Le slot de publication de l'oracle est conservé quand il est connu.
+    pub fn from_source(
+        observation: &PriceObservation,
+        oracle_type: OracleType,
+        feed: Pubkey,
+        clock: &Clock,
+    ) -> Self {
+        Self {
+            price: observation.price,
+            confidence: observation.confidence,
+            publish_time: observation.publish_time,
+            slot: if observation.slot > 0 { observation.slot } else { clock.slot },
+            oracle_type,
+            feed,
+        }
     }
-    Some(value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn update(verification_level: VerificationLevel, posted_slot: u64) -> PriceUpdateV2 {
+        PriceUpdateV2 {
+            verification_level,
+            feed_id: [0u8; 32],
+            price: 15_050_000_000,
+            conf: 0,
+            exponent: -8,
+            publish_time: 0,
+            posted_slot,
+        }
+    }
+
+    #[test]
+    fn test_pyth_policy_verification_level() {
+        let policy = PythUpdatePolicy {
+            require_full_verification: false,
+            min_signatures: 5,
+            max_slot_lag: 0,
+        };
+        assert!(policy.check(&update(VerificationLevel::Full, 0), 100).is_ok());
+        assert!(policy.check(&update(VerificationLevel::Partial { num_signatures: 5 }, 0), 100).is_ok());
+        assert!(policy.check(&update(VerificationLevel::Partial { num_signatures: 4 }, 0), 100).is_err());
+
+        let strict = PythUpdatePolicy { require_full_verification: true, ..policy };
+        assert!(strict.check(&update(VerificationLevel::Partial { num_signatures: 13 }, 0), 100).is_err());
+        assert!(strict.check(&update(VerificationLevel::Full, 0), 100).is_ok());
+    }
+
     #[test]
-    fn test_normalize_decimal() {
-        // Prix de $150.50 avec expo -8 (format Pyth standard)
-        // 15050000000 * 10^-8 = $150.50
-        // diff = -8 - (-8) = 0, donc pas de changement
-        assert_eq!(normalize_decimal(15050000000, -8), Some(15050000000));
-        
-        // Prix de $1.50 avec expo -8
-        // 150000000 * 10^-8 = $1.50
-        assert_eq!(normalize_decimal(150000000, -8), Some(150000000));
-        
-        // Prix de $150.50 avec expo -6
-        // 150500000 * 10^-6 = $150.50
-        // diff = -8 - (-6) = -2, donc divise par 10^2 = 100
-        // 150500000 / 100 = 1505000
-        assert_eq!(normalize_decimal(150500000, -6), Some(1505000));
-        
-        // Prix de $150.50 avec expo -10
-        // 1505000000000 * 10^-10 = $150.50
-        // diff = -8 - (-10) = 2, donc multiplie par 10^2 = 100
-        // 1505000000000 * 100 = overflow? Non, car i128
-        assert_eq!(normalize_decimal(1505000000000_i128, -10), Some(150500000000000));
-        
-        // Prix négatif -> None
-        assert_eq!(normalize_decimal(-100, -8), None);
+    fn test_pyth_policy_slot_lag() {
+        let policy = PythUpdatePolicy {
+            require_full_verification: true,
+            min_signatures: 0,
+            max_slot_lag: 50,
+        };
+        assert!(policy.check(&update(VerificationLevel::Full, 1_000), 1_050).is_ok());
+        assert!(policy.check(&update(VerificationLevel::Full, 1_000), 1_051).is_err());
+
+        // 0 désactive le contrôle en slots
+        let disabled = PythUpdatePolicy { max_slot_lag: 0, ..policy };
+        assert!(disabled.check(&update(VerificationLevel::Full, 1_000), 9_999).is_ok());
     }
 }
@@ -0,0 +1,772 @@
+//! Mode maker Phoenix: ordres post-only posés par un siège propre à chaque utilisateur
+//!
+//! Le PDA [b"phoenix_maker", market, user] détient le siège Phoenix de l'utilisateur
+//! (approuvé par l'autorité du marché ou le seat manager) et ses deux comptes escrow
+//! base/quote ([b"phoenix_maker_vault", market, user, mint]). Aucun fonds n'est partagé
+//! entre utilisateurs: siège et escrows ne contiennent que ceux de leur propriétaire.
+//!
+//! - Pose: l'input est déposé dans l'escrow, Phoenix y prélève ce qui manque aux fonds
+//!   libres du siège; le reste de l'escrow est rendu aussitôt. L'identifiant de l'ordre
+//!   est lu dans le FIFOMarket (`order_sequence_number`) et l'ordre doit reposer au carnet.
+//! - Règlement: annulation du reliquat s'il est encore au carnet, retrait de tous les fonds
+//!   libres du siège, puis versement à l'utilisateur des soldes réels des escrows. Les fonds
+//!   rendus par ForceCancelOrders / EvictSeat sont ainsi restitués, jamais comptés exécutés.
+//!   Avec plusieurs ordres ouverts, le produit déjà exécuté des autres ordres suit aussi.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_spl::token;
+
+use std::cmp::Ordering;
+
+use crate::balance_delta::read_token_amount;
+use crate::state::MAX_PHOENIX_ORDER_TTL_SECS;
+use crate::{
+    error, ErrorCode, InitializePhoenixMaker, PhoenixMakerOrderPlaced, PhoenixMakerOrderSettled,
+    PlacePhoenixMakerOrder, SettlePhoenixMakerOrder, PHOENIX_PROGRAM_ID,
+};
+
+/// Phoenix instruction tags (u8)
+const PLACE_LIMIT_ORDER_TAG: u8 = 2;
+const CANCEL_MULTIPLE_ORDERS_BY_ID_TAG: u8 = 10;
+const WITHDRAW_FUNDS_TAG: u8 = 12;
+const REQUEST_SEAT_TAG: u8 = 14;
+
+/// OrderPacket::PostOnly
+const ORDER_PACKET_POST_ONLY: u8 = 0;
+
+/// Side Phoenix
+pub const SIDE_BID: u8 = 0;
+pub const SIDE_ASK: u8 = 1;
+
+/// Taille d'un compte Seat Phoenix (discriminant, market, trader, approval_status, padding)
+const PHOENIX_SEAT_LEN: usize = 8 + 32 + 32 + 8 + 48;
+
+/// MarketHeader Phoenix (576 octets, voir `cpi_phoenix` pour les lot sizes)
+const HEADER_BASE_DECIMALS_OFFSET: usize = 40;
+const HEADER_BASE_MINT_OFFSET: usize = 48;
+const HEADER_BASE_VAULT_OFFSET: usize = 80;
+const HEADER_BASE_LOT_SIZE_OFFSET: usize = 112;
+const HEADER_QUOTE_MINT_OFFSET: usize = 128;
+const HEADER_QUOTE_VAULT_OFFSET: usize = 160;
+const HEADER_QUOTE_LOT_SIZE_OFFSET: usize = 192;
+const HEADER_TICK_SIZE_OFFSET: usize = 200;
+const HEADER_RAW_BASE_UNITS_OFFSET: usize = 312;
+const HEADER_LEN: usize = HEADER_RAW_BASE_UNITS_OFFSET + 4;
+
+/// FIFOMarket (après l'en-tête) et arbres rouge-noir sokoban
+pub(crate) const PHOENIX_BIDS_SIZE_OFFSET: usize = 16;
+const PHOENIX_ORDER_SEQUENCE_NUMBER_OFFSET: usize = 848;
+pub(crate) const PHOENIX_TAKER_FEE_BPS_OFFSET: usize = 856;
+pub(crate) const PHOENIX_BIDS_OFFSET: usize = 880;
+/// root (u32) + padding, puis size (u64), bump_index (u32), free_list_head (u32)
+pub(crate) const RB_TREE_HEADER_LEN: usize = 32;
+/// registres [left, right, parent, color] + FIFOOrderId (16) + FIFORestingOrder (32)
+pub(crate) const RB_NODE_LEN: usize = 64;
+pub(crate) const RB_LEFT: usize = 0;
+const RB_RIGHT: usize = 1;
+/// Profondeur bornée: un arbre rouge-noir valide ne dépasse pas 2 * log2(n)
+pub(crate) const RB_MAX_DEPTH: usize = 128;
+
+/// Champs de l'en-tête de marché utiles au mode maker
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhoenixMarketHeader {
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub tick_size_in_quote_atoms_per_base_unit: u64,
+    /// Atoms de base par unité de base (10^decimals * raw_base_units_per_base_unit)
+    pub base_atoms_per_base_unit: u128,
+}
+
+impl PhoenixMarketHeader {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= HEADER_LEN, ErrorCode::InvalidPhoenixOrder);
+        let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let key_at = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+
+        let base_decimals = u32_at(HEADER_BASE_DECIMALS_OFFSET);
+        let raw_base_units = u32_at(HEADER_RAW_BASE_UNITS_OFFSET).max(1);
+        let base_atoms_per_base_unit = 10u128
+            .checked_pow(base_decimals)
+            .and_then(|v| v.checked_mul(raw_base_units as u128))
+            .ok_or_else(|| error!(ErrorCode::InvalidPhoenixOrder))?;
+
+        let header = Self {
+            base_mint: key_at(HEADER_BASE_MINT_OFFSET),
+            base_vault: key_at(HEADER_BASE_VAULT_OFFSET),
+            quote_mint: key_at(HEADER_QUOTE_MINT_OFFSET),
+            quote_vault: key_at(HEADER_QUOTE_VAULT_OFFSET),
+            base_lot_size: u64_at(HEADER_BASE_LOT_SIZE_OFFSET),
+            quote_lot_size: u64_at(HEADER_QUOTE_LOT_SIZE_OFFSET),
+            tick_size_in_quote_atoms_per_base_unit: u64_at(HEADER_TICK_SIZE_OFFSET),
+            base_atoms_per_base_unit,
+        };
+        require!(
+            header.base_lot_size > 0
+                && header.quote_lot_size > 0
+                && header.tick_size_in_quote_atoms_per_base_unit > 0,
+            ErrorCode::InvalidPhoenixOrder
+        );
+        Ok(header)
+    }
+
+    /// Quote atoms par unité de base au prix donné
+    fn quote_atoms_per_base_unit(&self, price_in_ticks: u64) -> u128 {
+        price_in_ticks as u128 * self.tick_size_in_quote_atoms_per_base_unit as u128
+    }
+
+    /// Input à déposer pour un ordre (base pour un Ask, quote arrondi au-dessus pour un Bid)
+    pub fn required_input(&self, side: u8, price_in_ticks: u64, num_base_lots: u64) -> Result<u64> {
+        let base_atoms = (num_base_lots as u128)
+            .checked_mul(self.base_lot_size as u128)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+        let amount = if side == SIDE_ASK {
+            base_atoms
+        } else {
+            base_atoms
+                .checked_mul(self.quote_atoms_per_base_unit(price_in_ticks))
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))?
+                .div_ceil(self.base_atoms_per_base_unit)
+        };
+        u64::try_from(amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Produit d'un fill (en atoms de l'actif de sortie), arrondi au lot inférieur
+    pub fn fill_proceeds(&self, side: u8, price_in_ticks: u64, filled_input: u64) -> Result<u64> {
+        let per_unit = self.quote_atoms_per_base_unit(price_in_ticks);
+        let (atoms, lot_size) = if side == SIDE_ASK {
+            // Vend la base: reçoit du quote
+            let quote = (filled_input as u128)
+                .checked_mul(per_unit)
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))?
+                / self.base_atoms_per_base_unit;
+            (quote, self.quote_lot_size as u128)
+        } else {
+            // Achète la base: reçoit de la base
+            let base = (filled_input as u128)
+                .checked_mul(self.base_atoms_per_base_unit)
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))?
+                .checked_div(per_unit)
+                .ok_or_else(|| error!(ErrorCode::InvalidPhoenixOrder))?;
+            (base, self.base_lot_size as u128)
+        };
+        u64::try_from(atoms / lot_size * lot_size).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| error!(ErrorCode::InvalidPhoenixOrder))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| error!(ErrorCode::InvalidPhoenixOrder))
+}
+
+/// Séquence que le FIFOMarket attribuera au prochain ordre posé
+pub fn market_order_sequence_number(data: &[u8]) -> Result<u64> {
+    read_u64(data, PHOENIX_ORDER_SEQUENCE_NUMBER_OFFSET)
+}
+
+/// `order_sequence_number` de l'identifiant Phoenix (séquence inversée pour un Bid)
+pub fn order_id_sequence_number(side: u8, market_sequence_number: u64) -> u64 {
+    if side == SIDE_BID {
+        !market_sequence_number
+    } else {
+        market_sequence_number
+    }
+}
+
+/// Offset de l'arbre du côté donné (bids puis asks, dimensionnés par l'en-tête)
+pub(crate) fn phoenix_tree_offset(data: &[u8], side: u8) -> Result<usize> {
+    if side == SIDE_BID {
+        return Ok(PHOENIX_BIDS_OFFSET);
+    }
+    let bids_size = read_u64(data, PHOENIX_BIDS_SIZE_OFFSET)? as usize;
+    bids_size
+        .checked_mul(RB_NODE_LEN)
+        .and_then(|len| len.checked_add(PHOENIX_BIDS_OFFSET + RB_TREE_HEADER_LEN))
+        .ok_or_else(|| error!(ErrorCode::InvalidPhoenixOrder))
+}
+
+/// Ordre de l'arbre Phoenix: prix puis séquence, inversé pour les bids (meilleur à gauche)
+fn compare_order_ids(side: u8, order_id: (u64, u64), node_id: (u64, u64)) -> Ordering {
+    let ordering = order_id.0.cmp(&node_id.0).then(order_id.1.cmp(&node_id.1));
+    if side == SIDE_BID {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Base lots restants d'un ordre au carnet (None s'il n'y est plus: exécuté ou annulé)
+pub fn find_resting_order(
+    data: &[u8],
+    side: u8,
+    price_in_ticks: u64,
+    order_sequence_number: u64,
+) -> Result<Option<u64>> {
+    let tree_offset = phoenix_tree_offset(data, side)?;
+    let node_offset = |index: u32| tree_offset + RB_TREE_HEADER_LEN + (index as usize - 1) * RB_NODE_LEN;
+
+    let mut index = read_u32(data, tree_offset)?;
+    for _ in 0..RB_MAX_DEPTH {
+        if index == 0 {
+            return Ok(None);
+        }
+        let node = node_offset(index);
+        let node_id = (read_u64(data, node + 16)?, read_u64(data, node + 24)?);
+        let register = match compare_order_ids(side, (price_in_ticks, order_sequence_number), node_id) {
+            Ordering::Equal => return Ok(Some(read_u64(data, node + 40)?)),
+            Ordering::Less => RB_LEFT,
+            Ordering::Greater => RB_RIGHT,
+        };
+        index = read_u32(data, node + register * 4)?;
+    }
+    err!(ErrorCode::InvalidPhoenixOrder)
+}
+
+/// Crée les escrows de l'utilisateur et demande le siège Phoenix de son PDA
+pub fn initialize(ctx: Context<InitializePhoenixMaker>) -> Result<()> {
+    require!(!ctx.accounts.state.is_paused, error::SwapbackError::ProtocolPaused);
+    let market = &ctx.accounts.market;
+    let header = PhoenixMarketHeader::parse(&market.try_borrow_data()?)?;
+    require_keys_eq!(ctx.accounts.base_mint.key(), header.base_mint, ErrorCode::InvalidPhoenixOrder);
+    require_keys_eq!(ctx.accounts.quote_mint.key(), header.quote_mint, ErrorCode::InvalidPhoenixOrder);
+
+    let maker_seat = &ctx.accounts.maker_seat;
+    let (seat, _) = Pubkey::find_program_address(
+        &[b"seat", market.key.as_ref(), maker_seat.key.as_ref()],
+        &PHOENIX_PROGRAM_ID,
+    );
+    require_keys_eq!(ctx.accounts.seat.key(), seat, ErrorCode::InvalidPhoenixOrder);
+
+    // Le PDA paie lui-même le compte Seat: l'utilisateur lui avance le loyer manquant
+    let rent = Rent::get()?;
+    let needed = rent
+        .minimum_balance(0)
+        .saturating_add(rent.minimum_balance(PHOENIX_SEAT_LEN))
+        .saturating_sub(maker_seat.lamports());
+    if needed > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: maker_seat.to_account_info(),
+                },
+            ),
+            needed,
+        )?;
+    }
+
+    let market_key = market.key();
+    let user_key = ctx.accounts.user.key();
+    let bump = [ctx.bumps.maker_seat];
+    let seeds: &[&[u8]] = &[b"phoenix_maker", market_key.as_ref(), user_key.as_ref(), &bump];
+    let instruction = Instruction {
+        program_id: PHOENIX_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ctx.accounts.log_authority.key(), false),
+            AccountMeta::new(market_key, false),
+            AccountMeta::new(maker_seat.key(), true),
+            AccountMeta::new(seat, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![REQUEST_SEAT_TAG],
+    };
+    invoke_signed(
+        &instruction,
+        &[
+            ctx.accounts.phoenix_program.to_account_info(),
+            ctx.accounts.log_authority.to_account_info(),
+            market.to_account_info(),
+            maker_seat.to_account_info(),
+            ctx.accounts.seat.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    msg!("🪑 Phoenix maker seat requested for {} on market {}", user_key, market_key);
+    msg!("⚠️ Seat must be approved by the market authority before placing orders");
+    Ok(())
+}
+
+/// Pose un ordre post-only depuis le siège de l'utilisateur
+pub fn place_order(
+    ctx: Context<PlacePhoenixMakerOrder>,
+    nonce: u64,
+    side: u8,
+    price_in_ticks: u64,
+    num_base_lots: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.state.is_paused, error::SwapbackError::ProtocolPaused);
+    require!(side == SIDE_BID || side == SIDE_ASK, ErrorCode::InvalidPhoenixOrder);
+    require!(price_in_ticks > 0 && num_base_lots > 0, ErrorCode::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        expires_at > now && expires_at <= now.saturating_add(MAX_PHOENIX_ORDER_TTL_SECS),
+        ErrorCode::InvalidPhoenixOrder
+    );
+
+    let (header, sequence_before) = {
+        let data = ctx.accounts.market.try_borrow_data()?;
+        (PhoenixMarketHeader::parse(&data)?, market_order_sequence_number(&data)?)
+    };
+    validate_market_accounts(
+        &header,
+        &ctx.accounts.maker_base_account,
+        &ctx.accounts.maker_quote_account,
+        ctx.accounts.base_vault.key,
+        ctx.accounts.quote_vault.key,
+    )?;
+    let input_mint = if side == SIDE_ASK { header.base_mint } else { header.quote_mint };
+    require_keys_eq!(ctx.accounts.user_input_account.mint, input_mint, ErrorCode::InvalidTokenAccount);
+
+    let amount_in = header.required_input(side, price_in_ticks, num_base_lots)?;
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+
+    let market_key = ctx.accounts.market.key();
+    let user_key = ctx.accounts.user.key();
+    let bump = [ctx.bumps.maker_seat];
+    let seeds: &[&[u8]] = &[b"phoenix_maker", market_key.as_ref(), user_key.as_ref(), &bump];
+    let phoenix_accounts = [
+        ctx.accounts.phoenix_program.to_account_info(),
+        ctx.accounts.log_authority.to_account_info(),
+        ctx.accounts.market.to_account_info(),
+        ctx.accounts.maker_seat.to_account_info(),
+        ctx.accounts.maker_base_account.to_account_info(),
+        ctx.accounts.maker_quote_account.to_account_info(),
+        ctx.accounts.base_vault.to_account_info(),
+        ctx.accounts.quote_vault.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    ];
+
+    let input_escrow = if side == SIDE_ASK {
+        ctx.accounts.maker_base_account.to_account_info()
+    } else {
+        ctx.accounts.maker_quote_account.to_account_info()
+    };
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.user_input_account.to_account_info(),
+                to: input_escrow.clone(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_in,
+    )?;
+
+    let mut data = Vec::with_capacity(1 + 1 + 1 + 8 + 8 + 16 + 2 + 2 + 1);
+    data.push(PLACE_LIMIT_ORDER_TAG);
+    data.push(ORDER_PACKET_POST_ONLY);
+    data.push(side);
+    data.extend_from_slice(&price_in_ticks.to_le_bytes());
+    data.extend_from_slice(&num_base_lots.to_le_bytes());
+    data.extend_from_slice(&(nonce as u128).to_le_bytes()); // client_order_id
+    data.push(1); // reject_post_only: refuser plutôt que glisser si l'ordre croise
+    data.push(0); // use_only_deposited_funds
+    data.push(0); // last_valid_slot: None (expiration gérée par le règlement)
+    data.push(0); // last_valid_unix_timestamp_in_seconds: None
+    data.push(0); // fail_silently_on_insufficient_funds
+
+    let seat_info = ctx.accounts.seat.to_account_info();
+    let mut place_accounts = phoenix_accounts.to_vec();
+    place_accounts.insert(4, seat_info);
+    let mut metas = phoenix_metas(&phoenix_accounts);
+    metas.insert(4, AccountMeta::new_readonly(ctx.accounts.seat.key(), false));
+    invoke_signed(
+        &Instruction { program_id: PHOENIX_PROGRAM_ID, accounts: metas, data },
+        &place_accounts,
+        &[seeds],
+    )?;
+
+    // Identifiant attribué par le FIFOMarket: l'ordre doit reposer au carnet sous cet ID
+    let order_sequence_number = order_id_sequence_number(side, sequence_before);
+    {
+        let data = ctx.accounts.market.try_borrow_data()?;
+        require!(
+            market_order_sequence_number(&data)? == sequence_before.wrapping_add(1)
+                && find_resting_order(&data, side, price_in_ticks, order_sequence_number)?.is_some(),
+            ErrorCode::InvalidPhoenixOrder
+        );
+    }
+
+    // Phoenix a d'abord puisé dans les fonds libres du siège: l'excédent revient à l'utilisateur
+    let unused = read_token_amount(&input_escrow)?;
+    if unused > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: input_escrow,
+                    to: ctx.accounts.user_input_account.to_account_info(),
+                    authority: ctx.accounts.maker_seat.to_account_info(),
+                },
+                &[seeds],
+            ),
+            unused,
+        )?;
+    }
+
+    let order = &mut ctx.accounts.order;
+    order.user = user_key;
+    order.market = market_key;
+    order.nonce = nonce;
+    order.side = side;
+    order.price_in_ticks = price_in_ticks;
+    order.num_base_lots = num_base_lots;
+    order.order_sequence_number = order_sequence_number;
+    order.amount_in = amount_in;
+    order.created_at = now;
+    order.expires_at = expires_at;
+    order.bump = ctx.bumps.order;
+
+    emit!(PhoenixMakerOrderPlaced {
+        order: order.key(),
+        user: order.user,
+        market: market_key,
+        side,
+        price_in_ticks,
+        num_base_lots,
+        amount_in,
+        expires_at,
+    });
+    msg!(
+        "📌 Phoenix post-only {} lots @ {} ticks (seq {})",
+        num_base_lots,
+        price_in_ticks,
+        sequence_before
+    );
+    Ok(())
+}
+
+/// Règle un ordre: annule le reliquat, reverse à l'utilisateur les soldes réels de ses escrows.
+/// Permissionless après expiration, réservé à l'utilisateur avant.
+pub fn settle_order(ctx: Context<SettlePhoenixMakerOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        order.is_expired(now) || ctx.accounts.settler.key() == order.user,
+        ErrorCode::PhoenixOrderNotExpired
+    );
+
+    let (header, resting_lots) = {
+        let data = ctx.accounts.market.try_borrow_data()?;
+        (
+            PhoenixMarketHeader::parse(&data)?,
+            find_resting_order(&data, order.side, order.price_in_ticks, order.order_sequence_number)?,
+        )
+    };
+    validate_market_accounts(
+        &header,
+        &ctx.accounts.maker_base_account,
+        &ctx.accounts.maker_quote_account,
+        ctx.accounts.base_vault.key,
+        ctx.accounts.quote_vault.key,
+    )?;
+    require_keys_eq!(ctx.accounts.user_base_account.mint, header.base_mint, ErrorCode::InvalidTokenAccount);
+    require_keys_eq!(ctx.accounts.user_quote_account.mint, header.quote_mint, ErrorCode::InvalidTokenAccount);
+
+    let market_key = ctx.accounts.market.key();
+    let bump = [ctx.bumps.maker_seat];
+    let seeds: &[&[u8]] = &[b"phoenix_maker", market_key.as_ref(), order.user.as_ref(), &bump];
+    let phoenix_accounts = [
+        ctx.accounts.phoenix_program.to_account_info(),
+        ctx.accounts.log_authority.to_account_info(),
+        ctx.accounts.market.to_account_info(),
+        ctx.accounts.maker_seat.to_account_info(),
+        ctx.accounts.maker_base_account.to_account_info(),
+        ctx.accounts.maker_quote_account.to_account_info(),
+        ctx.accounts.base_vault.to_account_info(),
+        ctx.accounts.quote_vault.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    ];
+
+    // Annulation du reliquat encore au carnet: Phoenix reverse les fonds libérés aux escrows
+    if let Some(lots) = resting_lots {
+        let mut data = Vec::with_capacity(1 + 4 + 1 + 8 + 8);
+        data.push(CANCEL_MULTIPLE_ORDERS_BY_ID_TAG);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.push(order.side);
+        data.extend_from_slice(&order.price_in_ticks.to_le_bytes());
+        data.extend_from_slice(&order.order_sequence_number.to_le_bytes());
+        invoke_signed(
+            &Instruction { program_id: PHOENIX_PROGRAM_ID, accounts: phoenix_metas(&phoenix_accounts), data },
+            &phoenix_accounts,
+            &[seeds],
+        )?;
+        msg!("🧹 Cancelled {} resting lots", lots);
+    } else {
+        msg!("ℹ️ Order no longer on the book (filled or cancelled by Phoenix)");
+    }
+
+    // Fills et fonds libérés (y compris ForceCancelOrders / EvictSeat) rapatriés du siège
+    sweep_free_funds(&phoenix_accounts, seeds)?;
+
+    let (input_escrow, output_escrow, user_input, user_output) = if order.is_bid() {
+        (
+            &ctx.accounts.maker_quote_account,
+            &ctx.accounts.maker_base_account,
+            &ctx.accounts.user_quote_account,
+            &ctx.accounts.user_base_account,
+        )
+    } else {
+        (
+            &ctx.accounts.maker_base_account,
+            &ctx.accounts.maker_quote_account,
+            &ctx.accounts.user_base_account,
+            &ctx.accounts.user_quote_account,
+        )
+    };
+
+    // Soldes réellement détenus par les escrows de l'utilisateur, jamais un montant calculé
+    let refunded = read_token_amount(&input_escrow.to_account_info())?;
+    let proceeds = read_token_amount(&output_escrow.to_account_info())?;
+    for (from, to, amount) in [(input_escrow, user_input, refunded), (output_escrow, user_output, proceeds)] {
+        if amount == 0 {
+            continue;
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.maker_seat.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+    }
+    let filled_in = order.amount_in.saturating_sub(refunded);
+
+    emit!(PhoenixMakerOrderSettled {
+        order: order.key(),
+        user: order.user,
+        market: market_key,
+        filled_in,
+        refunded,
+        proceeds,
+        settler: ctx.accounts.settler.key(),
+    });
+    msg!("✅ Phoenix maker order settled: filled {}, refunded {}, proceeds {}", filled_in, refunded, proceeds);
+    Ok(())
+}
+
+/// Escrows de l'utilisateur et vaults du marché doivent correspondre à l'en-tête
+fn validate_market_accounts(
+    header: &PhoenixMarketHeader,
+    maker_base_account: &token::TokenAccount,
+    maker_quote_account: &token::TokenAccount,
+    base_vault: &Pubkey,
+    quote_vault: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(maker_base_account.mint, header.base_mint, ErrorCode::InvalidPhoenixOrder);
+    require_keys_eq!(maker_quote_account.mint, header.quote_mint, ErrorCode::InvalidPhoenixOrder);
+    require_keys_eq!(*base_vault, header.base_vault, ErrorCode::InvalidPhoenixOrder);
+    require_keys_eq!(*quote_vault, header.quote_vault, ErrorCode::InvalidPhoenixOrder);
+    Ok(())
+}
+
+/// WithdrawFunds(None, None): tous les fonds libres du siège vers ses escrows
+fn sweep_free_funds(phoenix_accounts: &[AccountInfo], seeds: &[&[u8]]) -> Result<()> {
+    let data = vec![WITHDRAW_FUNDS_TAG, 0, 0];
+    invoke_signed(
+        &Instruction { program_id: PHOENIX_PROGRAM_ID, accounts: phoenix_metas(phoenix_accounts), data },
+        phoenix_accounts,
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+/// Metas Phoenix: [program, log_authority, market, trader, base, quote, base_vault, quote_vault, token_program]
+fn phoenix_metas(accounts: &[AccountInfo]) -> Vec<AccountMeta> {
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(i, info)| match i {
+            3 => AccountMeta::new_readonly(*info.key, true),
+            2 | 4..=7 => AccountMeta::new(*info.key, false),
+            _ => AccountMeta::new_readonly(*info.key, false),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SOL/USDC: base 9 décimales, lot 0.001 SOL; quote lot 1 atom; tick 0.001 USDC
+    fn sol_usdc() -> PhoenixMarketHeader {
+        PhoenixMarketHeader {
+            base_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            base_lot_size: 1_000_000,
+            quote_lot_size: 1,
+            tick_size_in_quote_atoms_per_base_unit: 1_000,
+            base_atoms_per_base_unit: 1_000_000_000,
+        }
+    }
+
+    /// Dimensions d'un petit marché Phoenix (bids/asks 512, sièges 128)
+    const BIDS_SIZE: u64 = 512;
+    const ASKS_SIZE: u64 = 512;
+    const NUM_SEATS: u64 = 128;
+    const TRADER_NODE_LEN: usize = 16 + 32 + 96;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Compte marché complet: MarketHeader (576) + FIFOMarket + bids + asks + traders
+    fn market_account(header: &PhoenixMarketHeader, event_sequence_number: u64, order_sequence_number: u64) -> Vec<u8> {
+        let tree_len = |size: u64| RB_TREE_HEADER_LEN + size as usize * RB_NODE_LEN;
+        let len = PHOENIX_BIDS_OFFSET
+            + tree_len(BIDS_SIZE)
+            + tree_len(ASKS_SIZE)
+            + RB_TREE_HEADER_LEN
+            + NUM_SEATS as usize * TRADER_NODE_LEN;
+        let mut data = vec![0u8; len];
+        // MarketHeader
+        put(&mut data, 0, &[0xd7; 8]); // discriminant
+        put(&mut data, 8, &1u64.to_le_bytes()); // status: Active
+        put(&mut data, 16, &BIDS_SIZE.to_le_bytes());
+        put(&mut data, 24, &ASKS_SIZE.to_le_bytes());
+        put(&mut data, 32, &NUM_SEATS.to_le_bytes());
+        put(&mut data, HEADER_BASE_DECIMALS_OFFSET, &9u32.to_le_bytes());
+        put(&mut data, HEADER_BASE_MINT_OFFSET, header.base_mint.as_ref());
+        put(&mut data, HEADER_BASE_VAULT_OFFSET, header.base_vault.as_ref());
+        put(&mut data, HEADER_BASE_LOT_SIZE_OFFSET, &header.base_lot_size.to_le_bytes());
+        put(&mut data, 120, &6u32.to_le_bytes()); // quote decimals
+        put(&mut data, HEADER_QUOTE_MINT_OFFSET, header.quote_mint.as_ref());
+        put(&mut data, HEADER_QUOTE_VAULT_OFFSET, header.quote_vault.as_ref());
+        put(&mut data, HEADER_QUOTE_LOT_SIZE_OFFSET, &header.quote_lot_size.to_le_bytes());
+        put(&mut data, HEADER_TICK_SIZE_OFFSET, &header.tick_size_in_quote_atoms_per_base_unit.to_le_bytes());
+        put(&mut data, 208, Pubkey::new_unique().as_ref()); // authority
+        put(&mut data, 240, Pubkey::new_unique().as_ref()); // fee_recipient
+        put(&mut data, 272, &event_sequence_number.to_le_bytes()); // séquence des événements
+        put(&mut data, HEADER_RAW_BASE_UNITS_OFFSET, &1u32.to_le_bytes());
+        // FIFOMarket
+        put(&mut data, 832, &1_000u64.to_le_bytes()); // base_lots_per_base_unit
+        put(&mut data, 840, &1_000u64.to_le_bytes()); // tick_size_in_quote_lots_per_base_unit
+        put(&mut data, PHOENIX_ORDER_SEQUENCE_NUMBER_OFFSET, &order_sequence_number.to_le_bytes());
+        put(&mut data, PHOENIX_TAKER_FEE_BPS_OFFSET, &2u64.to_le_bytes());
+        data
+    }
+
+    /// Insertion BST (sans rééquilibrage) d'un ordre au carnet, comme l'arbre de Phoenix l'ordonne
+    fn rest_order(data: &mut [u8], side: u8, price_in_ticks: u64, order_sequence_number: u64, num_base_lots: u64) {
+        let tree = phoenix_tree_offset(data, side).unwrap();
+        let size = read_u64(data, tree + 8).unwrap();
+        let index = size as u32 + 1;
+        let node_offset = |index: u32| tree + RB_TREE_HEADER_LEN + (index as usize - 1) * RB_NODE_LEN;
+        let node = node_offset(index);
+        put(data, node + 16, &price_in_ticks.to_le_bytes());
+        put(data, node + 24, &order_sequence_number.to_le_bytes());
+        put(data, node + 32, &1u64.to_le_bytes()); // trader_index
+        put(data, node + 40, &num_base_lots.to_le_bytes());
+        put(data, tree + 8, &(size + 1).to_le_bytes());
+
+        let mut parent = read_u32(data, tree).unwrap();
+        if parent == 0 {
+            put(data, tree, &index.to_le_bytes());
+            return;
+        }
+        loop {
+            let parent_node = node_offset(parent);
+            let parent_id = (read_u64(data, parent_node + 16).unwrap(), read_u64(data, parent_node + 24).unwrap());
+            let register = match compare_order_ids(side, (price_in_ticks, order_sequence_number), parent_id) {
+                Ordering::Less => RB_LEFT,
+                _ => RB_RIGHT,
+            };
+            let child = read_u32(data, parent_node + register * 4).unwrap();
+            if child == 0 {
+                put(data, parent_node + register * 4, &index.to_le_bytes());
+                put(data, node + 8, &parent.to_le_bytes());
+                return;
+            }
+            parent = child;
+        }
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let header = sol_usdc();
+        let data = market_account(&header, 7_777, 42);
+        assert_eq!(PhoenixMarketHeader::parse(&data).unwrap(), header);
+        assert!(PhoenixMarketHeader::parse(&data[..HEADER_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_required_input_and_proceeds() {
+        let header = sol_usdc();
+        // 2 SOL @ 150 USDC (150_000 ticks de 0.001)
+        let (price, lots) = (150_000, 2_000);
+        assert_eq!(header.required_input(SIDE_ASK, price, lots).unwrap(), 2_000_000_000);
+        assert_eq!(header.required_input(SIDE_BID, price, lots).unwrap(), 300_000_000);
+
+        // Ask exécuté à moitié: 1 SOL vendu -> 150 USDC
+        assert_eq!(header.fill_proceeds(SIDE_ASK, price, 1_000_000_000).unwrap(), 150_000_000);
+        // Bid exécuté à moitié: 150 USDC -> 1 SOL
+        assert_eq!(header.fill_proceeds(SIDE_BID, price, 150_000_000).unwrap(), 1_000_000_000);
+        // Reliquat inférieur à un lot de base: rien à reverser
+        assert_eq!(header.fill_proceeds(SIDE_BID, price, 100_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_order_sequence_number_from_fifo_market() {
+        let header = sol_usdc();
+        let data = market_account(&header, 7_777, 42);
+        // order_sequence_number du FIFOMarket, pas la séquence d'événements du MarketHeader
+        assert_eq!(market_order_sequence_number(&data).unwrap(), 42);
+        assert_eq!(order_id_sequence_number(SIDE_ASK, 42), 42);
+        assert_eq!(order_id_sequence_number(SIDE_BID, 42), !42u64);
+        assert!(market_order_sequence_number(&data[..PHOENIX_ORDER_SEQUENCE_NUMBER_OFFSET + 7]).is_err());
+    }
+
+    #[test]
+    fn test_find_resting_order() {
+        let header = sol_usdc();
+        let mut data = market_account(&header, 7_777, 50);
+        for (seq, price, lots) in [(40, 149_900, 10), (41, 149_950, 20), (42, 149_900, 30), (43, 149_800, 40)] {
+            rest_order(&mut data, SIDE_BID, price, order_id_sequence_number(SIDE_BID, seq), lots);
+        }
+        for (seq, price, lots) in [(44, 150_100, 5), (45, 150_050, 6), (46, 150_100, 7)] {
+            rest_order(&mut data, SIDE_ASK, price, order_id_sequence_number(SIDE_ASK, seq), lots);
+        }
+
+        let bid = |price, seq| find_resting_order(&data, SIDE_BID, price, order_id_sequence_number(SIDE_BID, seq));
+        let ask = |price, seq| find_resting_order(&data, SIDE_ASK, price, order_id_sequence_number(SIDE_ASK, seq));
+        assert_eq!(bid(149_900, 40).unwrap(), Some(10));
+        assert_eq!(bid(149_900, 42).unwrap(), Some(30));
+        assert_eq!(bid(149_800, 43).unwrap(), Some(40));
+        assert_eq!(ask(150_100, 46).unwrap(), Some(7));
+        assert_eq!(ask(150_050, 45).unwrap(), Some(6));
+        // Même séquence côté opposé, prix ou séquence différents: absent
+        assert_eq!(ask(149_900, 40).unwrap(), None);
+        assert_eq!(bid(149_900, 41).unwrap(), None);
+        assert_eq!(ask(150_100, 47).unwrap(), None);
+        // Séquence non inversée pour un bid: pas l'identifiant Phoenix
+        assert_eq!(find_resting_order(&data, SIDE_BID, 149_900, 40).unwrap(), None);
+    }
+}
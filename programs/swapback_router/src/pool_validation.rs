@@ -0,0 +1,1412 @@
+//! Validation des comptes de pool avant CPI
+//!
+//! Les modules `cpi_*` vérifient les comptes utilisateur (signer, comptes
+//! d'entrée/sortie). Ce module vérifie, pour chaque venue native, que les
+//! comptes de pool passés dans `remaining_accounts` appartiennent bien au
+//! programme de la venue (owner + discriminator) et que les vaults, mints et
+//! comptes auxiliaires sont ceux référencés par le pool lui-même.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+
+use crate::token_extensions::unpack_token_account;
+use crate::{
+    cpi_lifinity, cpi_meteora, cpi_meteora_damm, cpi_openbook_v2, cpi_orca, cpi_phoenix, cpi_raydium,
+    cpi_raydium_clmm, cpi_raydium_cpmm, cpi_saber, cpi_sanctum, lst_pricing, ErrorCode,
+    LIFINITY_PROGRAM_ID, METEORA_DAMM_PROGRAM_ID, METEORA_DAMM_V2_PROGRAM_ID,
+    METEORA_DLMM_PROGRAM_ID, OPENBOOK_V2_PROGRAM_ID, ORCA_WHIRLPOOL_PROGRAM_ID, PHOENIX_PROGRAM_ID,
+    RAYDIUM_AMM_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID, SABER_PROGRAM_ID,
+    SANCTUM_PROGRAM_ID,
+};
+
+/// Raydium AMM v4 authority (PDA ["amm authority"])
+pub const RAYDIUM_AMM_AUTHORITY: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+
+/// Raydium CPMM vault & LP mint authority (PDA ["vault_and_lp_mint_auth_seed"])
+pub const RAYDIUM_CPMM_AUTHORITY: Pubkey = pubkey!("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL");
+
+/// Meteora DAMM v2 pool authority (PDA ["pool_authority"])
+pub const METEORA_DAMM_V2_POOL_AUTHORITY: Pubkey = pubkey!("HLnpSz9h2S4hiLQ43rnSD9XkcUThA7B8hQMKmDaiTLcC");
+
+/// Meteora DAMM v2 event authority (PDA ["__event_authority"])
+pub const METEORA_DAMM_V2_EVENT_AUTHORITY: Pubkey = pubkey!("3rmHSu74h1ZcmAisVcWerTCiRDQbUrBKmcwptYGjHfet");
+
+/// Phoenix log authority (PDA ["log"])
+pub const PHOENIX_LOG_AUTHORITY: Pubkey = pubkey!("7aDTsspkQNGKmrexAN7FLx9oxU3iPczSSvHNggyuqYkR");
+
+// Orca Whirlpool
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+const WHIRLPOOL_TOKEN_MINT_A_OFFSET: usize = 101;
+const WHIRLPOOL_TOKEN_VAULT_A_OFFSET: usize = 133;
+const WHIRLPOOL_TOKEN_MINT_B_OFFSET: usize = 181;
+const WHIRLPOOL_TOKEN_VAULT_B_OFFSET: usize = 213;
+
+// Raydium AMM v4 (AmmInfo, pas de discriminator)
+const RAYDIUM_AMM_INFO_LEN: usize = 752;
+const RAYDIUM_COIN_VAULT_OFFSET: usize = 336;
+const RAYDIUM_PC_VAULT_OFFSET: usize = 368;
+const RAYDIUM_OPEN_ORDERS_OFFSET: usize = 496;
+const RAYDIUM_MARKET_OFFSET: usize = 528;
+const RAYDIUM_MARKET_PROGRAM_OFFSET: usize = 560;
+
+// Raydium CLMM
+const CLMM_POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+const CLMM_AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+const CLMM_AMM_CONFIG_OFFSET: usize = 9;
+const CLMM_TOKEN_MINT_0_OFFSET: usize = 73;
+const CLMM_TOKEN_MINT_1_OFFSET: usize = 105;
+const CLMM_TOKEN_VAULT_0_OFFSET: usize = 137;
+const CLMM_TOKEN_VAULT_1_OFFSET: usize = 169;
+const CLMM_OBSERVATION_OFFSET: usize = 201;
+
+// Raydium CPMM (mêmes noms de comptes Anchor que CLMM, donc mêmes discriminators)
+const CPMM_AMM_CONFIG_OFFSET: usize = 8;
+const CPMM_TOKEN_0_VAULT_OFFSET: usize = 72;
+const CPMM_TOKEN_1_VAULT_OFFSET: usize = 104;
+const CPMM_TOKEN_0_MINT_OFFSET: usize = 168;
+const CPMM_TOKEN_1_MINT_OFFSET: usize = 200;
+const CPMM_TOKEN_0_PROGRAM_OFFSET: usize = 232;
+const CPMM_TOKEN_1_PROGRAM_OFFSET: usize = 264;
+const CPMM_OBSERVATION_OFFSET: usize = 296;
+
+// Meteora DLMM
+const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+const LB_PAIR_TOKEN_X_MINT_OFFSET: usize = 88;
+const LB_PAIR_TOKEN_Y_MINT_OFFSET: usize = 120;
+const LB_PAIR_RESERVE_X_OFFSET: usize = 152;
+const LB_PAIR_RESERVE_Y_OFFSET: usize = 184;
+const LB_PAIR_ORACLE_OFFSET: usize = 552;
+
+// Phoenix MarketHeader: TokenParams = decimals u32, vault_bump u32, mint, vault
+const PHOENIX_BASE_MINT_OFFSET: usize = 48;
+const PHOENIX_BASE_VAULT_OFFSET: usize = 80;
+const PHOENIX_QUOTE_MINT_OFFSET: usize = 128;
+const PHOENIX_QUOTE_VAULT_OFFSET: usize = 160;
+
+// Meteora Dynamic AMM Pool et Dynamic Vault
+const DAMM_POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+const DAMM_TOKEN_A_MINT_OFFSET: usize = 40;
+const DAMM_TOKEN_B_MINT_OFFSET: usize = 72;
+const DAMM_A_VAULT_OFFSET: usize = 104;
+const DAMM_B_VAULT_OFFSET: usize = 136;
+const DAMM_A_VAULT_LP_OFFSET: usize = 168;
+const DAMM_B_VAULT_LP_OFFSET: usize = 200;
+const DAMM_PROTOCOL_TOKEN_A_FEE_OFFSET: usize = 234;
+const DAMM_PROTOCOL_TOKEN_B_FEE_OFFSET: usize = 266;
+const DYNAMIC_VAULT_DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+const DYNAMIC_VAULT_TOKEN_VAULT_OFFSET: usize = 19;
+const DYNAMIC_VAULT_LP_MINT_OFFSET: usize = 115;
+
+// Meteora DAMM v2 Pool (cp-amm, zero-copy): PoolFeesStruct (160 octets) puis mints et vaults
+const DAMM_V2_TOKEN_A_MINT_OFFSET: usize = 168;
+const DAMM_V2_TOKEN_B_MINT_OFFSET: usize = 200;
+const DAMM_V2_TOKEN_A_VAULT_OFFSET: usize = 232;
+const DAMM_V2_TOKEN_B_VAULT_OFFSET: usize = 264;
+
+// OpenBook v2 Market (zero-copy; NonZeroPubkeyOption: clé nulle = None)
+const OPENBOOK_MARKET_DISCRIMINATOR: [u8; 8] = [219, 190, 213, 55, 0, 227, 198, 154];
+const OPENBOOK_MARKET_AUTHORITY_OFFSET: usize = 16;
+const OPENBOOK_OPEN_ORDERS_ADMIN_OFFSET: usize = 88;
+const OPENBOOK_BIDS_OFFSET: usize = 200;
+const OPENBOOK_ASKS_OFFSET: usize = 232;
+const OPENBOOK_EVENT_HEAP_OFFSET: usize = 264;
+const OPENBOOK_ORACLE_A_OFFSET: usize = 296;
+const OPENBOOK_ORACLE_B_OFFSET: usize = 328;
+const OPENBOOK_BASE_MINT_OFFSET: usize = 576;
+const OPENBOOK_QUOTE_MINT_OFFSET: usize = 608;
+const OPENBOOK_BASE_VAULT_OFFSET: usize = 640;
+const OPENBOOK_QUOTE_VAULT_OFFSET: usize = 680;
+
+// Lifinity v2
+const LIFINITY_AMM_DISCRIMINATOR: [u8; 8] = [143, 245, 200, 17, 74, 214, 196, 135];
+const LIFINITY_TOKEN_A_ACCOUNT_OFFSET: usize = 158;
+const LIFINITY_TOKEN_B_ACCOUNT_OFFSET: usize = 190;
+const LIFINITY_POOL_MINT_OFFSET: usize = 222;
+const LIFINITY_FEE_ACCOUNT_OFFSET: usize = 318;
+
+// Saber StableSwap (SwapInfo, pas de discriminator)
+const SABER_IS_INITIALIZED_OFFSET: usize = 0;
+const SABER_NONCE_OFFSET: usize = 2;
+const SABER_TOKEN_A_RESERVES_OFFSET: usize = 107;
+const SABER_TOKEN_A_MINT_OFFSET: usize = 139;
+const SABER_TOKEN_A_ADMIN_FEES_OFFSET: usize = 171;
+const SABER_TOKEN_B_RESERVES_OFFSET: usize = 204;
+const SABER_TOKEN_B_MINT_OFFSET: usize = 236;
+const SABER_TOKEN_B_ADMIN_FEES_OFFSET: usize = 268;
+
+/// Valide les comptes de pool d'une venue avant le CPI.
+/// Les venues sans validateur (non supportées) sont rejetées plus loin par `execute_dex_swap`.
+pub fn validate_venue_accounts(dex_program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+    match *dex_program {
+        ORCA_WHIRLPOOL_PROGRAM_ID => validate_orca(accounts),
+        RAYDIUM_AMM_PROGRAM_ID => validate_raydium_amm(accounts),
+        RAYDIUM_CLMM_PROGRAM_ID => validate_raydium_clmm(accounts),
+        RAYDIUM_CPMM_PROGRAM_ID => validate_raydium_cpmm(accounts),
+        METEORA_DLMM_PROGRAM_ID => validate_meteora(accounts),
+        METEORA_DAMM_PROGRAM_ID => validate_meteora_damm(accounts),
+        METEORA_DAMM_V2_PROGRAM_ID => validate_meteora_damm_v2(accounts),
+        PHOENIX_PROGRAM_ID => validate_phoenix(accounts),
+        OPENBOOK_V2_PROGRAM_ID => validate_openbook_v2(accounts),
+        LIFINITY_PROGRAM_ID => validate_lifinity(accounts),
+        SANCTUM_PROGRAM_ID => validate_sanctum(accounts),
+        SABER_PROGRAM_ID => validate_saber(accounts),
+        _ => Ok(()),
+    }
+}
+
+fn validate_orca(accounts: &[AccountInfo]) -> Result<()> {
+    require_len(accounts, cpi_orca::ORCA_SWAP_ACCOUNT_COUNT, "orca")?;
+    require_key(&accounts[cpi_orca::TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "orca token program")?;
+
+    let whirlpool = &accounts[cpi_orca::WHIRLPOOL_INDEX];
+    require_owner(whirlpool, &ORCA_WHIRLPOOL_PROGRAM_ID, "orca whirlpool")?;
+    let data = whirlpool.try_borrow_data()?;
+    require_discriminator(&data, &WHIRLPOOL_DISCRIMINATOR, "orca whirlpool")?;
+
+    require_key(
+        &accounts[cpi_orca::TOKEN_VAULT_A_INDEX],
+        &read_pubkey(&data, WHIRLPOOL_TOKEN_VAULT_A_OFFSET, "orca whirlpool")?,
+        "orca vault a",
+    )?;
+    require_key(
+        &accounts[cpi_orca::TOKEN_VAULT_B_INDEX],
+        &read_pubkey(&data, WHIRLPOOL_TOKEN_VAULT_B_OFFSET, "orca whirlpool")?,
+        "orca vault b",
+    )?;
+    require_token_mint(
+        &accounts[cpi_orca::TOKEN_OWNER_A_INDEX],
+        &read_pubkey(&data, WHIRLPOOL_TOKEN_MINT_A_OFFSET, "orca whirlpool")?,
+        "orca token owner a",
+    )?;
+    require_token_mint(
+        &accounts[cpi_orca::TOKEN_OWNER_B_INDEX],
+        &read_pubkey(&data, WHIRLPOOL_TOKEN_MINT_B_OFFSET, "orca whirlpool")?,
+        "orca token owner b",
+    )?;
+
+    for tick_array in &accounts[cpi_orca::TICK_ARRAY_0_INDEX..=cpi_orca::TICK_ARRAY_2_INDEX] {
+        require_owner(tick_array, &ORCA_WHIRLPOOL_PROGRAM_ID, "orca tick array")?;
+    }
+    Ok(())
+}
+
+fn validate_raydium_amm(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_raydium::*;
+
+    require_len(accounts, RAYDIUM_SWAP_ACCOUNT_COUNT, "raydium")?;
+    require_key(&accounts[AMM_AUTHORITY_INDEX], &RAYDIUM_AMM_AUTHORITY, "raydium authority")?;
+
+    let pool = &accounts[AMM_POOL_INDEX];
+    require_owner(pool, &RAYDIUM_AMM_PROGRAM_ID, "raydium pool")?;
+    let data = pool.try_borrow_data()?;
+    if data.len() != RAYDIUM_AMM_INFO_LEN {
+        return invalid("raydium pool layout");
+    }
+
+    for (index, offset, label) in [
+        (AMM_OPEN_ORDERS_INDEX, RAYDIUM_OPEN_ORDERS_OFFSET, "raydium open orders"),
+        (AMM_COIN_VAULT_INDEX, RAYDIUM_COIN_VAULT_OFFSET, "raydium coin vault"),
+        (AMM_PC_VAULT_INDEX, RAYDIUM_PC_VAULT_OFFSET, "raydium pc vault"),
+        (MARKET_INDEX, RAYDIUM_MARKET_OFFSET, "raydium market"),
+        (MARKET_PROGRAM_INDEX, RAYDIUM_MARKET_PROGRAM_OFFSET, "raydium market program"),
+    ] {
+        require_key(&accounts[index], &read_pubkey(&data, offset, "raydium pool")?, label)?;
+    }
+
+    let market_program = accounts[MARKET_PROGRAM_INDEX].key();
+    for index in [MARKET_INDEX, MARKET_BIDS_INDEX, MARKET_ASKS_INDEX, MARKET_EVENT_QUEUE_INDEX] {
+        require_owner(&accounts[index], &market_program, "raydium market account")?;
+    }
+    Ok(())
+}
+
+fn validate_raydium_clmm(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_raydium_clmm::*;
+
+    require_len(accounts, RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT, "raydium clmm")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "raydium clmm token program")?;
+
+    let amm_config = &accounts[AMM_CONFIG_INDEX];
+    require_owner(amm_config, &RAYDIUM_CLMM_PROGRAM_ID, "raydium clmm config")?;
+    require_discriminator(&amm_config.try_borrow_data()?, &CLMM_AMM_CONFIG_DISCRIMINATOR, "raydium clmm config")?;
+
+    let pool = &accounts[POOL_STATE_INDEX];
+    require_owner(pool, &RAYDIUM_CLMM_PROGRAM_ID, "raydium clmm pool")?;
+    let data = pool.try_borrow_data()?;
+    require_discriminator(&data, &CLMM_POOL_STATE_DISCRIMINATOR, "raydium clmm pool")?;
+
+    require_key(amm_config, &read_pubkey(&data, CLMM_AMM_CONFIG_OFFSET, "raydium clmm pool")?, "raydium clmm config")?;
+    require_key(
+        &accounts[OBSERVATION_STATE_INDEX],
+        &read_pubkey(&data, CLMM_OBSERVATION_OFFSET, "raydium clmm pool")?,
+        "raydium clmm observation",
+    )?;
+
+    // Le couple (input_vault, output_vault) doit être (vault_0, vault_1) ou l'inverse,
+    // avec des mints cohérents côté utilisateur
+    let vault_0 = read_pubkey(&data, CLMM_TOKEN_VAULT_0_OFFSET, "raydium clmm pool")?;
+    let vault_1 = read_pubkey(&data, CLMM_TOKEN_VAULT_1_OFFSET, "raydium clmm pool")?;
+    let mint_0 = read_pubkey(&data, CLMM_TOKEN_MINT_0_OFFSET, "raydium clmm pool")?;
+    let mint_1 = read_pubkey(&data, CLMM_TOKEN_MINT_1_OFFSET, "raydium clmm pool")?;
+    let input_vault = accounts[INPUT_VAULT_INDEX].key();
+    let output_vault = accounts[OUTPUT_VAULT_INDEX].key();
+    let (input_mint, output_mint) = if input_vault == vault_0 && output_vault == vault_1 {
+        (mint_0, mint_1)
+    } else if input_vault == vault_1 && output_vault == vault_0 {
+        (mint_1, mint_0)
+    } else {
+        return invalid("raydium clmm vaults");
+    };
+    require_token_mint(&accounts[INPUT_TOKEN_ACCOUNT_INDEX], &input_mint, "raydium clmm input account")?;
+    require_token_mint(&accounts[OUTPUT_TOKEN_ACCOUNT_INDEX], &output_mint, "raydium clmm output account")?;
+
+    for tick_array in &accounts[TICK_ARRAY_0_INDEX..] {
+        require_owner(tick_array, &RAYDIUM_CLMM_PROGRAM_ID, "raydium clmm tick array")?;
+    }
+    Ok(())
+}
+
+fn validate_raydium_cpmm(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_raydium_cpmm::*;
+
+    require_len(accounts, RAYDIUM_CPMM_SWAP_ACCOUNT_COUNT, "raydium cpmm")?;
+    require_key(&accounts[AUTHORITY_INDEX], &RAYDIUM_CPMM_AUTHORITY, "raydium cpmm authority")?;
+
+    let amm_config = &accounts[AMM_CONFIG_INDEX];
+    require_owner(amm_config, &RAYDIUM_CPMM_PROGRAM_ID, "raydium cpmm config")?;
+    require_discriminator(&amm_config.try_borrow_data()?, &CLMM_AMM_CONFIG_DISCRIMINATOR, "raydium cpmm config")?;
+
+    let pool = &accounts[POOL_STATE_INDEX];
+    require_owner(pool, &RAYDIUM_CPMM_PROGRAM_ID, "raydium cpmm pool")?;
+    let data = pool.try_borrow_data()?;
+    require_discriminator(&data, &CLMM_POOL_STATE_DISCRIMINATOR, "raydium cpmm pool")?;
+
+    require_key(amm_config, &read_pubkey(&data, CPMM_AMM_CONFIG_OFFSET, "raydium cpmm pool")?, "raydium cpmm config")?;
+    require_key(
+        &accounts[OBSERVATION_STATE_INDEX],
+        &read_pubkey(&data, CPMM_OBSERVATION_OFFSET, "raydium cpmm pool")?,
+        "raydium cpmm observation",
+    )?;
+
+    // Côté 0 ou côté 1 en entrée: vault, mint et token program doivent suivre le même côté
+    let side_0 = (CPMM_TOKEN_0_VAULT_OFFSET, CPMM_TOKEN_0_MINT_OFFSET, CPMM_TOKEN_0_PROGRAM_OFFSET);
+    let side_1 = (CPMM_TOKEN_1_VAULT_OFFSET, CPMM_TOKEN_1_MINT_OFFSET, CPMM_TOKEN_1_PROGRAM_OFFSET);
+    let (input_side, output_side) =
+        if accounts[INPUT_VAULT_INDEX].key() == read_pubkey(&data, side_0.0, "raydium cpmm pool")? {
+            (side_0, side_1)
+        } else {
+            (side_1, side_0)
+        };
+
+    for ((vault_offset, mint_offset, program_offset), vault_index, mint_index, program_index, token_index, label) in [
+        (input_side, INPUT_VAULT_INDEX, INPUT_TOKEN_MINT_INDEX, INPUT_TOKEN_PROGRAM_INDEX, INPUT_TOKEN_ACCOUNT_INDEX, "raydium cpmm input"),
+        (output_side, OUTPUT_VAULT_INDEX, OUTPUT_TOKEN_MINT_INDEX, OUTPUT_TOKEN_PROGRAM_INDEX, OUTPUT_TOKEN_ACCOUNT_INDEX, "raydium cpmm output"),
+    ] {
+        require_key(&accounts[vault_index], &read_pubkey(&data, vault_offset, "raydium cpmm pool")?, label)?;
+        require_key(&accounts[program_index], &read_pubkey(&data, program_offset, "raydium cpmm pool")?, label)?;
+        let mint = read_pubkey(&data, mint_offset, "raydium cpmm pool")?;
+        require_key(&accounts[mint_index], &mint, label)?;
+        require_token_mint(&accounts[token_index], &mint, label)?;
+    }
+    Ok(())
+}
+
+fn validate_meteora(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_meteora::*;
+
+    require_len(accounts, METEORA_SWAP_ACCOUNT_COUNT, "meteora")?;
+    require_key(&accounts[PROGRAM_INDEX], &METEORA_DLMM_PROGRAM_ID, "meteora program")?;
+
+    let lb_pair = &accounts[LB_PAIR_INDEX];
+    require_owner(lb_pair, &METEORA_DLMM_PROGRAM_ID, "meteora lb pair")?;
+    let data = lb_pair.try_borrow_data()?;
+    require_discriminator(&data, &LB_PAIR_DISCRIMINATOR, "meteora lb pair")?;
+
+    for (index, offset, label) in [
+        (RESERVE_X_INDEX, LB_PAIR_RESERVE_X_OFFSET, "meteora reserve x"),
+        (RESERVE_Y_INDEX, LB_PAIR_RESERVE_Y_OFFSET, "meteora reserve y"),
+        (TOKEN_X_MINT_INDEX, LB_PAIR_TOKEN_X_MINT_OFFSET, "meteora token x mint"),
+        (TOKEN_Y_MINT_INDEX, LB_PAIR_TOKEN_Y_MINT_OFFSET, "meteora token y mint"),
+        (ORACLE_INDEX, LB_PAIR_ORACLE_OFFSET, "meteora oracle"),
+    ] {
+        require_key(&accounts[index], &read_pubkey(&data, offset, "meteora lb pair")?, label)?;
+    }
+
+    // Extension optionnelle: l'ID du programme tient lieu de None
+    let bitmap_extension = &accounts[BIN_ARRAY_BITMAP_EXTENSION_INDEX];
+    if bitmap_extension.key() != METEORA_DLMM_PROGRAM_ID {
+        require_owner(bitmap_extension, &METEORA_DLMM_PROGRAM_ID, "meteora bitmap extension")?;
+    }
+
+    for bin_array in &accounts[METEORA_SWAP_ACCOUNT_COUNT..] {
+        require_owner(bin_array, &METEORA_DLMM_PROGRAM_ID, "meteora bin array")?;
+    }
+    Ok(())
+}
+
+fn validate_meteora_damm(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_meteora_damm::*;
+
+    require_len(accounts, METEORA_DAMM_SWAP_ACCOUNT_COUNT, "meteora damm")?;
+    require_key(&accounts[VAULT_PROGRAM_INDEX], &METEORA_VAULT_PROGRAM_ID, "meteora vault program")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "meteora damm token program")?;
+
+    let pool = &accounts[POOL_INDEX];
+    require_owner(pool, &METEORA_DAMM_PROGRAM_ID, "meteora damm pool")?;
+    let data = pool.try_borrow_data()?;
+    require_discriminator(&data, &DAMM_POOL_DISCRIMINATOR, "meteora damm pool")?;
+
+    for (index, offset, label) in [
+        (A_VAULT_INDEX, DAMM_A_VAULT_OFFSET, "meteora damm a vault"),
+        (B_VAULT_INDEX, DAMM_B_VAULT_OFFSET, "meteora damm b vault"),
+        (A_VAULT_LP_INDEX, DAMM_A_VAULT_LP_OFFSET, "meteora damm a vault lp"),
+        (B_VAULT_LP_INDEX, DAMM_B_VAULT_LP_OFFSET, "meteora damm b vault lp"),
+    ] {
+        require_key(&accounts[index], &read_pubkey(&data, offset, "meteora damm pool")?, label)?;
+    }
+
+    // Chaque vault de prêt doit pointer vers le token vault et le LP mint fournis
+    for (vault_index, token_vault_index, lp_mint_index, label) in [
+        (A_VAULT_INDEX, A_TOKEN_VAULT_INDEX, A_VAULT_LP_MINT_INDEX, "meteora damm a vault"),
+        (B_VAULT_INDEX, B_TOKEN_VAULT_INDEX, B_VAULT_LP_MINT_INDEX, "meteora damm b vault"),
+    ] {
+        let vault = &accounts[vault_index];
+        require_owner(vault, &METEORA_VAULT_PROGRAM_ID, label)?;
+        let vault_data = vault.try_borrow_data()?;
+        require_discriminator(&vault_data, &DYNAMIC_VAULT_DISCRIMINATOR, label)?;
+        require_key(&accounts[token_vault_index], &read_pubkey(&vault_data, DYNAMIC_VAULT_TOKEN_VAULT_OFFSET, label)?, label)?;
+        require_key(&accounts[lp_mint_index], &read_pubkey(&vault_data, DYNAMIC_VAULT_LP_MINT_OFFSET, label)?, label)?;
+    }
+
+    // Direction A->B ou B->A; les frais protocole sont prélevés sur le token d'entrée
+    let mint_a = read_pubkey(&data, DAMM_TOKEN_A_MINT_OFFSET, "meteora damm pool")?;
+    let mint_b = read_pubkey(&data, DAMM_TOKEN_B_MINT_OFFSET, "meteora damm pool")?;
+    let source = &accounts[USER_SOURCE_TOKEN_INDEX];
+    let (input_mint, output_mint, fee_offset) = if require_token_mint(source, &mint_a, "meteora damm source").is_ok() {
+        (mint_a, mint_b, DAMM_PROTOCOL_TOKEN_A_FEE_OFFSET)
+    } else {
+        (mint_b, mint_a, DAMM_PROTOCOL_TOKEN_B_FEE_OFFSET)
+    };
+    require_token_mint(source, &input_mint, "meteora damm source")?;
+    require_token_mint(&accounts[USER_DESTINATION_TOKEN_INDEX], &output_mint, "meteora damm destination")?;
+    require_key(
+        &accounts[PROTOCOL_TOKEN_FEE_INDEX],
+        &read_pubkey(&data, fee_offset, "meteora damm pool")?,
+        "meteora damm protocol fee",
+    )?;
+    Ok(())
+}
+
+fn validate_meteora_damm_v2(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_meteora_damm::*;
+
+    require_len(accounts, METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT, "meteora damm v2")?;
+    require_key(&accounts[V2_POOL_AUTHORITY_INDEX], &METEORA_DAMM_V2_POOL_AUTHORITY, "meteora damm v2 authority")?;
+    require_key(&accounts[V2_EVENT_AUTHORITY_INDEX], &METEORA_DAMM_V2_EVENT_AUTHORITY, "meteora damm v2 event authority")?;
+    require_key(&accounts[V2_PROGRAM_INDEX], &METEORA_DAMM_V2_PROGRAM_ID, "meteora damm v2 program")?;
+
+    let pool = &accounts[V2_POOL_INDEX];
+    require_owner(pool, &METEORA_DAMM_V2_PROGRAM_ID, "meteora damm v2 pool")?;
+    let pool_data = pool.try_borrow_data()?;
+    require_discriminator(&pool_data, &DAMM_POOL_DISCRIMINATOR, "meteora damm v2 pool")?;
+
+    // Mints et vaults: ceux enregistrés dans le pool
+    for (index, offset, label) in [
+        (V2_TOKEN_A_MINT_INDEX, DAMM_V2_TOKEN_A_MINT_OFFSET, "meteora damm v2 mint a"),
+        (V2_TOKEN_B_MINT_INDEX, DAMM_V2_TOKEN_B_MINT_OFFSET, "meteora damm v2 mint b"),
+        (V2_TOKEN_A_VAULT_INDEX, DAMM_V2_TOKEN_A_VAULT_OFFSET, "meteora damm v2 vault a"),
+        (V2_TOKEN_B_VAULT_INDEX, DAMM_V2_TOKEN_B_VAULT_OFFSET, "meteora damm v2 vault b"),
+    ] {
+        require_key(&accounts[index], &read_pubkey(&pool_data, offset, "meteora damm v2 pool")?, label)?;
+    }
+
+    // Vaults: comptes token détenus par l'autorité commune, sur les mints du pool
+    for (vault_index, mint_index, program_index, label) in [
+        (V2_TOKEN_A_VAULT_INDEX, V2_TOKEN_A_MINT_INDEX, V2_TOKEN_A_PROGRAM_INDEX, "meteora damm v2 vault a"),
+        (V2_TOKEN_B_VAULT_INDEX, V2_TOKEN_B_MINT_INDEX, V2_TOKEN_B_PROGRAM_INDEX, "meteora damm v2 vault b"),
+    ] {
+        let token_program = accounts[program_index].key;
+        if *token_program != anchor_spl::token::ID && *token_program != anchor_spl::token_2022::ID {
+            return invalid(label);
+        }
+        let mint = &accounts[mint_index];
+        require_owner(mint, token_program, label)?;
+        let vault = &accounts[vault_index];
+        require_owner(vault, token_program, label)?;
+        let vault_data = vault.try_borrow_data()?;
+        require_key(mint, &read_pubkey(&vault_data, 0, label)?, label)?;
+        require_key(&accounts[V2_POOL_AUTHORITY_INDEX], &read_pubkey(&vault_data, 32, label)?, label)?;
+    }
+
+    let (mint_a, mint_b) = (accounts[V2_TOKEN_A_MINT_INDEX].key(), accounts[V2_TOKEN_B_MINT_INDEX].key());
+    let input = &accounts[V2_INPUT_TOKEN_ACCOUNT_INDEX];
+    let (input_mint, output_mint) = if require_token_mint(input, &mint_a, "meteora damm v2 input").is_ok() {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    };
+    require_token_mint(input, &input_mint, "meteora damm v2 input")?;
+    require_token_mint(&accounts[V2_OUTPUT_TOKEN_ACCOUNT_INDEX], &output_mint, "meteora damm v2 output")?;
+    Ok(())
+}
+
+fn validate_phoenix(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_phoenix::*;
+
+    require_len(accounts, PHOENIX_SWAP_ACCOUNT_COUNT, "phoenix")?;
+    require_key(&accounts[PHOENIX_PROGRAM_INDEX], &PHOENIX_PROGRAM_ID, "phoenix program")?;
+    require_key(&accounts[LOG_AUTHORITY_INDEX], &PHOENIX_LOG_AUTHORITY, "phoenix log authority")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "phoenix token program")?;
+
+    let market = &accounts[MARKET_INDEX];
+    require_owner(market, &PHOENIX_PROGRAM_ID, "phoenix market")?;
+    let data = market.try_borrow_data()?;
+
+    require_key(
+        &accounts[BASE_VAULT_INDEX],
+        &read_pubkey(&data, PHOENIX_BASE_VAULT_OFFSET, "phoenix market")?,
+        "phoenix base vault",
+    )?;
+    require_key(
+        &accounts[QUOTE_VAULT_INDEX],
+        &read_pubkey(&data, PHOENIX_QUOTE_VAULT_OFFSET, "phoenix market")?,
+        "phoenix quote vault",
+    )?;
+    require_token_mint(
+        &accounts[BASE_ACCOUNT_INDEX],
+        &read_pubkey(&data, PHOENIX_BASE_MINT_OFFSET, "phoenix market")?,
+        "phoenix base account",
+    )?;
+    require_token_mint(
+        &accounts[QUOTE_ACCOUNT_INDEX],
+        &read_pubkey(&data, PHOENIX_QUOTE_MINT_OFFSET, "phoenix market")?,
+        "phoenix quote account",
+    )?;
+    Ok(())
+}
+
+fn validate_openbook_v2(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_openbook_v2::*;
+
+    require_len(accounts, OPENBOOK_V2_SWAP_ACCOUNT_COUNT, "openbook")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "openbook token program")?;
+    require_key(&accounts[SYSTEM_PROGRAM_INDEX], &anchor_lang::system_program::ID, "openbook system program")?;
+
+    let market = &accounts[MARKET_INDEX];
+    require_owner(market, &OPENBOOK_V2_PROGRAM_ID, "openbook market")?;
+    let data = market.try_borrow_data()?;
+    require_discriminator(&data, &OPENBOOK_MARKET_DISCRIMINATOR, "openbook market")?;
+
+    for (index, offset, label) in [
+        (MARKET_AUTHORITY_INDEX, OPENBOOK_MARKET_AUTHORITY_OFFSET, "openbook market authority"),
+        (BIDS_INDEX, OPENBOOK_BIDS_OFFSET, "openbook bids"),
+        (ASKS_INDEX, OPENBOOK_ASKS_OFFSET, "openbook asks"),
+        (EVENT_HEAP_INDEX, OPENBOOK_EVENT_HEAP_OFFSET, "openbook event heap"),
+        (MARKET_BASE_VAULT_INDEX, OPENBOOK_BASE_VAULT_OFFSET, "openbook base vault"),
+        (MARKET_QUOTE_VAULT_INDEX, OPENBOOK_QUOTE_VAULT_OFFSET, "openbook quote vault"),
+    ] {
+        require_key(&accounts[index], &read_pubkey(&data, offset, "openbook market")?, label)?;
+    }
+
+    // Comptes optionnels: l'ID du programme tient lieu de None
+    for (index, offset, label) in [
+        (ORACLE_A_INDEX, OPENBOOK_ORACLE_A_OFFSET, "openbook oracle a"),
+        (ORACLE_B_INDEX, OPENBOOK_ORACLE_B_OFFSET, "openbook oracle b"),
+        (OPEN_ORDERS_ADMIN_INDEX, OPENBOOK_OPEN_ORDERS_ADMIN_OFFSET, "openbook open orders admin"),
+    ] {
+        let expected = read_pubkey(&data, offset, "openbook market")?;
+        let expected = if expected == Pubkey::default() { OPENBOOK_V2_PROGRAM_ID } else { expected };
+        require_key(&accounts[index], &expected, label)?;
+    }
+
+    require_token_mint(
+        &accounts[USER_BASE_ACCOUNT_INDEX],
+        &read_pubkey(&data, OPENBOOK_BASE_MINT_OFFSET, "openbook market")?,
+        "openbook base account",
+    )?;
+    require_token_mint(
+        &accounts[USER_QUOTE_ACCOUNT_INDEX],
+        &read_pubkey(&data, OPENBOOK_QUOTE_MINT_OFFSET, "openbook market")?,
+        "openbook quote account",
+    )?;
+    Ok(())
+}
+
+fn validate_lifinity(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_lifinity::*;
+
+    require_len(accounts, LIFINITY_SWAP_ACCOUNT_COUNT, "lifinity")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "lifinity token program")?;
+    require_owner(&accounts[CONFIG_ACCOUNT_INDEX], &LIFINITY_PROGRAM_ID, "lifinity config")?;
+
+    let amm = &accounts[AMM_INDEX];
+    require_owner(amm, &LIFINITY_PROGRAM_ID, "lifinity amm")?;
+    let data = amm.try_borrow_data()?;
+    require_discriminator(&data, &LIFINITY_AMM_DISCRIMINATOR, "lifinity amm")?;
+
+    let token_a_account = read_pubkey(&data, LIFINITY_TOKEN_A_ACCOUNT_OFFSET, "lifinity amm")?;
+    let token_b_account = read_pubkey(&data, LIFINITY_TOKEN_B_ACCOUNT_OFFSET, "lifinity amm")?;
+    let swap_source = accounts[SWAP_SOURCE_INDEX].key();
+    let swap_destination = accounts[SWAP_DESTINATION_INDEX].key();
+    let vaults_match = (swap_source == token_a_account && swap_destination == token_b_account)
+        || (swap_source == token_b_account && swap_destination == token_a_account);
+    if !vaults_match {
+        return invalid("lifinity vaults");
+    }
+
+    require_key(
+        &accounts[POOL_MINT_INDEX],
+        &read_pubkey(&data, LIFINITY_POOL_MINT_OFFSET, "lifinity amm")?,
+        "lifinity pool mint",
+    )?;
+    require_key(
+        &accounts[FEE_ACCOUNT_INDEX],
+        &read_pubkey(&data, LIFINITY_FEE_ACCOUNT_OFFSET, "lifinity amm")?,
+        "lifinity fee account",
+    )?;
+    Ok(())
+}
+
+fn validate_sanctum(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_sanctum::*;
+
+    require_len(accounts, SANCTUM_SWAP_ACCOUNT_COUNT, "sanctum")?;
+    require_key(&accounts[INSTRUCTIONS_SYSVAR_INDEX], &sysvar::instructions::ID, "sanctum instructions sysvar")?;
+    require_owner(&accounts[LST_STATE_LIST_INDEX], &SANCTUM_PROGRAM_ID, "sanctum lst state list")?;
+
+    let pool_state = &accounts[POOL_STATE_INDEX];
+    require_owner(pool_state, &SANCTUM_PROGRAM_ID, "sanctum pool state")?;
+    require_key(pool_state, &lst_pricing::infinity_pool_state_address(), "sanctum pool state")?;
+
+    for (mint_index, token_program_index, token_index, label) in [
+        (SOURCE_LST_MINT_INDEX, SOURCE_TOKEN_PROGRAM_INDEX, SOURCE_TOKEN_ACCOUNT_INDEX, "sanctum source"),
+        (
+            DESTINATION_LST_MINT_INDEX,
+            DESTINATION_TOKEN_PROGRAM_INDEX,
+            DESTINATION_TOKEN_ACCOUNT_INDEX,
+            "sanctum destination",
+        ),
+    ] {
+        let mint = &accounts[mint_index];
+        require_owner(mint, accounts[token_program_index].key, label)?;
+        require_token_mint(&accounts[token_index], mint.key, label)?;
+    }
+    Ok(())
+}
+
+fn validate_saber(accounts: &[AccountInfo]) -> Result<()> {
+    use cpi_saber::*;
+
+    require_len(accounts, SABER_SWAP_ACCOUNT_COUNT, "saber")?;
+    require_key(&accounts[TOKEN_PROGRAM_INDEX], &anchor_spl::token::ID, "saber token program")?;
+    require_key(&accounts[CLOCK_INDEX], &sysvar::clock::ID, "saber clock")?;
+
+    let swap_info = &accounts[SWAP_INFO_INDEX];
+    require_owner(swap_info, &SABER_PROGRAM_ID, "saber swap info")?;
+    let data = swap_info.try_borrow_data()?;
+    if data.get(SABER_IS_INITIALIZED_OFFSET) != Some(&1) {
+        return invalid("saber swap info");
+    }
+
+    // Authority = PDA [swap_info, nonce]
+    let nonce = *data
+        .get(SABER_NONCE_OFFSET)
+        .ok_or_else(|| error!(ErrorCode::InvalidPoolAccount))?;
+    let authority = Pubkey::create_program_address(&[swap_info.key.as_ref(), &[nonce]], &SABER_PROGRAM_ID)
+        .map_err(|_| error!(ErrorCode::InvalidPoolAccount))?;
+    require_key(&accounts[SWAP_AUTHORITY_INDEX], &authority, "saber authority")?;
+
+    let reserves_a = read_pubkey(&data, SABER_TOKEN_A_RESERVES_OFFSET, "saber swap info")?;
+    let reserves_b = read_pubkey(&data, SABER_TOKEN_B_RESERVES_OFFSET, "saber swap info")?;
+    let swap_source = accounts[SWAP_SOURCE_INDEX].key();
+    let swap_destination = accounts[SWAP_DESTINATION_INDEX].key();
+    let (source_mint_offset, destination_mint_offset, admin_fees_offset) =
+        if swap_source == reserves_a && swap_destination == reserves_b {
+            (SABER_TOKEN_A_MINT_OFFSET, SABER_TOKEN_B_MINT_OFFSET, SABER_TOKEN_B_ADMIN_FEES_OFFSET)
+        } else if swap_source == reserves_b && swap_destination == reserves_a {
+            (SABER_TOKEN_B_MINT_OFFSET, SABER_TOKEN_A_MINT_OFFSET, SABER_TOKEN_A_ADMIN_FEES_OFFSET)
+        } else {
+            return invalid("saber reserves");
+        };
+
+    // Les frais admin sont prélevés sur le token de sortie
+    require_key(
+        &accounts[ADMIN_FEE_DESTINATION_INDEX],
+        &read_pubkey(&data, admin_fees_offset, "saber swap info")?,
+        "saber admin fees",
+    )?;
+    require_token_mint(
+        &accounts[SOURCE_ACCOUNT_INDEX],
+        &read_pubkey(&data, source_mint_offset, "saber swap info")?,
+        "saber source account",
+    )?;
+    require_token_mint(
+        &accounts[DESTINATION_ACCOUNT_INDEX],
+        &read_pubkey(&data, destination_mint_offset, "saber swap info")?,
+        "saber destination account",
+    )?;
+    Ok(())
+}
+
+fn invalid<T>(label: &str) -> Result<T> {
+    msg!("Pool validation failed: {}", label);
+    err!(ErrorCode::InvalidPoolAccount)
+}
+
+fn require_len(accounts: &[AccountInfo], expected: usize, label: &str) -> Result<()> {
+    if accounts.len() < expected {
+        return invalid(label);
+    }
+    Ok(())
+}
+
+fn require_key(account: &AccountInfo, expected: &Pubkey, label: &str) -> Result<()> {
+    if account.key != expected {
+        return invalid(label);
+    }
+    Ok(())
+}
+
+fn require_owner(account: &AccountInfo, program_id: &Pubkey, label: &str) -> Result<()> {
+    if account.owner != program_id {
+        return invalid(label);
+    }
+    Ok(())
+}
+
+fn require_discriminator(data: &[u8], discriminator: &[u8; 8], label: &str) -> Result<()> {
+    if data.len() < 8 || data[..8] != discriminator[..] {
+        return invalid(label);
+    }
+    Ok(())
+}
+
+fn read_pubkey(data: &[u8], offset: usize, label: &str) -> Result<Pubkey> {
+    match data.get(offset..offset + 32) {
+        Some(bytes) => Ok(Pubkey::new_from_array(bytes.try_into().unwrap())),
+        None => invalid(label),
+    }
+}
+
+/// Compte SPL Token initialisé portant le mint attendu
+fn require_token_mint(account: &AccountInfo, mint: &Pubkey, label: &str) -> Result<()> {
+    if account.owner != &anchor_spl::token::ID && account.owner != &anchor_spl::token_2022::ID {
+        return invalid(label);
+    }
+    let data = account.try_borrow_data()?;
+    match unpack_token_account(&data) {
+        Ok(token_account) if token_account.mint == *mint => Ok(()),
+        _ => invalid(label),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::state::{Account as SplAccount, AccountState};
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+            Self { key: Pubkey::new_unique(), owner, lamports: 1, data }
+        }
+
+        fn token(mint: Pubkey) -> Self {
+            let mut data = vec![0u8; SplAccount::LEN];
+            SplAccount::pack(
+                SplAccount { mint, state: AccountState::Initialized, ..SplAccount::default() },
+                &mut data,
+            )
+            .unwrap();
+            Self::new(anchor_spl::token::ID, data)
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(&self.key, false, true, &mut self.lamports, &mut self.data, &self.owner, false, 0)
+        }
+    }
+
+    fn write_pubkey(data: &mut [u8], offset: usize, key: &Pubkey) {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+
+    /// Comptes Orca cohérents: [token_program, authority, whirlpool, owner_a, vault_a, owner_b, vault_b, ticks x3, oracle]
+    fn orca_accounts() -> Vec<TestAccount> {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (vault_a, vault_b) = (TestAccount::token(mint_a), TestAccount::token(mint_b));
+
+        let mut pool_data = vec![0u8; 653];
+        pool_data[..8].copy_from_slice(&WHIRLPOOL_DISCRIMINATOR);
+        write_pubkey(&mut pool_data, WHIRLPOOL_TOKEN_MINT_A_OFFSET, &mint_a);
+        write_pubkey(&mut pool_data, WHIRLPOOL_TOKEN_VAULT_A_OFFSET, &vault_a.key);
+        write_pubkey(&mut pool_data, WHIRLPOOL_TOKEN_MINT_B_OFFSET, &mint_b);
+        write_pubkey(&mut pool_data, WHIRLPOOL_TOKEN_VAULT_B_OFFSET, &vault_b.key);
+
+        let mut token_program = TestAccount::new(Pubkey::default(), vec![]);
+        token_program.key = anchor_spl::token::ID;
+        vec![
+            token_program,
+            TestAccount::new(Pubkey::default(), vec![]),
+            TestAccount::new(ORCA_WHIRLPOOL_PROGRAM_ID, pool_data),
+            TestAccount::token(mint_a),
+            vault_a,
+            TestAccount::token(mint_b),
+            vault_b,
+            TestAccount::new(ORCA_WHIRLPOOL_PROGRAM_ID, vec![0; 8]),
+            TestAccount::new(ORCA_WHIRLPOOL_PROGRAM_ID, vec![0; 8]),
+            TestAccount::new(ORCA_WHIRLPOOL_PROGRAM_ID, vec![0; 8]),
+            TestAccount::new(Pubkey::default(), vec![]),
+        ]
+    }
+
+    /// Comptes CPMM cohérents, entrée sur le côté 1 (vault_1 -> vault_0)
+    fn cpmm_accounts() -> Vec<TestAccount> {
+        let (mint_0, mint_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (vault_0, vault_1) = (TestAccount::token(mint_0), TestAccount::token(mint_1));
+        let config = TestAccount::new(RAYDIUM_CPMM_PROGRAM_ID, CLMM_AMM_CONFIG_DISCRIMINATOR.to_vec());
+        let observation = TestAccount::new(RAYDIUM_CPMM_PROGRAM_ID, vec![0; 8]);
+
+        let mut pool_data = vec![0u8; 637];
+        pool_data[..8].copy_from_slice(&CLMM_POOL_STATE_DISCRIMINATOR);
+        write_pubkey(&mut pool_data, CPMM_AMM_CONFIG_OFFSET, &config.key);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_0_VAULT_OFFSET, &vault_0.key);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_1_VAULT_OFFSET, &vault_1.key);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_0_MINT_OFFSET, &mint_0);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_1_MINT_OFFSET, &mint_1);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_0_PROGRAM_OFFSET, &anchor_spl::token::ID);
+        write_pubkey(&mut pool_data, CPMM_TOKEN_1_PROGRAM_OFFSET, &anchor_spl::token::ID);
+        write_pubkey(&mut pool_data, CPMM_OBSERVATION_OFFSET, &observation.key);
+
+        let keyed = |key: Pubkey| {
+            let mut account = TestAccount::new(Pubkey::default(), vec![]);
+            account.key = key;
+            account
+        };
+        vec![
+            TestAccount::new(Pubkey::default(), vec![]),
+            keyed(RAYDIUM_CPMM_AUTHORITY),
+            config,
+            TestAccount::new(RAYDIUM_CPMM_PROGRAM_ID, pool_data),
+            TestAccount::token(mint_1),
+            TestAccount::token(mint_0),
+            vault_1,
+            vault_0,
+            keyed(anchor_spl::token::ID),
+            keyed(anchor_spl::token::ID),
+            keyed(mint_1),
+            keyed(mint_0),
+            observation,
+        ]
+    }
+
+    fn validate(program: &Pubkey, accounts: &mut [TestAccount]) -> Result<()> {
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(|a| a.info()).collect();
+        validate_venue_accounts(program, &infos)
+    }
+
+    #[test]
+    fn test_known_authorities() {
+        let (raydium, _) = Pubkey::find_program_address(&[b"amm authority"], &RAYDIUM_AMM_PROGRAM_ID);
+        assert_eq!(raydium, RAYDIUM_AMM_AUTHORITY);
+        let (phoenix, _) = Pubkey::find_program_address(&[b"log"], &PHOENIX_PROGRAM_ID);
+        assert_eq!(phoenix, PHOENIX_LOG_AUTHORITY);
+        let (cpmm, _) =
+            Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &RAYDIUM_CPMM_PROGRAM_ID);
+        assert_eq!(cpmm, RAYDIUM_CPMM_AUTHORITY);
+        let (damm_v2, _) = Pubkey::find_program_address(&[b"pool_authority"], &METEORA_DAMM_V2_PROGRAM_ID);
+        assert_eq!(damm_v2, METEORA_DAMM_V2_POOL_AUTHORITY);
+        let (damm_v2_events, _) =
+            Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DAMM_V2_PROGRAM_ID);
+        assert_eq!(damm_v2_events, METEORA_DAMM_V2_EVENT_AUTHORITY);
+    }
+
+    #[test]
+    fn test_orca_accepts_consistent_pool() {
+        let mut accounts = orca_accounts();
+        assert!(validate(&ORCA_WHIRLPOOL_PROGRAM_ID, &mut accounts).is_ok());
+    }
+
+    #[test]
+    fn test_orca_rejects_fake_pool_and_vault() {
+        // Pool possédé par un autre programme
+        let mut accounts = orca_accounts();
+        accounts[cpi_orca::WHIRLPOOL_INDEX].owner = Pubkey::new_unique();
+        assert!(validate(&ORCA_WHIRLPOOL_PROGRAM_ID, &mut accounts).is_err());
+
+        // Vault qui n'appartient pas au pool
+        let mut accounts = orca_accounts();
+        accounts[cpi_orca::TOKEN_VAULT_A_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&ORCA_WHIRLPOOL_PROGRAM_ID, &mut accounts).is_err());
+
+        // Compte utilisateur sur un autre mint
+        let mut accounts = orca_accounts();
+        accounts[cpi_orca::TOKEN_OWNER_B_INDEX] = TestAccount::token(Pubkey::new_unique());
+        assert!(validate(&ORCA_WHIRLPOOL_PROGRAM_ID, &mut accounts).is_err());
+
+        // Mauvais discriminator
+        let mut accounts = orca_accounts();
+        accounts[cpi_orca::WHIRLPOOL_INDEX].data[0] ^= 0xff;
+        assert!(validate(&ORCA_WHIRLPOOL_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    #[test]
+    fn test_raydium_cpmm_validation() {
+        let mut accounts = cpmm_accounts();
+        assert!(validate(&RAYDIUM_CPMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Mint d'entrée pris sur l'autre côté du pool
+        let mut accounts = cpmm_accounts();
+        accounts.swap(cpi_raydium_cpmm::INPUT_TOKEN_MINT_INDEX, cpi_raydium_cpmm::OUTPUT_TOKEN_MINT_INDEX);
+        assert!(validate(&RAYDIUM_CPMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Token program différent de celui enregistré dans le pool
+        let mut accounts = cpmm_accounts();
+        accounts[cpi_raydium_cpmm::OUTPUT_TOKEN_PROGRAM_INDEX].key = anchor_spl::token_2022::ID;
+        assert!(validate(&RAYDIUM_CPMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Pool CLMM présenté comme pool CPMM
+        let mut accounts = cpmm_accounts();
+        accounts[cpi_raydium_cpmm::POOL_STATE_INDEX].owner = RAYDIUM_CLMM_PROGRAM_ID;
+        assert!(validate(&RAYDIUM_CPMM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    #[test]
+    fn test_openbook_v2_validation() {
+        use cpi_openbook_v2::*;
+
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts: Vec<TestAccount> = (0..OPENBOOK_V2_SWAP_ACCOUNT_COUNT)
+            .map(|_| TestAccount::new(Pubkey::default(), vec![]))
+            .collect();
+        accounts[USER_BASE_ACCOUNT_INDEX] = TestAccount::token(base_mint);
+        accounts[USER_QUOTE_ACCOUNT_INDEX] = TestAccount::token(quote_mint);
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[SYSTEM_PROGRAM_INDEX].key = anchor_lang::system_program::ID;
+        // Pas d'oracle ni d'admin open orders sur ce marché
+        for index in [ORACLE_A_INDEX, ORACLE_B_INDEX, OPEN_ORDERS_ADMIN_INDEX] {
+            accounts[index].key = OPENBOOK_V2_PROGRAM_ID;
+        }
+
+        let mut market_data = vec![0u8; 848];
+        market_data[..8].copy_from_slice(&OPENBOOK_MARKET_DISCRIMINATOR);
+        write_pubkey(&mut market_data, OPENBOOK_BASE_MINT_OFFSET, &base_mint);
+        write_pubkey(&mut market_data, OPENBOOK_QUOTE_MINT_OFFSET, &quote_mint);
+        for (index, offset) in [
+            (MARKET_AUTHORITY_INDEX, OPENBOOK_MARKET_AUTHORITY_OFFSET),
+            (BIDS_INDEX, OPENBOOK_BIDS_OFFSET),
+            (ASKS_INDEX, OPENBOOK_ASKS_OFFSET),
+            (EVENT_HEAP_INDEX, OPENBOOK_EVENT_HEAP_OFFSET),
+            (MARKET_BASE_VAULT_INDEX, OPENBOOK_BASE_VAULT_OFFSET),
+            (MARKET_QUOTE_VAULT_INDEX, OPENBOOK_QUOTE_VAULT_OFFSET),
+        ] {
+            write_pubkey(&mut market_data, offset, &accounts[index].key);
+        }
+        accounts[MARKET_INDEX] = TestAccount::new(OPENBOOK_V2_PROGRAM_ID, market_data);
+        assert!(validate(&OPENBOOK_V2_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Marché avec oracle: l'oracle doit être fourni
+        let oracle = Pubkey::new_unique();
+        write_pubkey(&mut accounts[MARKET_INDEX].data, OPENBOOK_ORACLE_A_OFFSET, &oracle);
+        assert!(validate(&OPENBOOK_V2_PROGRAM_ID, &mut accounts).is_err());
+        accounts[ORACLE_A_INDEX].key = oracle;
+        assert!(validate(&OPENBOOK_V2_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Carnet bids d'un autre marché
+        accounts[BIDS_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&OPENBOOK_V2_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Dynamic AMM cohérents, swap B -> A
+    fn meteora_damm_accounts() -> Vec<TestAccount> {
+        use cpi_meteora_damm::*;
+
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts: Vec<TestAccount> = (0..METEORA_DAMM_SWAP_ACCOUNT_COUNT)
+            .map(|_| TestAccount::new(Pubkey::default(), vec![]))
+            .collect();
+        accounts[USER_SOURCE_TOKEN_INDEX] = TestAccount::token(mint_b);
+        accounts[USER_DESTINATION_TOKEN_INDEX] = TestAccount::token(mint_a);
+        accounts[VAULT_PROGRAM_INDEX].key = METEORA_VAULT_PROGRAM_ID;
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+
+        for (vault_index, token_vault_index, lp_mint_index) in [
+            (A_VAULT_INDEX, A_TOKEN_VAULT_INDEX, A_VAULT_LP_MINT_INDEX),
+            (B_VAULT_INDEX, B_TOKEN_VAULT_INDEX, B_VAULT_LP_MINT_INDEX),
+        ] {
+            let mut vault_data = vec![0u8; 1_232];
+            vault_data[..8].copy_from_slice(&DYNAMIC_VAULT_DISCRIMINATOR);
+            write_pubkey(&mut vault_data, DYNAMIC_VAULT_TOKEN_VAULT_OFFSET, &accounts[token_vault_index].key);
+            write_pubkey(&mut vault_data, DYNAMIC_VAULT_LP_MINT_OFFSET, &accounts[lp_mint_index].key);
+            accounts[vault_index] = TestAccount::new(METEORA_VAULT_PROGRAM_ID, vault_data);
+        }
+
+        let mut pool_data = vec![0u8; 944];
+        pool_data[..8].copy_from_slice(&DAMM_POOL_DISCRIMINATOR);
+        write_pubkey(&mut pool_data, DAMM_TOKEN_A_MINT_OFFSET, &mint_a);
+        write_pubkey(&mut pool_data, DAMM_TOKEN_B_MINT_OFFSET, &mint_b);
+        for (index, offset) in [
+            (A_VAULT_INDEX, DAMM_A_VAULT_OFFSET),
+            (B_VAULT_INDEX, DAMM_B_VAULT_OFFSET),
+            (A_VAULT_LP_INDEX, DAMM_A_VAULT_LP_OFFSET),
+            (B_VAULT_LP_INDEX, DAMM_B_VAULT_LP_OFFSET),
+            (PROTOCOL_TOKEN_FEE_INDEX, DAMM_PROTOCOL_TOKEN_B_FEE_OFFSET),
+        ] {
+            write_pubkey(&mut pool_data, offset, &accounts[index].key);
+        }
+        accounts[POOL_INDEX] = TestAccount::new(METEORA_DAMM_PROGRAM_ID, pool_data);
+        accounts
+    }
+
+    #[test]
+    fn test_meteora_damm_validation() {
+        use cpi_meteora_damm::*;
+
+        let mut accounts = meteora_damm_accounts();
+        assert!(validate(&METEORA_DAMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Frais protocole du mauvais côté
+        let mut accounts = meteora_damm_accounts();
+        let fee_a = Pubkey::new_unique();
+        write_pubkey(&mut accounts[POOL_INDEX].data, DAMM_PROTOCOL_TOKEN_A_FEE_OFFSET, &fee_a);
+        accounts[PROTOCOL_TOKEN_FEE_INDEX].key = fee_a;
+        assert!(validate(&METEORA_DAMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Token vault qui n'est pas celui du vault de prêt
+        let mut accounts = meteora_damm_accounts();
+        accounts[A_TOKEN_VAULT_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&METEORA_DAMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Pool DLMM présenté comme pool Dynamic AMM
+        let mut accounts = meteora_damm_accounts();
+        accounts[POOL_INDEX].owner = METEORA_DLMM_PROGRAM_ID;
+        assert!(validate(&METEORA_DAMM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes DAMM v2 cohérents, swap A -> B
+    fn meteora_damm_v2_accounts() -> Vec<TestAccount> {
+        use cpi_meteora_damm::*;
+
+        let mut accounts: Vec<TestAccount> = (0..METEORA_DAMM_V2_SWAP_ACCOUNT_COUNT)
+            .map(|_| TestAccount::new(Pubkey::default(), vec![]))
+            .collect();
+        accounts[V2_POOL_AUTHORITY_INDEX].key = METEORA_DAMM_V2_POOL_AUTHORITY;
+        accounts[V2_EVENT_AUTHORITY_INDEX].key = METEORA_DAMM_V2_EVENT_AUTHORITY;
+        accounts[V2_PROGRAM_INDEX].key = METEORA_DAMM_V2_PROGRAM_ID;
+        accounts[V2_TOKEN_A_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[V2_TOKEN_B_PROGRAM_INDEX].key = anchor_spl::token::ID;
+
+        let (mint_a, mint_b) = (
+            TestAccount::new(anchor_spl::token::ID, vec![0; 82]),
+            TestAccount::new(anchor_spl::token::ID, vec![0; 82]),
+        );
+        let mut pool_data = vec![0u8; 1_112];
+        pool_data[..8].copy_from_slice(&DAMM_POOL_DISCRIMINATOR);
+        for (mint, vault_index, mint_offset, vault_offset) in [
+            (&mint_a, V2_TOKEN_A_VAULT_INDEX, DAMM_V2_TOKEN_A_MINT_OFFSET, DAMM_V2_TOKEN_A_VAULT_OFFSET),
+            (&mint_b, V2_TOKEN_B_VAULT_INDEX, DAMM_V2_TOKEN_B_MINT_OFFSET, DAMM_V2_TOKEN_B_VAULT_OFFSET),
+        ] {
+            let mut vault = TestAccount::token(mint.key);
+            write_pubkey(&mut vault.data, 32, &METEORA_DAMM_V2_POOL_AUTHORITY);
+            write_pubkey(&mut pool_data, mint_offset, &mint.key);
+            write_pubkey(&mut pool_data, vault_offset, &vault.key);
+            accounts[vault_index] = vault;
+        }
+        accounts[V2_INPUT_TOKEN_ACCOUNT_INDEX] = TestAccount::token(mint_a.key);
+        accounts[V2_OUTPUT_TOKEN_ACCOUNT_INDEX] = TestAccount::token(mint_b.key);
+        accounts[V2_TOKEN_A_MINT_INDEX] = mint_a;
+        accounts[V2_TOKEN_B_MINT_INDEX] = mint_b;
+        accounts[V2_POOL_INDEX] = TestAccount::new(METEORA_DAMM_V2_PROGRAM_ID, pool_data);
+        accounts
+    }
+
+    #[test]
+    fn test_meteora_damm_v2_validation() {
+        use cpi_meteora_damm::*;
+
+        let mut accounts = meteora_damm_v2_accounts();
+        assert!(validate(&METEORA_DAMM_V2_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Vault d'un autre pool, bien que détenu par l'autorité commune sur le bon mint
+        let mut accounts = meteora_damm_v2_accounts();
+        let mint_a = accounts[V2_TOKEN_A_MINT_INDEX].key;
+        let mut other_vault = TestAccount::token(mint_a);
+        write_pubkey(&mut other_vault.data, 32, &METEORA_DAMM_V2_POOL_AUTHORITY);
+        accounts[V2_TOKEN_A_VAULT_INDEX] = other_vault;
+        assert!(validate(&METEORA_DAMM_V2_PROGRAM_ID, &mut accounts).is_err());
+
+        // Mint qui n'est pas celui du pool
+        let mut accounts = meteora_damm_v2_accounts();
+        accounts[V2_TOKEN_B_MINT_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&METEORA_DAMM_V2_PROGRAM_ID, &mut accounts).is_err());
+
+        // Pool Dynamic AMM (v1) présenté comme pool DAMM v2
+        let mut accounts = meteora_damm_v2_accounts();
+        accounts[V2_POOL_INDEX].owner = METEORA_DAMM_PROGRAM_ID;
+        assert!(validate(&METEORA_DAMM_V2_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    fn blank_accounts(count: usize) -> Vec<TestAccount> {
+        (0..count).map(|_| TestAccount::new(Pubkey::default(), vec![])).collect()
+    }
+
+    /// Comptes Raydium AMM v4 cohérents (marché OpenBook détenu par son programme)
+    fn raydium_amm_accounts() -> Vec<TestAccount> {
+        use cpi_raydium::*;
+
+        let mut accounts = blank_accounts(RAYDIUM_SWAP_ACCOUNT_COUNT);
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[AMM_AUTHORITY_INDEX].key = RAYDIUM_AMM_AUTHORITY;
+        let market_program = accounts[MARKET_PROGRAM_INDEX].key;
+        for index in [MARKET_INDEX, MARKET_BIDS_INDEX, MARKET_ASKS_INDEX, MARKET_EVENT_QUEUE_INDEX] {
+            accounts[index].owner = market_program;
+        }
+
+        let mut pool_data = vec![0u8; RAYDIUM_AMM_INFO_LEN];
+        for (index, offset) in [
+            (AMM_OPEN_ORDERS_INDEX, RAYDIUM_OPEN_ORDERS_OFFSET),
+            (AMM_COIN_VAULT_INDEX, RAYDIUM_COIN_VAULT_OFFSET),
+            (AMM_PC_VAULT_INDEX, RAYDIUM_PC_VAULT_OFFSET),
+            (MARKET_INDEX, RAYDIUM_MARKET_OFFSET),
+            (MARKET_PROGRAM_INDEX, RAYDIUM_MARKET_PROGRAM_OFFSET),
+        ] {
+            write_pubkey(&mut pool_data, offset, &accounts[index].key);
+        }
+        accounts[AMM_POOL_INDEX] = TestAccount::new(RAYDIUM_AMM_PROGRAM_ID, pool_data);
+        accounts
+    }
+
+    #[test]
+    fn test_raydium_amm_validation() {
+        use cpi_raydium::*;
+
+        let mut accounts = raydium_amm_accounts();
+        assert!(validate(&RAYDIUM_AMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Vault coin d'un autre pool
+        let mut accounts = raydium_amm_accounts();
+        accounts[AMM_COIN_VAULT_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&RAYDIUM_AMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Event queue qui n'appartient pas au programme du marché
+        let mut accounts = raydium_amm_accounts();
+        accounts[MARKET_EVENT_QUEUE_INDEX].owner = Pubkey::new_unique();
+        assert!(validate(&RAYDIUM_AMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Layout AmmInfo tronqué
+        let mut accounts = raydium_amm_accounts();
+        accounts[AMM_POOL_INDEX].data.truncate(RAYDIUM_AMM_INFO_LEN - 1);
+        assert!(validate(&RAYDIUM_AMM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Raydium CLMM cohérents, swap 0 -> 1
+    fn raydium_clmm_accounts() -> Vec<TestAccount> {
+        use cpi_raydium_clmm::*;
+
+        let (mint_0, mint_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = blank_accounts(RAYDIUM_CLMM_SWAP_ACCOUNT_COUNT);
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[AMM_CONFIG_INDEX] = TestAccount::new(RAYDIUM_CLMM_PROGRAM_ID, CLMM_AMM_CONFIG_DISCRIMINATOR.to_vec());
+        accounts[INPUT_TOKEN_ACCOUNT_INDEX] = TestAccount::token(mint_0);
+        accounts[OUTPUT_TOKEN_ACCOUNT_INDEX] = TestAccount::token(mint_1);
+        for tick_array in &mut accounts[TICK_ARRAY_0_INDEX..=TICK_ARRAY_2_INDEX] {
+            tick_array.owner = RAYDIUM_CLMM_PROGRAM_ID;
+        }
+
+        let mut pool_data = vec![0u8; 1_544];
+        pool_data[..8].copy_from_slice(&CLMM_POOL_STATE_DISCRIMINATOR);
+        write_pubkey(&mut pool_data, CLMM_TOKEN_MINT_0_OFFSET, &mint_0);
+        write_pubkey(&mut pool_data, CLMM_TOKEN_MINT_1_OFFSET, &mint_1);
+        for (index, offset) in [
+            (AMM_CONFIG_INDEX, CLMM_AMM_CONFIG_OFFSET),
+            (INPUT_VAULT_INDEX, CLMM_TOKEN_VAULT_0_OFFSET),
+            (OUTPUT_VAULT_INDEX, CLMM_TOKEN_VAULT_1_OFFSET),
+            (OBSERVATION_STATE_INDEX, CLMM_OBSERVATION_OFFSET),
+        ] {
+            write_pubkey(&mut pool_data, offset, &accounts[index].key);
+        }
+        accounts[POOL_STATE_INDEX] = TestAccount::new(RAYDIUM_CLMM_PROGRAM_ID, pool_data);
+        accounts
+    }
+
+    #[test]
+    fn test_raydium_clmm_validation() {
+        use cpi_raydium_clmm::*;
+
+        let mut accounts = raydium_clmm_accounts();
+        assert!(validate(&RAYDIUM_CLMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Sens 1 -> 0: vaults et comptes utilisateur inversés ensemble
+        let mut accounts = raydium_clmm_accounts();
+        accounts.swap(INPUT_VAULT_INDEX, OUTPUT_VAULT_INDEX);
+        accounts.swap(INPUT_TOKEN_ACCOUNT_INDEX, OUTPUT_TOKEN_ACCOUNT_INDEX);
+        assert!(validate(&RAYDIUM_CLMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Vaults inversés seuls: mints utilisateur incohérents
+        let mut accounts = raydium_clmm_accounts();
+        accounts.swap(INPUT_VAULT_INDEX, OUTPUT_VAULT_INDEX);
+        assert!(validate(&RAYDIUM_CLMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // AmmConfig d'un autre pool
+        let mut accounts = raydium_clmm_accounts();
+        accounts[AMM_CONFIG_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&RAYDIUM_CLMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Tick array détenu par un autre programme
+        let mut accounts = raydium_clmm_accounts();
+        accounts[TICK_ARRAY_1_INDEX].owner = ORCA_WHIRLPOOL_PROGRAM_ID;
+        assert!(validate(&RAYDIUM_CLMM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Meteora DLMM cohérents, sans bitmap extension
+    fn meteora_dlmm_accounts() -> Vec<TestAccount> {
+        use cpi_meteora::*;
+
+        let mut accounts = blank_accounts(METEORA_SWAP_ACCOUNT_COUNT);
+        accounts[PROGRAM_INDEX].key = METEORA_DLMM_PROGRAM_ID;
+        accounts[BIN_ARRAY_BITMAP_EXTENSION_INDEX].key = METEORA_DLMM_PROGRAM_ID;
+
+        let mut pool_data = vec![0u8; 904];
+        pool_data[..8].copy_from_slice(&LB_PAIR_DISCRIMINATOR);
+        for (index, offset) in [
+            (RESERVE_X_INDEX, LB_PAIR_RESERVE_X_OFFSET),
+            (RESERVE_Y_INDEX, LB_PAIR_RESERVE_Y_OFFSET),
+            (TOKEN_X_MINT_INDEX, LB_PAIR_TOKEN_X_MINT_OFFSET),
+            (TOKEN_Y_MINT_INDEX, LB_PAIR_TOKEN_Y_MINT_OFFSET),
+            (ORACLE_INDEX, LB_PAIR_ORACLE_OFFSET),
+        ] {
+            write_pubkey(&mut pool_data, offset, &accounts[index].key);
+        }
+        accounts[LB_PAIR_INDEX] = TestAccount::new(METEORA_DLMM_PROGRAM_ID, pool_data);
+        accounts.push(TestAccount::new(METEORA_DLMM_PROGRAM_ID, vec![0; 8]));
+        accounts
+    }
+
+    #[test]
+    fn test_meteora_dlmm_validation() {
+        use cpi_meteora::*;
+
+        let mut accounts = meteora_dlmm_accounts();
+        assert!(validate(&METEORA_DLMM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Oracle d'un autre lb pair
+        let mut accounts = meteora_dlmm_accounts();
+        accounts[ORACLE_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&METEORA_DLMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Bitmap extension fournie mais détenue par un autre programme
+        let mut accounts = meteora_dlmm_accounts();
+        accounts[BIN_ARRAY_BITMAP_EXTENSION_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&METEORA_DLMM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Bin array étranger en fin de liste
+        let mut accounts = meteora_dlmm_accounts();
+        accounts.push(TestAccount::new(Pubkey::new_unique(), vec![0; 8]));
+        assert!(validate(&METEORA_DLMM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Phoenix cohérents
+    fn phoenix_accounts() -> Vec<TestAccount> {
+        use cpi_phoenix::*;
+
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = blank_accounts(PHOENIX_SWAP_ACCOUNT_COUNT);
+        accounts[PHOENIX_PROGRAM_INDEX].key = PHOENIX_PROGRAM_ID;
+        accounts[LOG_AUTHORITY_INDEX].key = PHOENIX_LOG_AUTHORITY;
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[BASE_ACCOUNT_INDEX] = TestAccount::token(base_mint);
+        accounts[QUOTE_ACCOUNT_INDEX] = TestAccount::token(quote_mint);
+
+        let mut market_data = vec![0u8; 624];
+        write_pubkey(&mut market_data, PHOENIX_BASE_MINT_OFFSET, &base_mint);
+        write_pubkey(&mut market_data, PHOENIX_QUOTE_MINT_OFFSET, &quote_mint);
+        write_pubkey(&mut market_data, PHOENIX_BASE_VAULT_OFFSET, &accounts[BASE_VAULT_INDEX].key);
+        write_pubkey(&mut market_data, PHOENIX_QUOTE_VAULT_OFFSET, &accounts[QUOTE_VAULT_INDEX].key);
+        accounts[MARKET_INDEX] = TestAccount::new(PHOENIX_PROGRAM_ID, market_data);
+        accounts
+    }
+
+    #[test]
+    fn test_phoenix_validation() {
+        use cpi_phoenix::*;
+
+        let mut accounts = phoenix_accounts();
+        assert!(validate(&PHOENIX_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Vault quote d'un autre marché
+        let mut accounts = phoenix_accounts();
+        accounts[QUOTE_VAULT_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&PHOENIX_PROGRAM_ID, &mut accounts).is_err());
+
+        // Comptes base et quote de l'utilisateur inversés
+        let mut accounts = phoenix_accounts();
+        accounts.swap(BASE_ACCOUNT_INDEX, QUOTE_ACCOUNT_INDEX);
+        assert!(validate(&PHOENIX_PROGRAM_ID, &mut accounts).is_err());
+
+        // Marché détenu par un autre programme
+        let mut accounts = phoenix_accounts();
+        accounts[MARKET_INDEX].owner = OPENBOOK_V2_PROGRAM_ID;
+        assert!(validate(&PHOENIX_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Lifinity cohérents, swap A -> B
+    fn lifinity_accounts() -> Vec<TestAccount> {
+        use cpi_lifinity::*;
+
+        let mut accounts = blank_accounts(LIFINITY_SWAP_ACCOUNT_COUNT);
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[CONFIG_ACCOUNT_INDEX].owner = LIFINITY_PROGRAM_ID;
+
+        let mut amm_data = vec![0u8; 895];
+        amm_data[..8].copy_from_slice(&LIFINITY_AMM_DISCRIMINATOR);
+        for (index, offset) in [
+            (SWAP_SOURCE_INDEX, LIFINITY_TOKEN_A_ACCOUNT_OFFSET),
+            (SWAP_DESTINATION_INDEX, LIFINITY_TOKEN_B_ACCOUNT_OFFSET),
+            (POOL_MINT_INDEX, LIFINITY_POOL_MINT_OFFSET),
+            (FEE_ACCOUNT_INDEX, LIFINITY_FEE_ACCOUNT_OFFSET),
+        ] {
+            write_pubkey(&mut amm_data, offset, &accounts[index].key);
+        }
+        accounts[AMM_INDEX] = TestAccount::new(LIFINITY_PROGRAM_ID, amm_data);
+        accounts
+    }
+
+    #[test]
+    fn test_lifinity_validation() {
+        use cpi_lifinity::*;
+
+        let mut accounts = lifinity_accounts();
+        assert!(validate(&LIFINITY_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Sens B -> A
+        let mut accounts = lifinity_accounts();
+        accounts.swap(SWAP_SOURCE_INDEX, SWAP_DESTINATION_INDEX);
+        assert!(validate(&LIFINITY_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Vault de destination étranger au pool
+        let mut accounts = lifinity_accounts();
+        accounts[SWAP_DESTINATION_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&LIFINITY_PROGRAM_ID, &mut accounts).is_err());
+
+        // Compte de frais d'un autre pool
+        let mut accounts = lifinity_accounts();
+        accounts[FEE_ACCOUNT_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&LIFINITY_PROGRAM_ID, &mut accounts).is_err());
+
+        // Config non détenue par Lifinity
+        let mut accounts = lifinity_accounts();
+        accounts[CONFIG_ACCOUNT_INDEX].owner = Pubkey::default();
+        assert!(validate(&LIFINITY_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Sanctum Infinity cohérents
+    fn sanctum_accounts() -> Vec<TestAccount> {
+        use cpi_sanctum::*;
+
+        let mut accounts = blank_accounts(SANCTUM_SWAP_ACCOUNT_COUNT);
+        accounts[INSTRUCTIONS_SYSVAR_INDEX].key = sysvar::instructions::ID;
+        accounts[LST_STATE_LIST_INDEX].owner = SANCTUM_PROGRAM_ID;
+        accounts[POOL_STATE_INDEX].key = lst_pricing::infinity_pool_state_address();
+        accounts[POOL_STATE_INDEX].owner = SANCTUM_PROGRAM_ID;
+        for (mint_index, program_index, token_index) in [
+            (SOURCE_LST_MINT_INDEX, SOURCE_TOKEN_PROGRAM_INDEX, SOURCE_TOKEN_ACCOUNT_INDEX),
+            (DESTINATION_LST_MINT_INDEX, DESTINATION_TOKEN_PROGRAM_INDEX, DESTINATION_TOKEN_ACCOUNT_INDEX),
+        ] {
+            accounts[program_index].key = anchor_spl::token::ID;
+            accounts[mint_index].owner = anchor_spl::token::ID;
+            accounts[token_index] = TestAccount::token(accounts[mint_index].key);
+        }
+        accounts
+    }
+
+    #[test]
+    fn test_sanctum_validation() {
+        use cpi_sanctum::*;
+
+        let mut accounts = sanctum_accounts();
+        assert!(validate(&SANCTUM_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Pool state qui n'est pas le PDA Infinity
+        let mut accounts = sanctum_accounts();
+        accounts[POOL_STATE_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&SANCTUM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Mint détenu par un autre token program que celui fourni
+        let mut accounts = sanctum_accounts();
+        accounts[DESTINATION_TOKEN_PROGRAM_INDEX].key = anchor_spl::token_2022::ID;
+        assert!(validate(&SANCTUM_PROGRAM_ID, &mut accounts).is_err());
+
+        // Compte de destination sur un autre mint
+        let mut accounts = sanctum_accounts();
+        accounts[DESTINATION_TOKEN_ACCOUNT_INDEX] = TestAccount::token(Pubkey::new_unique());
+        assert!(validate(&SANCTUM_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    /// Comptes Saber cohérents, swap A -> B
+    fn saber_accounts() -> Vec<TestAccount> {
+        use cpi_saber::*;
+
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = blank_accounts(SABER_SWAP_ACCOUNT_COUNT);
+        accounts[TOKEN_PROGRAM_INDEX].key = anchor_spl::token::ID;
+        accounts[CLOCK_INDEX].key = sysvar::clock::ID;
+        accounts[SOURCE_ACCOUNT_INDEX] = TestAccount::token(mint_a);
+        accounts[DESTINATION_ACCOUNT_INDEX] = TestAccount::token(mint_b);
+
+        let swap_info_key = Pubkey::new_unique();
+        let (authority, nonce) = Pubkey::find_program_address(&[swap_info_key.as_ref()], &SABER_PROGRAM_ID);
+        accounts[SWAP_AUTHORITY_INDEX].key = authority;
+
+        let mut swap_data = vec![0u8; 395];
+        swap_data[SABER_IS_INITIALIZED_OFFSET] = 1;
+        swap_data[SABER_NONCE_OFFSET] = nonce;
+        write_pubkey(&mut swap_data, SABER_TOKEN_A_MINT_OFFSET, &mint_a);
+        write_pubkey(&mut swap_data, SABER_TOKEN_B_MINT_OFFSET, &mint_b);
+        for (index, offset) in [
+            (SWAP_SOURCE_INDEX, SABER_TOKEN_A_RESERVES_OFFSET),
+            (SWAP_DESTINATION_INDEX, SABER_TOKEN_B_RESERVES_OFFSET),
+            (ADMIN_FEE_DESTINATION_INDEX, SABER_TOKEN_B_ADMIN_FEES_OFFSET),
+        ] {
+            write_pubkey(&mut swap_data, offset, &accounts[index].key);
+        }
+        accounts[SWAP_INFO_INDEX] = TestAccount::new(SABER_PROGRAM_ID, swap_data);
+        accounts[SWAP_INFO_INDEX].key = swap_info_key;
+        accounts
+    }
+
+    #[test]
+    fn test_saber_validation() {
+        use cpi_saber::*;
+
+        let mut accounts = saber_accounts();
+        assert!(validate(&SABER_PROGRAM_ID, &mut accounts).is_ok());
+
+        // Frais admin pris côté entrée au lieu du côté sortie
+        let mut accounts = saber_accounts();
+        let fees_a = Pubkey::new_unique();
+        write_pubkey(&mut accounts[SWAP_INFO_INDEX].data, SABER_TOKEN_A_ADMIN_FEES_OFFSET, &fees_a);
+        accounts[ADMIN_FEE_DESTINATION_INDEX].key = fees_a;
+        assert!(validate(&SABER_PROGRAM_ID, &mut accounts).is_err());
+
+        // Autorité qui n'est pas le PDA [swap_info, nonce]
+        let mut accounts = saber_accounts();
+        accounts[SWAP_AUTHORITY_INDEX].key = Pubkey::new_unique();
+        assert!(validate(&SABER_PROGRAM_ID, &mut accounts).is_err());
+
+        // Swap non initialisé
+        let mut accounts = saber_accounts();
+        accounts[SWAP_INFO_INDEX].data[SABER_IS_INITIALIZED_OFFSET] = 0;
+        assert!(validate(&SABER_PROGRAM_ID, &mut accounts).is_err());
+    }
+
+    #[test]
+    fn test_unknown_venue_is_not_validated_here() {
+        assert!(validate(&Pubkey::new_unique(), &mut []).is_ok());
+    }
+}
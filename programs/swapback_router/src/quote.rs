@@ -0,0 +1,634 @@
+//! Cotation on-chain d'une route (`quote_route`), sans exécution
+//!
+//! Même découpage et mêmes comptes que `execute_venues_swap`: chaque venue lit
+//! l'état de son pool et estime sa sortie brute (avant frais plateforme et
+//! transfer fees Token-2022). Destiné à `simulateTransaction`.
+//!
+//! Estimations:
+//! - Orca Whirlpool: liquidité courante, sans franchissement de tick
+//! - Raydium AMM v4: produit constant sur les vaults (PnL à prélever déduit)
+//! - Saber: invariant StableSwap avec l'amp courant (rampe comprise)
+//! - Phoenix: meilleur ordre du carnet uniquement (sortie plafonnée à sa taille)
+//!
+//! Les autres venues sont retournées avec `supported = false`.
+//!
+//! `plan_shortfall` réutilise ces estimations pour choisir, avant tout CPI, le
+//! premier plan (principal puis fallbacks) capable d'atteindre son min_out.
+
+use anchor_lang::prelude::*;
+
+use crate::balance_delta::read_token_amount;
+use crate::math::mul_div_u128;
+use crate::phoenix_maker::{
+    PhoenixMarketHeader, PHOENIX_BIDS_OFFSET, PHOENIX_BIDS_SIZE_OFFSET, PHOENIX_TAKER_FEE_BPS_OFFSET,
+    RB_LEFT, RB_MAX_DEPTH, RB_NODE_LEN, RB_TREE_HEADER_LEN, SIDE_ASK, SIDE_BID,
+};
+use crate::token_extensions::unpack_token_account;
+use crate::{
+    cpi_orca, cpi_phoenix, cpi_raydium, cpi_saber, pool_validation, ErrorCode, QuoteRoute,
+    QuoteRouteArgs, RouteQuote, VenueQuote, VenueRegistry, VenueWeight, MAX_VENUES,
+    METEORA_DLMM_PROGRAM_ID, ORCA_WHIRLPOOL_PROGRAM_ID, PHOENIX_PROGRAM_ID,
+    RAYDIUM_AMM_PROGRAM_ID, SABER_PROGRAM_ID,
+};
+
+const Q64: u128 = 1 << 64;
+
+/// Whirlpool: fee_rate (u16, centièmes de bps), liquidity (u128), sqrt_price (u128, Q64.64)
+const WHIRLPOOL_FEE_RATE_OFFSET: usize = 45;
+const WHIRLPOOL_LIQUIDITY_OFFSET: usize = 49;
+const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+const WHIRLPOOL_TOKEN_MINT_A_OFFSET: usize = 101;
+const WHIRLPOOL_FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// Raydium AmmInfo: frais de swap et PnL à prélever sur les vaults
+const RAYDIUM_SWAP_FEE_NUMERATOR_OFFSET: usize = 176;
+const RAYDIUM_SWAP_FEE_DENOMINATOR_OFFSET: usize = 184;
+const RAYDIUM_NEED_TAKE_PNL_COIN_OFFSET: usize = 192;
+const RAYDIUM_NEED_TAKE_PNL_PC_OFFSET: usize = 200;
+
+/// Saber SwapInfo: rampe d'amp et frais de trade
+const SABER_INITIAL_AMP_OFFSET: usize = 3;
+const SABER_TARGET_AMP_OFFSET: usize = 11;
+const SABER_START_RAMP_TS_OFFSET: usize = 19;
+const SABER_STOP_RAMP_TS_OFFSET: usize = 27;
+const SABER_TRADE_FEE_NUMERATOR_OFFSET: usize = 365;
+const SABER_TRADE_FEE_DENOMINATOR_OFFSET: usize = 373;
+const SABER_MAX_ITERATIONS: usize = 256;
+
+pub fn quote_route(ctx: Context<QuoteRoute>, args: QuoteRouteArgs) -> Result<RouteQuote> {
+    require!(args.amount_in > 0, ErrorCode::InvalidAmount);
+    require!(
+        !args.venues.is_empty() && args.venues.len() <= MAX_VENUES,
+        ErrorCode::InvalidPlanWeights
+    );
+    let total_weight: u32 = args.venues.iter().map(|v| v.weight as u32).sum();
+    require!(total_weight == 10_000, ErrorCode::InvalidPlanWeights);
+
+    let quote = quote_venues(
+        &ctx.accounts.venue_registry,
+        ctx.remaining_accounts,
+        &args.venues,
+        &args.input_mint,
+        args.amount_in,
+        &Clock::get()?,
+    )?;
+    msg!("📐 Quote: {} in -> {} out over {} venues", quote.amount_in, quote.amount_out, quote.venues.len());
+    Ok(quote)
+}
+
+/// Raison pour laquelle un plan n'est pas retenu d'office
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shortfall {
+    /// Venue sans estimateur: viabilité inconnue avant le CPI
+    Unestimated(Pubkey),
+    /// Cotation impossible, ou venue cotée sous sa part du min_out
+    BelowMinOut(String),
+}
+
+/// Raison pour laquelle un plan pourrait ne pas atteindre son min_out (None: viable)
+///
+/// Chaque venue cotée doit couvrir sa part du min_out, comme dans
+/// `execute_venues_swap`; une venue sans estimateur rend le plan `Unestimated`.
+/// Une venue cotée trop bas l'emporte sur une venue non estimable.
+pub fn plan_shortfall(
+    registry: &VenueRegistry,
+    remaining_accounts: &[AccountInfo],
+    venues: &[VenueWeight],
+    input_mint: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    clock: &Clock,
+) -> Option<Shortfall> {
+    let quote = match quote_venues(registry, remaining_accounts, venues, input_mint, amount_in, clock) {
+        Ok(quote) => quote,
+        Err(error) => {
+            msg!("Plan quote failed: {:?}", error);
+            return Some(Shortfall::BelowMinOut(String::from("Quote failed")));
+        }
+    };
+    for (venue_quote, venue_weight) in quote.venues.iter().zip(venues) {
+        let venue_min_out = (min_out as u128 * venue_weight.weight as u128 / 10_000) as u64;
+        if venue_quote.supported && venue_quote.amount_out < venue_min_out {
+            return Some(Shortfall::BelowMinOut(format!(
+                "{} quoted {} < min_out {}",
+                venue_quote.venue, venue_quote.amount_out, venue_min_out
+            )));
+        }
+    }
+    quote
+        .venues
+        .iter()
+        .find(|venue_quote| !venue_quote.supported)
+        .map(|venue_quote| Shortfall::Unestimated(venue_quote.venue))
+}
+
+/// Estimations par venue, mêmes découpage et curseur de comptes que `execute_venues_swap`
+pub(crate) fn quote_venues(
+    registry: &VenueRegistry,
+    remaining_accounts: &[AccountInfo],
+    venues: &[VenueWeight],
+    input_mint: &Pubkey,
+    amount_in: u64,
+    clock: &Clock,
+) -> Result<RouteQuote> {
+    let mut account_cursor = 0usize;
+    let mut quote = RouteQuote { amount_in, ..Default::default() };
+
+    for (venue_index, venue_weight) in venues.iter().enumerate() {
+        let registered = registry.enabled_entry(&venue_weight.venue)?.account_count as usize;
+        // Meteora DLMM: comptes dynamiques, forcément en dernière position
+        let required_accounts = if venue_weight.venue == METEORA_DLMM_PROGRAM_ID {
+            require!(venue_index + 1 == venues.len(), ErrorCode::DexExecutionFailed);
+            remaining_accounts.len().saturating_sub(account_cursor)
+        } else {
+            registered
+        };
+        require!(
+            required_accounts >= registered
+                && account_cursor + required_accounts <= remaining_accounts.len(),
+            ErrorCode::DexExecutionFailed
+        );
+        let account_slice = &remaining_accounts[account_cursor..account_cursor + required_accounts];
+        account_cursor += required_accounts;
+        registry.check_pools(&venue_weight.venue, account_slice, clock.unix_timestamp)?;
+
+        let venue_amount_in = ((amount_in as u128 * venue_weight.weight as u128) / 10_000) as u64;
+        let estimate = if venue_amount_in == 0 {
+            Some(0)
+        } else {
+            pool_validation::validate_venue_accounts(&venue_weight.venue, account_slice)?;
+            quote_venue(&venue_weight.venue, account_slice, input_mint, venue_amount_in, clock)?
+        };
+
+        let amount_out = estimate.unwrap_or(0);
+        quote.amount_out = quote.amount_out.saturating_add(amount_out);
+        quote.venues.push(VenueQuote {
+            venue: venue_weight.venue,
+            amount_in: venue_amount_in,
+            amount_out,
+            supported: estimate.is_some(),
+        });
+    }
+    Ok(quote)
+}
+
+/// Sortie estimée d'une venue sur des comptes validés (None si la venue n'a pas
+/// d'estimateur). Cotée avant le CPI, elle sert de référence à son fill réel.
+pub(crate) fn venue_estimate(
+    venue: &Pubkey,
+    accounts: &[AccountInfo],
+    input_mint: &Pubkey,
+    amount_in: u64,
+    clock: &Clock,
+) -> Result<Option<u64>> {
+    pool_validation::validate_venue_accounts(venue, accounts)?;
+    quote_venue(venue, accounts, input_mint, amount_in, clock)
+}
+
+/// Plus petit input (côté venue) dont la sortie estimée atteint `target_out`, borné par
+/// `max_in`; None si la venue n'a pas d'estimateur. Sert à émuler l'exact-out.
+pub(crate) fn exact_out_input(
+    venue: &Pubkey,
+    accounts: &[AccountInfo],
+    input_mint: &Pubkey,
+    target_out: u64,
+    max_in: u64,
+    clock: &Clock,
+) -> Result<Option<u64>> {
+    pool_validation::validate_venue_accounts(venue, accounts)?;
+    if quote_venue(venue, accounts, input_mint, 0, clock)?.is_none() {
+        return Ok(None);
+    }
+    minimal_input(target_out, max_in, |amount_in| {
+        Ok(quote_venue(venue, accounts, input_mint, amount_in, clock)?.unwrap_or(0))
+    })
+    .map(Some)
+}
+
+/// Recherche dichotomique sur une sortie croissante en l'input
+fn minimal_input(target_out: u64, max_in: u64, estimate: impl Fn(u64) -> Result<u64>) -> Result<u64> {
+    if estimate(max_in)? < target_out {
+        msg!("Exact-out: {} not reachable within max_in {}", target_out, max_in);
+        return err!(ErrorCode::SlippageExceeded);
+    }
+    // Invariant: estimate(high) >= target_out, estimate(low) < target_out (ou low = 0)
+    let (mut low, mut high) = (0u64, max_in);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if estimate(mid)? >= target_out {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    Ok(if target_out == 0 { 0 } else { high })
+}
+
+/// Sortie estimée d'une venue (None si la venue n'a pas d'estimateur)
+fn quote_venue(
+    venue: &Pubkey,
+    accounts: &[AccountInfo],
+    input_mint: &Pubkey,
+    amount_in: u64,
+    clock: &Clock,
+) -> Result<Option<u64>> {
+    let amount_out = match *venue {
+        ORCA_WHIRLPOOL_PROGRAM_ID => quote_whirlpool(accounts, input_mint, amount_in)?,
+        RAYDIUM_AMM_PROGRAM_ID => quote_raydium_amm(accounts, input_mint, amount_in)?,
+        SABER_PROGRAM_ID => quote_saber(accounts, amount_in, clock)?,
+        PHOENIX_PROGRAM_ID => quote_phoenix(accounts, input_mint, amount_in)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(amount_out))
+}
+
+fn quote_whirlpool(accounts: &[AccountInfo], input_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+    let data = accounts[cpi_orca::WHIRLPOOL_INDEX].try_borrow_data()?;
+    let fee_rate = read_u16(&data, WHIRLPOOL_FEE_RATE_OFFSET)?;
+    let liquidity = read_u128(&data, WHIRLPOOL_LIQUIDITY_OFFSET)?;
+    let sqrt_price = read_u128(&data, WHIRLPOOL_SQRT_PRICE_OFFSET)?;
+    let a_to_b = read_pubkey(&data, WHIRLPOOL_TOKEN_MINT_A_OFFSET)? == *input_mint;
+    Ok(whirlpool_output(liquidity, sqrt_price, fee_rate, amount_in, a_to_b))
+}
+
+fn quote_raydium_amm(accounts: &[AccountInfo], input_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+    let data = accounts[cpi_raydium::AMM_POOL_INDEX].try_borrow_data()?;
+    let coin_vault = &accounts[cpi_raydium::AMM_COIN_VAULT_INDEX];
+    let coin = unpack_token_account(&coin_vault.try_borrow_data()?)?;
+    let coin_reserve = coin
+        .amount
+        .saturating_sub(read_u64(&data, RAYDIUM_NEED_TAKE_PNL_COIN_OFFSET)?);
+    let pc_reserve = read_token_amount(&accounts[cpi_raydium::AMM_PC_VAULT_INDEX])?
+        .saturating_sub(read_u64(&data, RAYDIUM_NEED_TAKE_PNL_PC_OFFSET)?);
+    let (reserve_in, reserve_out) = if coin.mint == *input_mint {
+        (coin_reserve, pc_reserve)
+    } else {
+        (pc_reserve, coin_reserve)
+    };
+    Ok(constant_product_output(
+        reserve_in,
+        reserve_out,
+        amount_in,
+        read_u64(&data, RAYDIUM_SWAP_FEE_NUMERATOR_OFFSET)?,
+        read_u64(&data, RAYDIUM_SWAP_FEE_DENOMINATOR_OFFSET)?,
+    ))
+}
+
+fn quote_saber(accounts: &[AccountInfo], amount_in: u64, clock: &Clock) -> Result<u64> {
+    let data = accounts[cpi_saber::SWAP_INFO_INDEX].try_borrow_data()?;
+    let amp = saber_current_amp(
+        read_u64(&data, SABER_INITIAL_AMP_OFFSET)?,
+        read_u64(&data, SABER_TARGET_AMP_OFFSET)?,
+        read_u64(&data, SABER_START_RAMP_TS_OFFSET)? as i64,
+        read_u64(&data, SABER_STOP_RAMP_TS_OFFSET)? as i64,
+        clock.unix_timestamp,
+    );
+    // validate_saber garantit source = réserve d'entrée, destination = réserve de sortie
+    let reserve_in = read_token_amount(&accounts[cpi_saber::SWAP_SOURCE_INDEX])?;
+    let reserve_out = read_token_amount(&accounts[cpi_saber::SWAP_DESTINATION_INDEX])?;
+    Ok(stable_swap_output(
+        amp,
+        reserve_in,
+        reserve_out,
+        amount_in,
+        read_u64(&data, SABER_TRADE_FEE_NUMERATOR_OFFSET)?,
+        read_u64(&data, SABER_TRADE_FEE_DENOMINATOR_OFFSET)?,
+    ))
+}
+
+fn quote_phoenix(accounts: &[AccountInfo], input_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+    let data = accounts[cpi_phoenix::MARKET_INDEX].try_borrow_data()?;
+    let header = PhoenixMarketHeader::parse(&data)?;
+    // Vendre la base frappe le meilleur bid, acheter la base le meilleur ask
+    let side = if header.base_mint == *input_mint { SIDE_ASK } else { SIDE_BID };
+    let Some((price_in_ticks, num_base_lots)) = phoenix_top_of_book(&data, side)? else {
+        return Ok(0);
+    };
+
+    let available_input = if side == SIDE_ASK {
+        num_base_lots.saturating_mul(header.base_lot_size)
+    } else {
+        header.required_input(SIDE_BID, price_in_ticks, num_base_lots)?
+    };
+    let gross = header.fill_proceeds(side, price_in_ticks, amount_in.min(available_input))?;
+    let taker_fee_bps = read_u64(&data, PHOENIX_TAKER_FEE_BPS_OFFSET)?.min(10_000);
+    Ok((gross as u128 * (10_000 - taker_fee_bps as u128) / 10_000) as u64)
+}
+
+/// Meilleur ordre (prix en ticks, taille en base lots) du côté frappé
+///
+/// Phoenix ordonne les deux arbres du meilleur au moins bon prix (comparaison de
+/// prix inversée pour les bids): le meilleur ordre est le nœud le plus à gauche.
+fn phoenix_top_of_book(data: &[u8], side: u8) -> Result<Option<(u64, u64)>> {
+    let bids_size = read_u64(data, PHOENIX_BIDS_SIZE_OFFSET)? as usize;
+    let tree_offset = if side == SIDE_ASK {
+        PHOENIX_BIDS_OFFSET
+    } else {
+        let bids_len = bids_size
+            .checked_mul(RB_NODE_LEN)
+            .and_then(|len| len.checked_add(RB_TREE_HEADER_LEN))
+            .ok_or_else(|| error!(ErrorCode::InvalidPoolAccount))?;
+        PHOENIX_BIDS_OFFSET + bids_len
+    };
+    let node_offset = |index: u32| tree_offset + RB_TREE_HEADER_LEN + (index as usize - 1) * RB_NODE_LEN;
+
+    let mut index = read_u32(data, tree_offset)?;
+    if index == 0 {
+        return Ok(None);
+    }
+    for _ in 0..RB_MAX_DEPTH {
+        let next = read_u32(data, node_offset(index) + RB_LEFT * 4)?;
+        if next == 0 {
+            let node = node_offset(index);
+            let price_in_ticks = read_u64(data, node + 16)?;
+            let num_base_lots = read_u64(data, node + 40)?;
+            return Ok(Some((price_in_ticks, num_base_lots)));
+        }
+        index = next;
+    }
+    err!(ErrorCode::InvalidPoolAccount)
+}
+
+/// Whirlpool dans la plage de liquidité courante (Q64.64), frais en centièmes de bps
+fn whirlpool_output(liquidity: u128, sqrt_price: u128, fee_rate: u16, amount_in: u64, a_to_b: bool) -> u64 {
+    let amount = mul_div_u128(
+        amount_in as u128,
+        WHIRLPOOL_FEE_RATE_DENOMINATOR.saturating_sub(fee_rate as u128),
+        WHIRLPOOL_FEE_RATE_DENOMINATOR,
+    )
+    .unwrap_or(0);
+    if liquidity == 0 || sqrt_price == 0 || amount == 0 {
+        return 0;
+    }
+
+    let output = if a_to_b {
+        // sqrt_p' = L * sqrt_p / (L + dx * sqrt_p);  dy = L * (sqrt_p - sqrt_p')
+        mul_div_u128(amount, sqrt_price, Q64)
+            .and_then(|dx_sqrt| liquidity.checked_add(dx_sqrt))
+            .and_then(|denominator| mul_div_u128(liquidity, sqrt_price, denominator))
+            .and_then(|next| mul_div_u128(liquidity, sqrt_price - next, Q64))
+    } else {
+        // sqrt_p' = sqrt_p + dy / L;  dx = L * (sqrt_p' - sqrt_p) / (sqrt_p * sqrt_p')
+        mul_div_u128(amount, Q64, liquidity)
+            .and_then(|delta| sqrt_price.checked_add(delta))
+            .and_then(|next| {
+                mul_div_u128(liquidity, next - sqrt_price, sqrt_price)
+                    .and_then(|scaled| mul_div_u128(scaled, Q64, next))
+            })
+    };
+    output.map_or(0, |out| out.min(u64::MAX as u128) as u64)
+}
+
+/// Produit constant avec frais prélevés sur l'input (arrondis au-dessus)
+fn constant_product_output(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> u64 {
+    if fee_denominator == 0 || fee_numerator >= fee_denominator {
+        return 0;
+    }
+    let fee = (amount_in as u128 * fee_numerator as u128).div_ceil(fee_denominator as u128);
+    let amount = amount_in as u128 - fee;
+    mul_div_u128(reserve_out as u128, amount, reserve_in as u128 + amount).map_or(0, |out| out as u64)
+}
+
+/// Amp Saber interpolé linéairement pendant une rampe
+fn saber_current_amp(initial: u64, target: u64, start_ts: i64, stop_ts: i64, now: i64) -> u64 {
+    if now >= stop_ts || stop_ts <= start_ts {
+        return target;
+    }
+    let elapsed = now.saturating_sub(start_ts).max(0) as u128;
+    let duration = (stop_ts - start_ts) as u128;
+    if target >= initial {
+        initial + ((target - initial) as u128 * elapsed / duration) as u64
+    } else {
+        initial - ((initial - target) as u128 * elapsed / duration) as u64
+    }
+}
+
+/// StableSwap à deux tokens (Newton sur D puis sur y), frais de trade sur la sortie
+fn stable_swap_output(
+    amp: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> u64 {
+    let Some(d) = stable_swap_d(amp, reserve_in as u128, reserve_out as u128) else {
+        return 0;
+    };
+    let Some(new_out) = stable_swap_y(amp, reserve_in as u128 + amount_in as u128, d) else {
+        return 0;
+    };
+    // -1: arrondi en faveur du pool, comme Saber
+    let dy = (reserve_out as u128).saturating_sub(new_out).saturating_sub(1);
+    let fee = if fee_denominator == 0 {
+        0
+    } else {
+        dy * fee_numerator as u128 / fee_denominator as u128
+    };
+    dy.saturating_sub(fee) as u64
+}
+
+fn stable_swap_d(amp: u64, x: u128, y: u128) -> Option<u128> {
+    let sum = x + y;
+    if sum == 0 {
+        return Some(0);
+    }
+    if x == 0 || y == 0 || amp == 0 {
+        return None;
+    }
+    let leverage = amp as u128 * 2;
+    let mut d = sum;
+    for _ in 0..SABER_MAX_ITERATIONS {
+        let d_p = mul_div_u128(mul_div_u128(d, d, x * 2)?, d, y * 2)?;
+        let previous = d;
+        let numerator = leverage.checked_mul(sum)?.checked_add(d_p.checked_mul(2)?)?;
+        let denominator = (leverage - 1).checked_mul(d)?.checked_add(d_p.checked_mul(3)?)?;
+        d = mul_div_u128(numerator, d, denominator)?;
+        if d.abs_diff(previous) <= 1 {
+            return Some(d);
+        }
+    }
+    Some(d)
+}
+
+fn stable_swap_y(amp: u64, x: u128, d: u128) -> Option<u128> {
+    let ann = amp as u128 * 2;
+    let c = mul_div_u128(mul_div_u128(d, d, x.checked_mul(2)?)?, d, ann.checked_mul(2)?)?;
+    let b = x.checked_add(d / ann)?;
+    let mut y = d;
+    for _ in 0..SABER_MAX_ITERATIONS {
+        let previous = y;
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = (y * 2).checked_add(b)?.checked_sub(d)?;
+        y = numerator / denominator;
+        if y.abs_diff(previous) <= 1 {
+            return Some(y);
+        }
+    }
+    Some(y)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    Ok(u16::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
+    Ok(u128::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    Ok(Pubkey::new_from_array(read_bytes(data, offset)?))
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .map(|bytes| bytes.try_into().unwrap())
+        .ok_or_else(|| error!(ErrorCode::InvalidPoolAccount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whirlpool_output_matches_spot_for_small_trades() {
+        // Prix 1.0 (sqrt = 2^64), ~10M de chaque côté, sans frais: ~1:1
+        let liquidity = 10_000_000_000_000u128;
+        let out = whirlpool_output(liquidity, Q64, 0, 1_000_000, true);
+        assert!((999_990..=1_000_000).contains(&out), "{out}");
+        let out = whirlpool_output(liquidity, Q64, 0, 1_000_000, false);
+        assert!((999_990..=1_000_000).contains(&out), "{out}");
+
+        // Prix 4.0 (sqrt = 2 * 2^64): a->b multiplie par ~4, b->a divise par ~4
+        let out = whirlpool_output(liquidity, 2 * Q64, 0, 1_000_000, true);
+        assert!((3_999_990..=4_000_000).contains(&out), "{out}");
+        let out = whirlpool_output(liquidity, 2 * Q64, 0, 1_000_000, false);
+        assert!((249_990..=250_000).contains(&out), "{out}");
+
+        // 0.3% de frais
+        let out = whirlpool_output(liquidity, Q64, 3_000, 1_000_000, true);
+        assert!((996_990..=997_000).contains(&out), "{out}");
+
+        // Liquidité faible: fort impact de prix
+        let shallow = whirlpool_output(1_000_000, Q64, 0, 1_000_000, true);
+        assert_eq!(shallow, 500_000);
+    }
+
+    #[test]
+    fn test_constant_product_output() {
+        // 1_000 in sur 1M/2M avec 25 bps (3 atoms, arrondi au-dessus): 2M * 997 / 1_000_997
+        assert_eq!(constant_product_output(1_000_000, 2_000_000, 1_000, 25, 10_000), 1_992);
+        assert_eq!(constant_product_output(1_000_000, 2_000_000, 1_000, 0, 0), 0);
+        assert_eq!(constant_product_output(0, 0, 1_000, 25, 10_000), 0);
+    }
+
+    #[test]
+    fn test_stable_swap_output() {
+        // Pool équilibré, amp élevé: quasi 1:1 moins 4 bps de frais
+        let out = stable_swap_output(100, 1_000_000_000_000, 1_000_000_000_000, 1_000_000, 4, 10_000);
+        assert!((999_500..=999_600).contains(&out), "{out}");
+        // Amp faible: plus d'impact qu'avec un amp élevé
+        let low_amp = stable_swap_output(1, 1_000_000, 1_000_000, 100_000, 0, 10_000);
+        let high_amp = stable_swap_output(1_000, 1_000_000, 1_000_000, 100_000, 0, 10_000);
+        assert!(low_amp < high_amp && high_amp < 100_000);
+        assert_eq!(stable_swap_output(100, 0, 1_000_000, 1_000, 0, 10_000), 0);
+    }
+
+    #[test]
+    fn test_minimal_input() {
+        let estimate = |amount_in| Ok(constant_product_output(1_000_000_000, 2_000_000_000, amount_in, 25, 10_000));
+        let amount_in = minimal_input(100_000_000, 1_000_000_000, estimate).unwrap();
+        assert!(estimate(amount_in).unwrap() >= 100_000_000);
+        assert!(estimate(amount_in - 1).unwrap() < 100_000_000);
+        // Hors de portée avec max_in: refusé plutôt que sur-dépensé
+        assert!(minimal_input(100_000_000, amount_in - 1, estimate).is_err());
+        assert_eq!(minimal_input(100_000_000, amount_in, estimate).unwrap(), amount_in);
+    }
+
+    #[test]
+    fn test_saber_current_amp_ramp() {
+        assert_eq!(saber_current_amp(100, 200, 0, 100, 50), 150);
+        assert_eq!(saber_current_amp(200, 100, 0, 100, 25), 175);
+        assert_eq!(saber_current_amp(100, 200, 0, 100, 150), 200);
+        assert_eq!(saber_current_amp(100, 200, 0, 0, 0), 200);
+    }
+
+    #[test]
+    fn test_plan_shortfall_without_estimator() {
+        let venue = Pubkey::new_unique();
+        let registry = VenueRegistry {
+            venues: vec![crate::VenueEntry {
+                program_id: venue,
+                enabled: true,
+                max_notional_per_swap: 0,
+                max_split_share_bps: 10_000,
+                account_count: 1,
+            }],
+            quarantined_pools: vec![],
+            bump: 255,
+        };
+        let (key, owner, mut lamports, mut data) = (Pubkey::new_unique(), venue, 0, vec![]);
+        let accounts = [AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0)];
+        let plan = |venue| vec![VenueWeight { venue, weight: 10_000 }];
+        let clock = Clock::default();
+
+        // Venue sans estimateur: viabilité inconnue, pas présumée
+        assert_eq!(
+            plan_shortfall(&registry, &accounts, &plan(venue), &key, 1_000, u64::MAX, &clock),
+            Some(Shortfall::Unestimated(venue))
+        );
+        // Venue non enregistrée: plan écarté avant tout CPI
+        assert_eq!(
+            plan_shortfall(&registry, &accounts, &plan(Pubkey::new_unique()), &key, 1_000, 1, &clock),
+            Some(Shortfall::BelowMinOut(String::from("Quote failed")))
+        );
+    }
+
+    /// Marché Phoenix minimal: arbres bids/asks de 4 nœuds
+    fn phoenix_market(bids: &[(u64, u64)], asks: &[(u64, u64)]) -> Vec<u8> {
+        let tree_len = RB_TREE_HEADER_LEN + 4 * RB_NODE_LEN;
+        let mut data = vec![0u8; PHOENIX_BIDS_OFFSET + 2 * tree_len];
+        data[PHOENIX_BIDS_SIZE_OFFSET..PHOENIX_BIDS_SIZE_OFFSET + 8].copy_from_slice(&4u64.to_le_bytes());
+        for (tree, orders) in [(PHOENIX_BIDS_OFFSET, bids), (PHOENIX_BIDS_OFFSET + tree_len, asks)] {
+            // Chaîne dégénérée: la racine (1) a pour fils gauche 2, puis 3... (meilleur en dernier)
+            if !orders.is_empty() {
+                data[tree..tree + 4].copy_from_slice(&1u32.to_le_bytes());
+            }
+            for (i, (price, lots)) in orders.iter().enumerate() {
+                let node = tree + RB_TREE_HEADER_LEN + i * RB_NODE_LEN;
+                if i + 1 < orders.len() {
+                    let next = (i as u32 + 2).to_le_bytes();
+                    data[node + RB_LEFT * 4..node + RB_LEFT * 4 + 4].copy_from_slice(&next);
+                }
+                data[node + 16..node + 24].copy_from_slice(&price.to_le_bytes());
+                data[node + 40..node + 48].copy_from_slice(&lots.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_phoenix_top_of_book() {
+        let data = phoenix_market(&[(100, 5), (101, 7)], &[(103, 2), (102, 9)]);
+        // Vendre la base (SIDE_ASK) frappe le meilleur bid, acheter le meilleur ask
+        assert_eq!(phoenix_top_of_book(&data, SIDE_ASK).unwrap(), Some((101, 7)));
+        assert_eq!(phoenix_top_of_book(&data, SIDE_BID).unwrap(), Some((102, 9)));
+
+        let empty = phoenix_market(&[], &[]);
+        assert_eq!(phoenix_top_of_book(&empty, SIDE_ASK).unwrap(), None);
+    }
+}
@@ -0,0 +1,382 @@
+//! Venue RFQ: devis signés par un market maker whitelisté
+//!
+//! Le maker signe `RFQ_MESSAGE_PREFIX || program_id || borsh(RfqQuote)` hors chaîne; la signature
+//! est vérifiée par le programme ed25519 dans une instruction précédente de la
+//! transaction, retrouvée ici par introspection du sysvar instructions.
+//! Le router règle ensuite les deux transferts atomiquement, à condition que le
+//! devis batte l'estimation AMM (`quote::quote_venues`) des venues de référence.
+//!
+//! Comptes (après ceux-ci, les comptes de pools des venues de référence):
+//! 0. Sysvar instructions
+//! 1. `RfqMakers` PDA
+//! 2. Nonce PDA `[b"rfq_nonce", maker, nonce_le]` (allouée et marquée utilisée ici, mut)
+//! 3. Compte du maker recevant l'input (mut)
+//! 4. Compte du maker livrant l'output, délégué à la PDA `rfq_authority` (mut)
+//! 5. PDA `[b"rfq_authority"]`
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint};
+
+use crate::balance_delta;
+use crate::quote::quote_venues;
+use crate::token_extensions::{swap_mints, user_token_account};
+use crate::{ErrorCode, RfqFilled, RfqMakers, RfqParams, RfqQuote, SwapToC, RFQ_VENUE_ID};
+
+/// Comptes RFQ en tête de remaining_accounts
+pub const RFQ_ACCOUNT_COUNT: usize = 6;
+
+pub(crate) const INSTRUCTIONS_SYSVAR_INDEX: usize = 0;
+pub(crate) const RFQ_MAKERS_INDEX: usize = 1;
+pub(crate) const NONCE_INDEX: usize = 2;
+pub(crate) const MAKER_INPUT_INDEX: usize = 3;
+pub(crate) const MAKER_OUTPUT_INDEX: usize = 4;
+pub(crate) const RFQ_AUTHORITY_INDEX: usize = 5;
+
+/// Domaine du message signé (évite la réutilisation d'une signature hors RFQ)
+pub const RFQ_MESSAGE_PREFIX: &[u8] = b"swapback-rfq-v2";
+
+pub const RFQ_MAKERS_SEED: &[u8] = b"rfq_makers";
+pub const RFQ_NONCE_SEED: &[u8] = b"rfq_nonce";
+pub const RFQ_AUTHORITY_SEED: &[u8] = b"rfq_authority";
+
+/// Instruction ed25519: [nb signatures u8, padding u8] puis 7 offsets u16 par signature
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// Donnée de la PDA nonce une fois le devis réglé
+const RFQ_NONCE_USED: u8 = 1;
+
+/// Message signé par le maker (lié au déploiement du router par son program id)
+pub fn quote_message(quote: &RfqQuote) -> Result<Vec<u8>> {
+    let mut message = RFQ_MESSAGE_PREFIX.to_vec();
+    message.extend_from_slice(crate::ID.as_ref());
+    quote.serialize(&mut message)?;
+    Ok(message)
+}
+
+/// Règle un devis RFQ; retourne l'output net crédité à l'utilisateur
+pub fn settle<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    params: &RfqParams,
+    account_slice: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_out: u64,
+) -> Result<u64> {
+    require!(account_slice.len() >= RFQ_ACCOUNT_COUNT, ErrorCode::DexExecutionFailed);
+    ctx.accounts
+        .venue_registry
+        .check_execution(&RFQ_VENUE_ID, amount_in)?;
+
+    let quote = &params.quote;
+    let clock = Clock::get()?;
+    let (input_mint, output_mint) = swap_mints(ctx)?;
+    require_keys_eq!(quote.taker, ctx.accounts.user.key(), ErrorCode::InvalidRfqQuote);
+    require_keys_eq!(quote.input_mint, input_mint, ErrorCode::InvalidRfqQuote);
+    require_keys_eq!(quote.output_mint, output_mint, ErrorCode::InvalidRfqQuote);
+    require!(
+        quote.amount_in == amount_in && quote.amount_out > 0,
+        ErrorCode::InvalidRfqQuote
+    );
+    require!(clock.unix_timestamp <= quote.expiry, ErrorCode::RfqQuoteExpired);
+
+    let rfq_makers_info = &account_slice[RFQ_MAKERS_INDEX];
+    require_keys_eq!(*rfq_makers_info.owner, crate::ID, ErrorCode::InvalidRfqQuote);
+    let rfq_makers = RfqMakers::try_deserialize(&mut &rfq_makers_info.try_borrow_data()?[..])?;
+    let expected_makers =
+        Pubkey::create_program_address(&[RFQ_MAKERS_SEED, &[rfq_makers.bump]], &crate::ID)
+            .map_err(|_| error!(ErrorCode::InvalidRfqQuote))?;
+    require_keys_eq!(rfq_makers_info.key(), expected_makers, ErrorCode::InvalidRfqQuote);
+    require!(rfq_makers.contains(&quote.maker), ErrorCode::RfqMakerNotAllowed);
+
+    verify_signature(&account_slice[INSTRUCTIONS_SYSVAR_INDEX], quote)?;
+
+    // Le devis doit battre la meilleure estimation AMM pour le même input
+    let benchmark_accounts = &ctx.remaining_accounts[account_slice.len()..];
+    let benchmark = quote_venues(
+        &ctx.accounts.venue_registry,
+        benchmark_accounts,
+        &params.benchmark_venues,
+        &input_mint,
+        amount_in,
+        &clock,
+    )?;
+    require!(
+        !benchmark.venues.is_empty() && benchmark.venues.iter().all(|venue| venue.supported),
+        ErrorCode::InvalidRfqQuote
+    );
+    require!(
+        quote.amount_out > benchmark.amount_out,
+        ErrorCode::RfqQuoteWorseThanAmm
+    );
+
+    consume_nonce(ctx, &account_slice[NONCE_INDEX], quote)?;
+
+    let maker_input = &account_slice[MAKER_INPUT_INDEX];
+    let maker_output = &account_slice[MAKER_OUTPUT_INDEX];
+    let maker_input_state = user_token_account(maker_input)?;
+    let maker_output_state = user_token_account(maker_output)?;
+    require_keys_eq!(maker_input_state.owner, quote.maker, ErrorCode::InvalidRfqQuote);
+    require_keys_eq!(maker_input_state.mint, input_mint, ErrorCode::InvalidRfqQuote);
+    require_keys_eq!(maker_output_state.owner, quote.maker, ErrorCode::InvalidRfqQuote);
+    require_keys_eq!(maker_output_state.mint, output_mint, ErrorCode::InvalidRfqQuote);
+
+    let rfq_authority = &account_slice[RFQ_AUTHORITY_INDEX];
+    let (expected_authority, authority_bump) =
+        Pubkey::find_program_address(&[RFQ_AUTHORITY_SEED], &crate::ID);
+    require_keys_eq!(rfq_authority.key(), expected_authority, ErrorCode::InvalidRfqQuote);
+
+    let input_mint_info = settlement_mint(ctx, ctx.accounts.input_mint.as_deref())?;
+    let output_mint_info = settlement_mint(ctx, ctx.accounts.output_mint.as_deref())?;
+
+    let source = ctx.accounts.user_token_account_a.to_account_info();
+    let destination = ctx.accounts.user_token_account_b.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let delta = balance_delta::with_balance_deltas(&source, &destination, amount_in, min_out, || {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.clone(),
+                token_interface::TransferChecked {
+                    from: source.clone(),
+                    mint: input_mint_info.0.clone(),
+                    to: maker_input.clone(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            quote.amount_in,
+            input_mint_info.1,
+        )?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token_interface::TransferChecked {
+                    from: maker_output.clone(),
+                    mint: output_mint_info.0.clone(),
+                    to: destination.clone(),
+                    authority: rfq_authority.clone(),
+                },
+                &[&[RFQ_AUTHORITY_SEED, &[authority_bump]]],
+            ),
+            quote.amount_out,
+            output_mint_info.1,
+        )
+    })?;
+
+    msg!(
+        "🤝 RFQ filled by {}: {} → {} (AMM benchmark {})",
+        quote.maker,
+        delta.spent,
+        delta.received,
+        benchmark.amount_out
+    );
+    emit!(RfqFilled {
+        user: ctx.accounts.user.key(),
+        maker: quote.maker,
+        nonce: quote.nonce,
+        amount_in: delta.spent,
+        amount_out: delta.received,
+        amm_benchmark_out: benchmark.amount_out,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(delta.received)
+}
+
+/// Mint (et décimales) requise par `transfer_checked`, sous le token program du swap
+fn settlement_mint<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    mint: Option<&InterfaceAccount<'info, Mint>>,
+) -> Result<(AccountInfo<'info>, u8)> {
+    let mint = mint.ok_or(ErrorCode::InvalidRfqQuote)?;
+    require_keys_eq!(
+        *mint.to_account_info().owner,
+        ctx.accounts.token_program.key(),
+        ErrorCode::InvalidRfqQuote
+    );
+    Ok((mint.to_account_info(), mint.decimals))
+}
+
+/// Cherche, avant l'instruction courante, une instruction ed25519 couvrant le devis
+fn verify_signature(instructions: &AccountInfo, quote: &RfqQuote) -> Result<()> {
+    require_keys_eq!(instructions.key(), instructions_sysvar::ID, ErrorCode::RfqSignatureMissing);
+    let message = quote_message(quote)?;
+    let current_index = load_current_index_checked(instructions)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions)?;
+        if instruction.program_id == ed25519_program::ID
+            && ed25519_instruction_matches(&instruction.data, &quote.maker, &message)
+        {
+            return Ok(());
+        }
+    }
+    err!(ErrorCode::RfqSignatureMissing)
+}
+
+/// Vrai si l'instruction ed25519 vérifie `message` signé par `signer`
+///
+/// Signature, clé et message doivent tous être portés par l'instruction ed25519
+/// elle-même (index d'instruction `u16::MAX`): sinon le programme ed25519 vérifierait
+/// des octets lus ailleurs que ceux comparés ici.
+pub fn ed25519_instruction_matches(data: &[u8], signer: &Pubkey, message: &[u8]) -> bool {
+    let Some(&signature_count) = data.first() else {
+        return false;
+    };
+    (0..signature_count as usize).any(|signature| {
+        let start = ED25519_HEADER_LEN + signature * ED25519_OFFSETS_LEN;
+        let Some(offsets) = data.get(start..start + ED25519_OFFSETS_LEN) else {
+            return false;
+        };
+        let field = |index: usize| u16::from_le_bytes([offsets[2 * index], offsets[2 * index + 1]]);
+        let (signature_ix, public_key_offset, public_key_ix) = (field(1), field(2) as usize, field(3));
+        let (message_offset, message_len, message_ix) = (field(4) as usize, field(5) as usize, field(6));
+        if [signature_ix, public_key_ix, message_ix].iter().any(|&ix| ix != u16::MAX) {
+            return false;
+        }
+        data.get(public_key_offset..public_key_offset + 32) == Some(signer.as_ref())
+            && data.get(message_offset..message_offset + message_len) == Some(message)
+    })
+}
+
+/// Consomme le nonce du devis: alloue sa PDA, l'assigne au router et la marque utilisée.
+/// Des lamports envoyés d'avance à l'adresse ne bloquent pas le devis.
+fn consume_nonce<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SwapToC<'info>>,
+    nonce_account: &AccountInfo<'info>,
+    quote: &RfqQuote,
+) -> Result<()> {
+    let nonce_bytes = quote.nonce.to_le_bytes();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[RFQ_NONCE_SEED, quote.maker.as_ref(), &nonce_bytes],
+        &crate::ID,
+    );
+    require_keys_eq!(nonce_account.key(), expected, ErrorCode::InvalidRfqQuote);
+    require!(
+        !is_nonce_used(nonce_account.owner, &nonce_account.try_borrow_data()?),
+        ErrorCode::RfqNonceUsed
+    );
+    require_keys_eq!(*nonce_account.owner, system_program::ID, ErrorCode::RfqNonceUsed);
+
+    let signer_seeds: &[&[u8]] = &[RFQ_NONCE_SEED, quote.maker.as_ref(), &nonce_bytes, &[bump]];
+    let missing_rent = Rent::get()?
+        .minimum_balance(1)
+        .saturating_sub(nonce_account.lamports());
+    if missing_rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: nonce_account.clone(),
+                },
+            ),
+            missing_rent,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Allocate {
+                account_to_allocate: nonce_account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        1,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Assign {
+                account_to_assign: nonce_account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )?;
+    nonce_account.try_borrow_mut_data()?[0] = RFQ_NONCE_USED;
+    Ok(())
+}
+
+/// Nonce déjà consommé: PDA détenue par le router et marquée utilisée
+fn is_nonce_used(owner: &Pubkey, data: &[u8]) -> bool {
+    *owner == crate::ID && data.first() == Some(&RFQ_NONCE_USED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Instruction ed25519 à une signature, tout en ligne (layout du programme natif)
+    fn ed25519_data(signer: &Pubkey, message: &[u8], instruction_index: u16) -> Vec<u8> {
+        let public_key_offset = ED25519_HEADER_LEN + ED25519_OFFSETS_LEN;
+        let signature_offset = public_key_offset + 32;
+        let message_offset = signature_offset + 64;
+        let mut data = vec![1u8, 0];
+        for field in [
+            signature_offset as u16,
+            instruction_index,
+            public_key_offset as u16,
+            instruction_index,
+            message_offset as u16,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    fn sample_quote() -> RfqQuote {
+        RfqQuote {
+            maker: Pubkey::new_unique(),
+            taker: Pubkey::new_unique(),
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            amount_in: 1_000_000,
+            amount_out: 2_000_000,
+            expiry: 1_700_000_000,
+            nonce: 42,
+        }
+    }
+
+    #[test]
+    fn test_ed25519_instruction_matches() {
+        let quote = sample_quote();
+        let message = quote_message(&quote).unwrap();
+        assert!(message.starts_with(RFQ_MESSAGE_PREFIX));
+        // Lié au program id: un devis signé pour un autre déploiement ne correspond pas
+        assert_eq!(
+            &message[RFQ_MESSAGE_PREFIX.len()..RFQ_MESSAGE_PREFIX.len() + 32],
+            crate::ID.as_ref()
+        );
+
+        let data = ed25519_data(&quote.maker, &message, u16::MAX);
+        assert!(ed25519_instruction_matches(&data, &quote.maker, &message));
+
+        // Autre signataire, autre devis
+        assert!(!ed25519_instruction_matches(&data, &Pubkey::new_unique(), &message));
+        let other = quote_message(&RfqQuote { amount_out: 2_000_001, ..quote.clone() }).unwrap();
+        assert!(!ed25519_instruction_matches(&data, &quote.maker, &other));
+
+        // Octets lus dans une autre instruction: refusé
+        let data = ed25519_data(&quote.maker, &message, 0);
+        assert!(!ed25519_instruction_matches(&data, &quote.maker, &message));
+
+        // Données tronquées
+        assert!(!ed25519_instruction_matches(&[], &quote.maker, &message));
+        assert!(!ed25519_instruction_matches(&[1, 0, 0], &quote.maker, &message));
+    }
+
+    #[test]
+    fn test_is_nonce_used() {
+        // Adresse pré-financée par un tiers: toujours détenue par le system program
+        assert!(!is_nonce_used(&system_program::ID, &[]));
+        assert!(!is_nonce_used(&crate::ID, &[0]));
+        assert!(is_nonce_used(&crate::ID, &[RFQ_NONCE_USED]));
+    }
+}
@@ -3,7 +3,12 @@ use anchor_lang::Discriminator;
 use std::collections::BTreeMap;
 
 use crate::venue_scoring::{VenueScore, VenueType};
-use crate::{JUPITER_PROGRAM_ID, ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_AMM_PROGRAM_ID};
+use crate::{
+    JUPITER_PROGRAM_ID, LIFINITY_PROGRAM_ID, METEORA_DAMM_PROGRAM_ID, METEORA_DAMM_V2_PROGRAM_ID,
+    METEORA_DLMM_PROGRAM_ID, OPENBOOK_V2_PROGRAM_ID, ORCA_WHIRLPOOL_PROGRAM_ID, PHOENIX_PROGRAM_ID,
+    RAYDIUM_AMM_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID, RFQ_VENUE_ID,
+    SABER_PROGRAM_ID, SANCTUM_PROGRAM_ID,
+};
 
 /// Minimum score en dessous duquel une venue est exclue du routing (0..=10_000).
 pub const MIN_QUALITY_SCORE_DEFAULT: u16 = 2500;
@@ -14,19 +19,54 @@ pub fn pubkey_to_venue_type(pubkey: &Pubkey) -> VenueType {
         VenueType::Jupiter
     } else if *pubkey == RAYDIUM_AMM_PROGRAM_ID {
         VenueType::Raydium
+    } else if *pubkey == RAYDIUM_CLMM_PROGRAM_ID {
+        VenueType::RaydiumClmm
+    } else if *pubkey == RAYDIUM_CPMM_PROGRAM_ID {
+        VenueType::RaydiumCpmm
     } else if *pubkey == ORCA_WHIRLPOOL_PROGRAM_ID {
         VenueType::Orca
+    } else if *pubkey == METEORA_DLMM_PROGRAM_ID {
+        VenueType::Meteora
+    } else if *pubkey == METEORA_DAMM_PROGRAM_ID || *pubkey == METEORA_DAMM_V2_PROGRAM_ID {
+        VenueType::MeteoraDamm
+    } else if *pubkey == PHOENIX_PROGRAM_ID {
+        VenueType::Phoenix
+    } else if *pubkey == OPENBOOK_V2_PROGRAM_ID {
+        VenueType::OpenBookV2
+    } else if *pubkey == LIFINITY_PROGRAM_ID {
+        VenueType::Lifinity
+    } else if *pubkey == SANCTUM_PROGRAM_ID {
+        VenueType::Sanctum
+    } else if *pubkey == SABER_PROGRAM_ID {
+        VenueType::Saber
+    } else if *pubkey == RFQ_VENUE_ID {
+        VenueType::Rfq
     } else {
         VenueType::Unknown
     }
 }
 
-/// Parse venue scores from remaining_accounts, indexed by Pubkey
+/// Venues carnet d'ordres (CLOB): coût CU par venue plus faible que les AMM
+pub fn is_clob_venue(pubkey: &Pubkey) -> bool {
+    matches!(
+        pubkey_to_venue_type(pubkey),
+        VenueType::Phoenix | VenueType::OpenBookV2
+    )
+}
+
+/// Parse venue scores from remaining_accounts, indexed by venue program Pubkey.
+/// Si plusieurs comptes (venue + pools) visent la même venue, le score le plus bas est retenu.
+/// Une venue en quarantaine a un score de 0.
 pub fn parse_venue_scores_by_pubkey<'info>(
     remaining: &[AccountInfo<'info>],
+    now: i64,
 ) -> BTreeMap<Pubkey, u16> {
     let mut map = BTreeMap::new();
     for ai in remaining {
+        // Seuls les comptes créés par ce programme font foi
+        if ai.owner != &crate::ID {
+            continue;
+        }
         let data = ai.try_borrow_data();
         if data.is_err() {
             continue;
@@ -41,7 +81,11 @@ pub fn parse_venue_scores_by_pubkey<'info>(
         }
         let mut slice: &[u8] = &data;
         if let Ok(vs) = VenueScore::try_deserialize(&mut slice) {
-            map.insert(vs.venue, vs.quality_score);
+            // Venue en quarantaine (circuit breaker): exclue du routing
+            let score = if vs.is_quarantined(now) { 0 } else { vs.quality_score };
+            map.entry(vs.venue)
+                .and_modify(|current: &mut u16| *current = (*current).min(score))
+                .or_insert(score);
         }
     }
     map
@@ -126,6 +170,9 @@ pub struct VenueAllocation {
 pub fn parse_venue_scores<'info>(remaining: &[AccountInfo<'info>]) -> BTreeMap<VenueType, u16> {
     let mut map = BTreeMap::new();
     for ai in remaining {
+        if ai.owner != &crate::ID {
+            continue;
+        }
         let data = ai.try_borrow_data();
         if data.is_err() {
             continue;
@@ -197,6 +244,14 @@ pub fn adjust_weights_with_scores(
 mod tests {
     use super::*;
 
+    #[test]
+    fn clob_venues_are_phoenix_and_openbook() {
+        assert!(is_clob_venue(&PHOENIX_PROGRAM_ID));
+        assert!(is_clob_venue(&OPENBOOK_V2_PROGRAM_ID));
+        assert!(!is_clob_venue(&RAYDIUM_CPMM_PROGRAM_ID));
+        assert!(!is_clob_venue(&Pubkey::new_unique()));
+    }
+
     // =========================================================================
     // RENORMALIZE WEIGHTS TESTS
     // =========================================================================
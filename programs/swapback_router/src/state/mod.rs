@@ -1,7 +1,15 @@
 pub mod dca_plan;
+pub mod phoenix_maker_order;
+pub mod rfq_makers;
 pub mod router_config;
 pub mod router_state;
+pub mod token_allowlist;
+pub mod venue_registry;
 
 pub use dca_plan::DcaPlan;
+pub use phoenix_maker_order::{PhoenixMakerOrder, MAX_PHOENIX_ORDER_TTL_SECS};
+pub use rfq_makers::RfqMakers;
 pub use router_config::RouterConfig;
 pub use router_state::{RouterState, UserRebate};
+pub use token_allowlist::TokenAllowlist;
+pub use venue_registry::{VenueEntry, VenueRegistry};
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+/// Durée de vie maximale d'un ordre maker Phoenix avant règlement permissionless
+pub const MAX_PHOENIX_ORDER_TTL_SECS: i64 = 7 * 86_400;
+
+/// Ordre post-only posé sur Phoenix via le siège maker de l'utilisateur
+///
+/// Seeds: [b"phoenix_order", user, market, nonce]
+/// Les fonds transitent par les escrows de l'utilisateur ([b"phoenix_maker_vault", market, user, mint]);
+/// le règlement annule le reliquat et reverse à l'utilisateur les soldes réels de ses escrows.
+#[account]
+pub struct PhoenixMakerOrder {
+    /// Utilisateur propriétaire de l'ordre
+    pub user: Pubkey,
+    /// Marché Phoenix
+    pub market: Pubkey,
+    /// Nonce choisi par l'utilisateur (plusieurs ordres par marché)
+    pub nonce: u64,
+    /// 0 = Bid (achète la base avec le quote), 1 = Ask (vend la base)
+    pub side: u8,
+    pub price_in_ticks: u64,
+    pub num_base_lots: u64,
+    /// Identifiant Phoenix de l'ordre (order_sequence_number du FIFOMarket, inversé pour un Bid)
+    pub order_sequence_number: u64,
+    /// Input déposé dans l'escrow (base pour un Ask, quote pour un Bid)
+    pub amount_in: u64,
+    pub created_at: i64,
+    /// Au-delà, n'importe qui peut régler l'ordre
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl PhoenixMakerOrder {
+    /// Layout:
+    /// - Discriminator: 8 bytes
+    /// - user: 32 bytes
+    /// - market: 32 bytes
+    /// - nonce: 8 bytes
+    /// - side: 1 byte
+    /// - price_in_ticks: 8 bytes
+    /// - num_base_lots: 8 bytes
+    /// - order_sequence_number: 8 bytes
+    /// - amount_in: 8 bytes
+    /// - created_at: 8 bytes
+    /// - expires_at: 8 bytes
+    /// - bump: 1 byte
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn is_bid(&self) -> bool {
+        self.side == 0
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
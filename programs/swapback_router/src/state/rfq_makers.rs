@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Maximum number of whitelisted RFQ market makers
+pub const MAX_RFQ_MAKERS: usize = 16;
+
+/// Market makers whose signed quotes the RFQ venue accepts
+///
+/// Seeds: [b"rfq_makers"]
+#[account]
+#[derive(InitSpace)]
+pub struct RfqMakers {
+    #[max_len(MAX_RFQ_MAKERS)]
+    pub makers: Vec<Pubkey>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RfqMakers {
+    pub const LEN: usize = 8  // discriminator
+        + 4 + MAX_RFQ_MAKERS * 32 // makers vec
+        + 1; // bump
+
+    pub fn contains(&self, maker: &Pubkey) -> bool {
+        self.makers.contains(maker)
+    }
+
+    /// Add or remove a maker (idempotent)
+    pub fn set(&mut self, maker: Pubkey, allowed: bool) -> Result<()> {
+        if !allowed {
+            self.makers.retain(|m| *m != maker);
+            return Ok(());
+        }
+        if self.contains(&maker) {
+            return Ok(());
+        }
+        require!(self.makers.len() < MAX_RFQ_MAKERS, ErrorCode::RfqMakerListFull);
+        self.makers.push(maker);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfq_makers_len() {
+        assert_eq!(RfqMakers::LEN, 8 + RfqMakers::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_set_is_idempotent_and_capped() {
+        let maker = Pubkey::new_unique();
+        let mut makers = RfqMakers { makers: vec![], bump: 255 };
+        makers.set(maker, true).unwrap();
+        makers.set(maker, true).unwrap();
+        assert_eq!(makers.makers.len(), 1);
+
+        makers.set(maker, false).unwrap();
+        assert!(!makers.contains(&maker));
+
+        for _ in 0..MAX_RFQ_MAKERS {
+            makers.set(Pubkey::new_unique(), true).unwrap();
+        }
+        assert!(makers.set(maker, true).is_err());
+    }
+}
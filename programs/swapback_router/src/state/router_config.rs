@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
-use crate::ErrorCode;
+use crate::{ErrorCode, DEFAULT_PYTH_MAX_SLOT_LAG, DEFAULT_PYTH_MIN_SIGNATURES};
 
 /// RouterConfig stores governance-controlled parameters that can be updated without redeploying the program
 #[account]
@@ -18,6 +19,12 @@ pub struct RouterConfig {
     /// Maximum venues allowed per swap (compute guard). None = use default (3)
     pub max_venues_per_swap: Option<u8>,
     pub bump: u8,
+    /// Pyth pull policy: refuse Partial updates entirely
+    pub pyth_require_full_verification: bool,
+    /// Pyth pull policy: min Wormhole signatures for Partial updates
+    pub pyth_min_signatures: u8,
+    /// Pyth pull policy: max slots between posted_slot and Clock::slot (0 = disabled)
+    pub pyth_max_slot_lag: u64,
 }
 
 impl RouterConfig {
@@ -27,7 +34,25 @@ impl RouterConfig {
         + 2 * 5               // five u16 parameters
         + 1 + 1               // feature flags
         + 1 + 1               // max_venues_per_swap option (1 byte flag + u8)
-        + 1; // bump
+        + 1                   // bump
+        + 1 + 1 + 8; // pyth update policy
+
+    /// Taille d'avant la politique Pyth, migrée par `migrate_router_config`
+    pub const LEGACY_LEN: usize = Self::LEN - (1 + 1 + 8);
+
+    /// Relit une config au layout legacy, avec la politique Pyth par défaut
+    pub fn from_legacy(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEGACY_LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let mut padded = data.to_vec();
+        padded.resize(Self::LEN, 0);
+        let mut config = Self::try_deserialize(&mut padded.as_slice()).ok()?;
+        config.pyth_require_full_verification = false;
+        config.pyth_min_signatures = DEFAULT_PYTH_MIN_SIGNATURES;
+        config.pyth_max_slot_lag = DEFAULT_PYTH_MAX_SLOT_LAG;
+        Some(config)
+    }
 
     /// Default max venues if not set
     pub const DEFAULT_MAX_VENUES: u8 = 3;
@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{DEFAULT_PYTH_MAX_SLOT_LAG, DEFAULT_PYTH_MIN_SIGNATURES};
 
 /// Router State Account - Global configuration and statistics
 #[account]
@@ -69,6 +72,15 @@ pub struct RouterState {
 
     /// PDA bump seed
     pub bump: u8,
+
+    /// Reject Pyth pull updates that are only partially verified (mirror of RouterConfig)
+    pub pyth_require_full_verification: bool,
+
+    /// Minimum Wormhole signatures accepted for a Partial Pyth update
+    pub pyth_min_signatures: u8,
+
+    /// Max slots between a Pyth update's posted_slot and Clock::slot (0 = disabled)
+    pub pyth_max_slot_lag: u64,
 }
 
 impl RouterState {
@@ -81,7 +93,25 @@ impl RouterState {
         + 32 + 32 + 32 + 32   // wallets
         + 8 + 8 + 8 + 8 + 8 + 8 + 8 // metrics
         + 1                   // dynamic_slippage_enabled
-        + 1; // bump
+        + 1                   // bump
+        + 1 + 1 + 8; // pyth update policy
+
+    /// Taille d'avant la politique Pyth, migrée par `migrate_router_state`
+    pub const LEGACY_LEN: usize = Self::LEN - (1 + 1 + 8);
+
+    /// Relit un état au layout legacy, avec la politique Pyth par défaut
+    pub fn from_legacy(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEGACY_LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let mut padded = data.to_vec();
+        padded.resize(Self::LEN, 0);
+        let mut state = Self::try_deserialize(&mut padded.as_slice()).ok()?;
+        state.pyth_require_full_verification = false;
+        state.pyth_min_signatures = DEFAULT_PYTH_MIN_SIGNATURES;
+        state.pyth_max_slot_lag = DEFAULT_PYTH_MAX_SLOT_LAG;
+        Some(state)
+    }
 }
 
 /// User Rebate Tracking Account
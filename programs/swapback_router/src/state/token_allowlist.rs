@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Maximum number of allowlisted Token-2022 mints
+pub const MAX_ALLOWLISTED_MINTS: usize = 32;
+
+/// Token-2022 mints the authority explicitly accepts despite risky extensions
+/// (permanent delegate, non-transferable)
+///
+/// Seeds: [b"token_allowlist"]
+#[account]
+#[derive(InitSpace)]
+pub struct TokenAllowlist {
+    #[max_len(MAX_ALLOWLISTED_MINTS)]
+    pub mints: Vec<Pubkey>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl TokenAllowlist {
+    pub const LEN: usize = 8  // discriminator
+        + 4 + MAX_ALLOWLISTED_MINTS * 32 // mints vec
+        + 1; // bump
+
+    pub fn contains(&self, mint: &Pubkey) -> bool {
+        self.mints.contains(mint)
+    }
+
+    /// Add or remove a mint (idempotent)
+    pub fn set(&mut self, mint: Pubkey, allowed: bool) -> Result<()> {
+        if !allowed {
+            self.mints.retain(|m| *m != mint);
+            return Ok(());
+        }
+        if self.contains(&mint) {
+            return Ok(());
+        }
+        require!(
+            self.mints.len() < MAX_ALLOWLISTED_MINTS,
+            ErrorCode::TokenAllowlistFull
+        );
+        self.mints.push(mint);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_allowlist_len() {
+        assert_eq!(TokenAllowlist::LEN, 8 + TokenAllowlist::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_set_is_idempotent_and_capped() {
+        let mint = Pubkey::new_unique();
+        let mut allowlist = TokenAllowlist { mints: vec![], bump: 255 };
+        allowlist.set(mint, true).unwrap();
+        allowlist.set(mint, true).unwrap();
+        assert_eq!(allowlist.mints.len(), 1);
+        assert!(allowlist.contains(&mint));
+
+        allowlist.set(mint, false).unwrap();
+        assert!(!allowlist.contains(&mint));
+
+        for _ in 0..MAX_ALLOWLISTED_MINTS {
+            allowlist.set(Pubkey::new_unique(), true).unwrap();
+        }
+        assert!(allowlist.set(mint, true).is_err());
+    }
+}
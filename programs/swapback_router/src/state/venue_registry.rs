@@ -0,0 +1,272 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Maximum number of venues the registry can hold
+pub const MAX_REGISTERED_VENUES: usize = 16;
+
+/// Maximum number of pools quarantined at the same time
+pub const MAX_QUARANTINED_POOLS: usize = 16;
+
+/// Per-venue execution flags managed by the authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct VenueEntry {
+    /// DEX program ID
+    pub program_id: Pubkey,
+    /// Kill switch: disabled venues are rejected before any CPI
+    pub enabled: bool,
+    /// Max input amount routed to this venue in one swap (0 = unlimited)
+    pub max_notional_per_swap: u64,
+    /// Max share of a split plan routed to this venue (basis points, 10000 = no limit)
+    pub max_split_share_bps: u16,
+    /// Number of CPI accounts consumed from remaining_accounts
+    pub account_count: u8,
+}
+
+/// Pool whose VenueScore tripped its circuit breaker: every swap path touching
+/// the pool is rejected while now < until (the rest of the venue stays routable)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PoolQuarantine {
+    pub venue: Pubkey,
+    pub pool: Pubkey,
+    pub until: i64,
+}
+
+/// Venue Registry - governed list of venues the router may CPI into
+#[account]
+#[derive(InitSpace)]
+pub struct VenueRegistry {
+    #[max_len(MAX_REGISTERED_VENUES)]
+    pub venues: Vec<VenueEntry>,
+
+    /// Pools quarantined by their VenueScore (expired slots are reused)
+    #[max_len(MAX_QUARANTINED_POOLS)]
+    pub quarantined_pools: Vec<PoolQuarantine>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl VenueRegistry {
+    pub const LEN: usize = 8  // discriminator
+        + 4 + MAX_REGISTERED_VENUES * (32 + 1 + 8 + 2 + 1) // venues vec
+        + 4 + MAX_QUARANTINED_POOLS * (32 + 32 + 8) // quarantined pools vec
+        + 1; // bump
+
+    pub fn entry(&self, program_id: &Pubkey) -> Option<&VenueEntry> {
+        self.venues.iter().find(|entry| entry.program_id == *program_id)
+    }
+
+    /// Registered and enabled venue, or the matching error
+    pub fn enabled_entry(&self, program_id: &Pubkey) -> Result<&VenueEntry> {
+        let entry = self
+            .entry(program_id)
+            .ok_or_else(|| error!(ErrorCode::VenueNotRegistered))?;
+        require!(entry.enabled, ErrorCode::VenueDisabled);
+        Ok(entry)
+    }
+
+    /// Checked before every CPI: venue enabled and amount within its notional cap
+    pub fn check_execution(&self, program_id: &Pubkey, amount_in: u64) -> Result<&VenueEntry> {
+        let entry = self.enabled_entry(program_id)?;
+        require!(
+            entry.max_notional_per_swap == 0 || amount_in <= entry.max_notional_per_swap,
+            ErrorCode::VenueNotionalExceeded
+        );
+        Ok(entry)
+    }
+
+    /// Checked for split plans: the venue's weight must stay within its share cap
+    pub fn check_split_share(&self, program_id: &Pubkey, weight_bps: u16) -> Result<()> {
+        let entry = self.enabled_entry(program_id)?;
+        require!(
+            weight_bps <= entry.max_split_share_bps,
+            ErrorCode::VenueShareExceeded
+        );
+        Ok(())
+    }
+
+    /// Insert or replace a venue entry
+    pub fn upsert(&mut self, new_entry: VenueEntry) -> Result<()> {
+        require!(
+            new_entry.max_split_share_bps <= 10_000,
+            ErrorCode::InvalidVenueConfig
+        );
+
+        if let Some(entry) = self
+            .venues
+            .iter_mut()
+            .find(|entry| entry.program_id == new_entry.program_id)
+        {
+            *entry = new_entry;
+            return Ok(());
+        }
+
+        require!(
+            self.venues.len() < MAX_REGISTERED_VENUES,
+            ErrorCode::VenueRegistryFull
+        );
+        self.venues.push(new_entry);
+        Ok(())
+    }
+
+    /// Whether `pool` of `venue` is quarantined at `now`
+    pub fn is_pool_quarantined(&self, venue: &Pubkey, pool: &Pubkey, now: i64) -> bool {
+        self.quarantined_pools
+            .iter()
+            .any(|quarantine| quarantine.venue == *venue && quarantine.pool == *pool && now < quarantine.until)
+    }
+
+    /// Checked before every CPI and quote: no account of the venue is a quarantined pool
+    pub fn check_pools(&self, venue: &Pubkey, accounts: &[AccountInfo], now: i64) -> Result<()> {
+        if let Some(account) = accounts
+            .iter()
+            .find(|account| self.is_pool_quarantined(venue, account.key, now))
+        {
+            msg!("🧯 Pool {} of venue {} is quarantined", account.key, venue);
+            return err!(ErrorCode::VenueQuarantined);
+        }
+        Ok(())
+    }
+
+    /// Reflect a pool score's quarantine (extends, never shortens an active one).
+    /// Full registry: the quarantine expiring first is replaced.
+    pub fn quarantine_pool(&mut self, venue: &Pubkey, pool: &Pubkey, until: i64, now: i64) {
+        if let Some(quarantine) = self
+            .quarantined_pools
+            .iter_mut()
+            .find(|quarantine| quarantine.venue == *venue && quarantine.pool == *pool)
+        {
+            quarantine.until = quarantine.until.max(until);
+            return;
+        }
+
+        self.quarantined_pools.retain(|quarantine| now < quarantine.until);
+        if self.quarantined_pools.len() >= MAX_QUARANTINED_POOLS {
+            if let Some(index) = (0..self.quarantined_pools.len())
+                .min_by_key(|index| self.quarantined_pools[*index].until)
+            {
+                self.quarantined_pools.swap_remove(index);
+            }
+        }
+        self.quarantined_pools.push(PoolQuarantine { venue: *venue, pool: *pool, until });
+    }
+
+    /// Lift a pool quarantine (authority breaker reset)
+    pub fn lift_pool_quarantine(&mut self, venue: &Pubkey, pool: &Pubkey) {
+        self.quarantined_pools
+            .retain(|quarantine| !(quarantine.venue == *venue && quarantine.pool == *pool));
+    }
+
+    /// Toggle a registered venue (incident response: one transaction)
+    pub fn set_enabled(&mut self, program_id: &Pubkey, enabled: bool) -> Result<()> {
+        let entry = self
+            .venues
+            .iter_mut()
+            .find(|entry| entry.program_id == *program_id)
+            .ok_or_else(|| error!(ErrorCode::VenueNotRegistered))?;
+        entry.enabled = enabled;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn venue(program_id: Pubkey) -> VenueEntry {
+        VenueEntry {
+            program_id,
+            enabled: true,
+            max_notional_per_swap: 1_000_000,
+            max_split_share_bps: 6_000,
+            account_count: 10,
+        }
+    }
+
+    #[test]
+    fn test_venue_registry_len() {
+        assert_eq!(VenueRegistry::LEN, 8 + VenueRegistry::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_check_execution() {
+        let program_id = Pubkey::new_unique();
+        let mut registry = VenueRegistry { venues: vec![], quarantined_pools: vec![], bump: 255 };
+        assert!(registry.check_execution(&program_id, 1).is_err());
+
+        registry.upsert(venue(program_id)).unwrap();
+        assert!(registry.check_execution(&program_id, 1_000_000).is_ok());
+        assert!(registry.check_execution(&program_id, 1_000_001).is_err());
+        assert!(registry.check_split_share(&program_id, 6_000).is_ok());
+        assert!(registry.check_split_share(&program_id, 6_001).is_err());
+
+        registry.set_enabled(&program_id, false).unwrap();
+        assert!(registry.check_execution(&program_id, 1).is_err());
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_caps() {
+        let program_id = Pubkey::new_unique();
+        let mut registry = VenueRegistry { venues: vec![], quarantined_pools: vec![], bump: 255 };
+        registry.upsert(venue(program_id)).unwrap();
+        registry
+            .upsert(VenueEntry { max_notional_per_swap: 0, ..venue(program_id) })
+            .unwrap();
+        assert_eq!(registry.venues.len(), 1);
+        assert!(registry.check_execution(&program_id, u64::MAX).is_ok());
+
+        assert!(registry
+            .upsert(VenueEntry { max_split_share_bps: 10_001, ..venue(program_id) })
+            .is_err());
+
+        for _ in 1..MAX_REGISTERED_VENUES {
+            registry.upsert(venue(Pubkey::new_unique())).unwrap();
+        }
+        assert!(registry.upsert(venue(Pubkey::new_unique())).is_err());
+    }
+
+    #[test]
+    fn test_pool_quarantine() {
+        let venue = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let other_pool = Pubkey::new_unique();
+        let mut registry = VenueRegistry { venues: vec![], quarantined_pools: vec![], bump: 255 };
+
+        registry.quarantine_pool(&venue, &pool, 1_000, 0);
+        assert!(registry.is_pool_quarantined(&venue, &pool, 999));
+        assert!(!registry.is_pool_quarantined(&venue, &pool, 1_000));
+        // Seul le pool est exclu: les autres pools de la venue restent routables
+        assert!(!registry.is_pool_quarantined(&venue, &other_pool, 999));
+        assert!(!registry.is_pool_quarantined(&Pubkey::new_unique(), &pool, 999));
+
+        // Une quarantaine plus courte ne raccourcit pas l'active
+        registry.quarantine_pool(&venue, &pool, 500, 0);
+        assert!(registry.is_pool_quarantined(&venue, &pool, 999));
+        assert_eq!(registry.quarantined_pools.len(), 1);
+
+        registry.lift_pool_quarantine(&venue, &pool);
+        assert!(!registry.is_pool_quarantined(&venue, &pool, 999));
+    }
+
+    #[test]
+    fn test_pool_quarantine_reuses_slots() {
+        let venue = Pubkey::new_unique();
+        let mut registry = VenueRegistry { venues: vec![], quarantined_pools: vec![], bump: 255 };
+        for until in 0..MAX_QUARANTINED_POOLS as i64 {
+            registry.quarantine_pool(&venue, &Pubkey::new_unique(), 1_000 + until, 0);
+        }
+
+        // Registre plein: la quarantaine qui expire en premier est remplacée
+        let first = registry.quarantined_pools[0].pool;
+        let pool = Pubkey::new_unique();
+        registry.quarantine_pool(&venue, &pool, 5_000, 0);
+        assert_eq!(registry.quarantined_pools.len(), MAX_QUARANTINED_POOLS);
+        assert!(!registry.is_pool_quarantined(&venue, &first, 0));
+        assert!(registry.is_pool_quarantined(&venue, &pool, 0));
+
+        // Les quarantaines expirées sont purgées
+        registry.quarantine_pool(&venue, &Pubkey::new_unique(), 9_000, 4_000);
+        assert_eq!(registry.quarantined_pools.len(), 2);
+    }
+}
@@ -0,0 +1,239 @@
+//! Support Token-2022 du chemin de swap
+//!
+//! - Extensions à risque (permanent delegate, non-transferable): mint refusée
+//!   sauf si l'autorité l'a inscrite dans la `TokenAllowlist`.
+//! - Transfer fee: l'input réellement reçu par la venue et l'output réellement
+//!   crédité à l'utilisateur sont nets des frais; l'attendu oracle et le min_out
+//!   transmis aux venues en tiennent compte.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::{Account as Token2022Account, Mint as Token2022Mint},
+};
+
+use crate::{ErrorCode, SwapToC};
+
+/// Extensions qui permettent à un tiers de saisir ou bloquer les fonds de l'utilisateur
+const DANGEROUS_EXTENSIONS: [ExtensionType; 2] =
+    [ExtensionType::PermanentDelegate, ExtensionType::NonTransferable];
+
+/// Vérifie les comptes utilisateur et les mints des deux côtés du swap
+///
+/// Les comptes A/B ne sont pas typés dans `SwapToC` (ils peuvent être créés dans
+/// l'instruction): programme token, autorité = user et mint sont vérifiés ici.
+/// Un compte Token-2022 exige sa mint (pour lire les extensions); une mint SPL
+/// classique peut être omise.
+pub fn check_swap_mints(ctx: &Context<SwapToC>) -> Result<()> {
+    let sides = [
+        (ctx.accounts.user_token_account_a.to_account_info(), ctx.accounts.input_mint.as_ref()),
+        (ctx.accounts.user_token_account_b.to_account_info(), ctx.accounts.output_mint.as_ref()),
+    ];
+    for (token_account, mint) in sides {
+        let state = user_token_account(&token_account)?;
+        require_keys_eq!(state.owner, ctx.accounts.user.key(), ErrorCode::InvalidTokenAccountOwner);
+        match mint {
+            Some(mint) => {
+                require_keys_eq!(mint.key(), state.mint, ErrorCode::InvalidTokenAccount);
+                let allowlisted = ctx
+                    .accounts
+                    .token_allowlist
+                    .as_ref()
+                    .is_some_and(|allowlist| allowlist.contains(&mint.key()));
+                check_mint(&mint.to_account_info(), allowlisted)?;
+            }
+            None if token_account.owner == &anchor_spl::token_2022::ID => {
+                msg!("Token-2022 account {} requires its mint", token_account.key);
+                return err!(ErrorCode::Token2022MintRequired);
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Mints (input, output) des comptes utilisateur du swap
+pub fn swap_mints(ctx: &Context<SwapToC>) -> Result<(Pubkey, Pubkey)> {
+    Ok((
+        user_token_account(&ctx.accounts.user_token_account_a)?.mint,
+        user_token_account(&ctx.accounts.user_token_account_b)?.mint,
+    ))
+}
+
+/// Décimales (input, output) lues sur les mints (vérifiées par `check_swap_mints`),
+/// jamais sur les arguments; None si une des deux mints n'est pas fournie
+pub fn swap_decimals(ctx: &Context<SwapToC>) -> Option<(u8, u8)> {
+    Some((
+        ctx.accounts.input_mint.as_ref()?.decimals,
+        ctx.accounts.output_mint.as_ref()?.decimals,
+    ))
+}
+
+/// Refuse une mint Token-2022 portant une extension à risque hors allowlist
+pub fn check_mint(mint: &AccountInfo, allowlisted: bool) -> Result<()> {
+    if mint.owner != &anchor_spl::token_2022::ID {
+        return Ok(());
+    }
+    let data = mint.try_borrow_data()?;
+    if let Some(extension) = dangerous_extension(&data)? {
+        if allowlisted {
+            msg!("⚠️ Mint {} has {:?} (allowlisted)", mint.key, extension);
+        } else {
+            msg!("Mint {} has {:?}, not allowlisted", mint.key, extension);
+            return err!(ErrorCode::DangerousMintExtension);
+        }
+    }
+    Ok(())
+}
+
+/// Frais de transfert prélevés sur un transfert brut de `amount` (0 hors Token-2022)
+pub fn transfer_fee(mint: Option<&AccountInfo>, epoch: u64, amount: u64) -> Result<u64> {
+    match mint {
+        Some(mint) if mint.owner == &anchor_spl::token_2022::ID => {
+            transfer_fee_from_data(&mint.try_borrow_data()?, epoch, amount)
+        }
+        _ => Ok(0),
+    }
+}
+
+/// Montant net reçu pour un transfert brut de `amount`
+pub fn amount_after_fee(mint: Option<&AccountInfo>, epoch: u64, amount: u64) -> Result<u64> {
+    Ok(amount.saturating_sub(transfer_fee(mint, epoch, amount)?))
+}
+
+/// Montant brut à transférer pour que le destinataire reçoive `net_amount`
+pub fn amount_before_fee(mint: Option<&AccountInfo>, epoch: u64, net_amount: u64) -> Result<u64> {
+    let fee = match mint {
+        Some(mint) if mint.owner == &anchor_spl::token_2022::ID => {
+            inverse_fee_from_data(&mint.try_borrow_data()?, epoch, net_amount)?
+        }
+        _ => 0,
+    };
+    net_amount
+        .checked_add(fee)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/// Compte token détenu par le programme SPL Token ou Token-2022
+pub fn user_token_account(info: &AccountInfo) -> Result<Token2022Account> {
+    require!(
+        info.owner == &anchor_spl::token::ID || info.owner == &anchor_spl::token_2022::ID,
+        ErrorCode::InvalidTokenAccount
+    );
+    unpack_token_account(&info.try_borrow_data()?)
+}
+
+/// Compte SPL Token ou Token-2022 (extensions comprises)
+pub fn unpack_token_account(data: &[u8]) -> Result<Token2022Account> {
+    StateWithExtensions::<Token2022Account>::unpack(data)
+        .map(|state| state.base)
+        .map_err(|_| error!(ErrorCode::InvalidTokenAccount))
+}
+
+fn dangerous_extension(data: &[u8]) -> Result<Option<ExtensionType>> {
+    let state = unpack_mint(data)?;
+    let extensions = state
+        .get_extension_types()
+        .map_err(|_| error!(ErrorCode::InvalidTokenAccount))?;
+    Ok(DANGEROUS_EXTENSIONS
+        .into_iter()
+        .find(|extension| extensions.contains(extension)))
+}
+
+fn transfer_fee_from_data(data: &[u8], epoch: u64, amount: u64) -> Result<u64> {
+    let state = unpack_mint(data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+fn inverse_fee_from_data(data: &[u8], epoch: u64, net_amount: u64) -> Result<u64> {
+    let state = unpack_mint(data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_inverse_epoch_fee(epoch, net_amount)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+fn unpack_mint(data: &[u8]) -> Result<StateWithExtensions<'_, Token2022Mint>> {
+    StateWithExtensions::<Token2022Mint>::unpack(data)
+        .map_err(|_| error!(ErrorCode::InvalidTokenAccount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFee,
+        BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+
+    fn mint_with(extensions: &[ExtensionType], init: impl FnOnce(&mut StateWithExtensionsMut<Token2022Mint>)) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Token2022Mint>(extensions).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+        init(&mut state);
+        state.base = Token2022Mint { decimals: 6, is_initialized: true, ..Default::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    /// 1% de frais, plafonnés à 5_000 atoms, à partir de l'epoch 10 (0.5% avant)
+    fn fee_mint() -> Vec<u8> {
+        mint_with(&[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.older_transfer_fee = TransferFee {
+                epoch: 0.into(),
+                maximum_fee: 5_000.into(),
+                transfer_fee_basis_points: 50.into(),
+            };
+            config.newer_transfer_fee = TransferFee {
+                epoch: 10.into(),
+                maximum_fee: 5_000.into(),
+                transfer_fee_basis_points: 100.into(),
+            };
+        })
+    }
+
+    #[test]
+    fn test_transfer_fee_by_epoch() {
+        let data = fee_mint();
+        assert_eq!(transfer_fee_from_data(&data, 9, 100_000).unwrap(), 500);
+        assert_eq!(transfer_fee_from_data(&data, 10, 100_000).unwrap(), 1_000);
+        // Plafond
+        assert_eq!(transfer_fee_from_data(&data, 10, 10_000_000).unwrap(), 5_000);
+        // Inverse: brut - frais(brut) = net
+        let fee = inverse_fee_from_data(&data, 10, 99_000).unwrap();
+        assert_eq!(fee, 1_000);
+        assert_eq!(99_000 + fee - transfer_fee_from_data(&data, 10, 99_000 + fee).unwrap(), 99_000);
+
+        let plain = mint_with(&[], |_| {});
+        assert_eq!(transfer_fee_from_data(&plain, 10, 100_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_dangerous_extensions() {
+        assert_eq!(dangerous_extension(&fee_mint()).unwrap(), None);
+
+        let delegated = mint_with(&[ExtensionType::PermanentDelegate], |state| {
+            state.init_extension::<PermanentDelegate>(true).unwrap().delegate =
+                Some(Pubkey::new_unique()).try_into().unwrap();
+        });
+        assert_eq!(dangerous_extension(&delegated).unwrap(), Some(ExtensionType::PermanentDelegate));
+
+        let (key, owner, mut lamports, mut data) =
+            (Pubkey::new_unique(), anchor_spl::token_2022::ID, 0, delegated);
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert_eq!(check_mint(&mint, false).unwrap_err(), error!(ErrorCode::DangerousMintExtension));
+        assert!(check_mint(&mint, true).is_ok());
+    }
+}
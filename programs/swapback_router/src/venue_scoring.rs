@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
 /// Enumeration of all supported DEX venues for native routing
 /// Each venue can generate NPI (Native Price Improvement) rebates
@@ -27,19 +28,42 @@ pub enum VenueType {
     
     // Unknown/Other
     Unknown,
+
+    // Venues ajoutées après déploiement: toujours en fin d'enum (index Borsh figés)
+    RaydiumCpmm,  // Constant product, Token-2022 aware
+    OpenBookV2,   // On-chain orderbook (CLOB)
+    MeteoraDamm,  // Dynamic AMM (vaults de prêt) + DAMM v2, stable ou constant product
+    Rfq,          // Devis signés par market makers (réglés par le router)
 }
 
+/// Durée d'une fenêtre de scoring: à chaque fenêtre écoulée, les compteurs sont divisés par 2
+pub const SCORE_WINDOW_SECS: i64 = 86_400;
+
+/// Poids d'un nouvel échantillon dans les moyennes exponentielles (bps)
+pub const SCORE_EMA_ALPHA_BPS: u64 = 2_000;
+
+/// Amélioration de prix vs oracle donnant le score maximal (±, en bps)
+pub const PRICE_IMPROVEMENT_RANGE_BPS: i64 = 100;
+
+/// Nombre d'exécutions (après décroissance) pour que le score soit pleinement crédité
+pub const SCORE_CONFIDENCE_SWAPS: u64 = 10;
+
+/// Venue Score - one account per venue program, optionally per pool
+/// Seeds: [b"venue_score", venue, pool] (pool = Pubkey::default() for venue-wide)
 #[account]
 pub struct VenueScore {
     pub venue: Pubkey,
+    /// Pool suivi (Pubkey::default() = toute la venue)
+    pub pool: Pubkey,
     pub venue_type: VenueType,
 
-    // Métriques de performance (rolling window)
+    // Compteurs (décroissance exponentielle par fenêtre)
     pub total_swaps: u64,
+    pub total_failures: u64,
     pub total_volume: u64,
-    pub total_npi_generated: i64,
-    pub avg_latency_ms: u32,
-    pub avg_slippage_bps: u16,
+
+    /// Moyenne exponentielle de l'écart d'exécution vs le mid oracle (bps, signé)
+    pub avg_price_improvement_bps: i32,
 
     // Score composite (0-10000)
     pub quality_score: u16,
@@ -47,84 +71,388 @@ pub struct VenueScore {
     // Timestamps
     pub last_updated: i64,
     pub window_start: i64,
+
+    // Circuit breaker (par venue)
+    pub failure_strikes: u16,      // Échecs/dépassements dans la fenêtre courante
+    pub strike_window_start: i64,  // Début de la fenêtre de comptage
+    pub quarantined_until: i64,    // Venue exclue tant que now < quarantined_until
+    pub max_failures: u8,          // Seuil de déclenchement (0 = désactivé)
+    pub failure_window_secs: i64,  // Durée de la fenêtre de comptage
+    pub quarantine_secs: i64,      // Durée de la quarantaine (cool-down)
+    pub max_adverse_bps: u16,      // Exécution pire que l'oracle au-delà => strike (0 = désactivé)
+
+    /// PDA bump seed
+    pub bump: u8,
 }
 
 impl VenueScore {
     pub const LEN: usize = 8 + // discriminator
         32 + // venue
+        32 + // pool
         1 + // venue_type (enum)
         8 + // total_swaps
+        8 + // total_failures
         8 + // total_volume
-        8 + // total_npi_generated
-        4 + // avg_latency_ms
-        2 + // avg_slippage_bps
+        4 + // avg_price_improvement_bps
         2 + // quality_score
         8 + // last_updated
-        8; // window_start
+        8 + // window_start
+        2 + // failure_strikes
+        8 + // strike_window_start
+        8 + // quarantined_until
+        1 + // max_failures
+        8 + // failure_window_secs
+        8 + // quarantine_secs
+        2 + // max_adverse_bps
+        1; // bump
+
+    /// Part des tentatives exécutées avec succès (bps, 10000 sans historique)
+    pub fn fill_ratio_bps(&self) -> u16 {
+        let attempts = self.total_swaps.saturating_add(self.total_failures);
+        if attempts == 0 {
+            return 10_000;
+        }
+        ((self.total_swaps as u128 * 10_000) / attempts as u128) as u16
+    }
+}
+
+/// Écart toléré entre la cotation d'une venue et son fill (approximation des estimateurs)
+pub const FILL_BELOW_QUOTE_TOLERANCE_BPS: u64 = 100;
+
+/// Fill sous la cotation de la venue elle-même (même état de pool, avant son CPI):
+/// échec imputable à la venue, contrairement à un min_out choisi par l'utilisateur
+pub fn fill_below_quote(received: u64, quoted_out: u64) -> bool {
+    (received as u128) * 10_000
+        < quoted_out as u128 * (10_000 - FILL_BELOW_QUOTE_TOLERANCE_BPS) as u128
+}
+
+/// Amélioration de prix signée d'une exécution vs la sortie implicite du mid oracle (bps)
+pub fn price_improvement_bps(amount_out: u64, oracle_expected_out: u64) -> i32 {
+    if oracle_expected_out == 0 {
+        return 0;
+    }
+    let diff = amount_out as i128 - oracle_expected_out as i128;
+    (diff * 10_000 / oracle_expected_out as i128).clamp(-10_000, 10_000) as i32
 }
 
 pub fn calculate_venue_score(venue: &VenueScore) -> u16 {
-    // Weighted scoring
-
-    // 1. NPI Score (40% weight)
-    // Normalized NPI per volume unit
-    let npi_score = if venue.total_volume > 0 && venue.total_npi_generated > 0 {
-        let npi_ratio = venue.total_npi_generated as f64 / venue.total_volume as f64;
-        // Target: 10 bps NPI (0.001) => 4000 points
-        (npi_ratio * 1000.0 * 4000.0).min(4000.0) as u16
-    } else {
-        0
-    };
-
-    // 2. Latency Score (30% weight)
-    // Target: < 100ms => 3000 points
-    let latency_score = if venue.avg_latency_ms > 0 {
-        ((100.0 / venue.avg_latency_ms as f64) * 3000.0).min(3000.0) as u16
-    } else {
-        // If 0 (unknown/instant), assume perfect or handle as uninitialized?
-        // Let's assume 0 means uninitialized, so maybe 0 score?
-        // But for now let's follow the logic: if 0, division by zero.
-        // If 0, let's say it's not scored yet.
-        0
-    };
-
-    // 3. Slippage Score (30% weight)
-    // Target: 0 bps slippage => 3000 points
-    // Penalty: -3 points per bps
-    let slippage_score =
-        ((10000u16.saturating_sub(venue.avg_slippage_bps)) as f64 * 0.3).min(3000.0) as u16;
-
-    (npi_score + latency_score + slippage_score).min(10000)
+    // 1. Price improvement vs oracle (50%): -100 bps => 0, 0 => 2500, +100 bps => 5000
+    let improvement = (venue.avg_price_improvement_bps as i64)
+        .clamp(-PRICE_IMPROVEMENT_RANGE_BPS, PRICE_IMPROVEMENT_RANGE_BPS)
+        + PRICE_IMPROVEMENT_RANGE_BPS;
+    let improvement_score = (improvement * 5_000 / (2 * PRICE_IMPROVEMENT_RANGE_BPS)) as u64;
+
+    // 2. Fill ratio (30%)
+    let fill_score = venue.fill_ratio_bps() as u64 * 3_000 / 10_000;
+
+    // 3. Confidence (20%): quelques exécutions récentes avant de créditer pleinement
+    let confidence_score =
+        venue.total_swaps.min(SCORE_CONFIDENCE_SWAPS) * 2_000 / SCORE_CONFIDENCE_SWAPS;
+
+    (improvement_score + fill_score + confidence_score).min(10_000) as u16
 }
 
 impl VenueScore {
-    pub fn update_stats(
-        &mut self,
-        volume: u64,
-        npi: i64,
-        latency_ms: u32,
-        slippage_bps: u16,
-        clock: &Clock,
-    ) {
-        // Update totals
+    /// Enregistre une exécution réussie.
+    /// Retourne true si l'exécution (trop adverse vs oracle) déclenche la quarantaine.
+    pub fn update_stats(&mut self, volume: u64, price_improvement_bps: i32, clock: &Clock) -> bool {
+        self.roll_window(clock.unix_timestamp);
+
+        // Le premier échantillon (ou après décroissance totale) initialise la moyenne
+        self.avg_price_improvement_bps = if self.total_swaps > 0 {
+            ema_signed(self.avg_price_improvement_bps as i64, price_improvement_bps as i64) as i32
+        } else {
+            price_improvement_bps
+        };
+
         self.total_swaps = self.total_swaps.saturating_add(1);
         self.total_volume = self.total_volume.saturating_add(volume);
-        self.total_npi_generated = self.total_npi_generated.saturating_add(npi);
-
-        // Update averages (Simple Moving Average approximation)
-        if self.total_swaps > 1 {
-            let old_count = self.total_swaps - 1;
-            self.avg_latency_ms = ((self.avg_latency_ms as u64 * old_count + latency_ms as u64)
-                / self.total_swaps) as u32;
-            self.avg_slippage_bps = ((self.avg_slippage_bps as u64 * old_count
-                + slippage_bps as u64)
-                / self.total_swaps) as u16;
-        } else {
-            self.avg_latency_ms = latency_ms;
-            self.avg_slippage_bps = slippage_bps;
-        }
 
         self.last_updated = clock.unix_timestamp;
         self.quality_score = calculate_venue_score(self);
+
+        let adverse = self.max_adverse_bps > 0
+            && price_improvement_bps < -(self.max_adverse_bps as i32);
+        adverse && self.record_strike(clock.unix_timestamp)
+    }
+
+    /// Enregistre un échec imputable à la venue (fill sous sa propre cotation).
+    /// Retourne true si l'échec déclenche la quarantaine.
+    pub fn record_failure(&mut self, clock: &Clock) -> bool {
+        self.roll_window(clock.unix_timestamp);
+        self.total_failures = self.total_failures.saturating_add(1);
+        self.last_updated = clock.unix_timestamp;
+        self.quality_score = calculate_venue_score(self);
+        self.record_strike(clock.unix_timestamp)
+    }
+
+    /// Whether the venue is currently excluded by its circuit breaker
+    pub fn is_quarantined(&self, current_time: i64) -> bool {
+        current_time < self.quarantined_until
+    }
+
+    /// Compte un strike dans la fenêtre courante et met la venue en quarantaine au seuil
+    fn record_strike(&mut self, current_time: i64) -> bool {
+        if self.max_failures == 0 {
+            return false;
+        }
+
+        let window_expired =
+            current_time.saturating_sub(self.strike_window_start) > self.failure_window_secs;
+        if self.failure_strikes == 0 || window_expired {
+            self.failure_strikes = 0;
+            self.strike_window_start = current_time;
+        }
+
+        self.failure_strikes = self.failure_strikes.saturating_add(1);
+        if self.failure_strikes < self.max_failures as u16 {
+            return false;
+        }
+
+        self.quarantined_until = current_time.saturating_add(self.quarantine_secs);
+        self.failure_strikes = 0;
+        true
+    }
+
+    /// Lève la quarantaine et remet les strikes à zéro (authority override)
+    pub fn reset_breaker(&mut self) {
+        self.quarantined_until = 0;
+        self.failure_strikes = 0;
+        self.strike_window_start = 0;
+    }
+
+    /// Fait avancer la fenêtre: chaque fenêtre écoulée divise les compteurs par 2
+    pub fn roll_window(&mut self, now: i64) {
+        if self.window_start == 0 || now < self.window_start {
+            self.window_start = now;
+            return;
+        }
+
+        let elapsed_windows = (now - self.window_start) / SCORE_WINDOW_SECS;
+        if elapsed_windows == 0 {
+            return;
+        }
+
+        let shift = elapsed_windows.min(63) as u32;
+        self.total_swaps >>= shift;
+        self.total_failures >>= shift;
+        self.total_volume >>= shift;
+        self.window_start += elapsed_windows * SCORE_WINDOW_SECS;
+    }
+}
+
+/// Adresse PDA d'un score: `[b"venue_score", venue, pool]` (pool par défaut = score de la venue)
+pub fn score_address(score: &VenueScore) -> Option<Pubkey> {
+    Pubkey::create_program_address(
+        &[b"venue_score", score.venue.as_ref(), score.pool.as_ref(), &[score.bump]],
+        &crate::ID,
+    )
+    .ok()
+}
+
+/// Le score suit une exécution de `venue` sur ces comptes (sa pool, ou toute la venue)
+pub fn tracks_execution(score: &VenueScore, venue: &Pubkey, accounts: &[AccountInfo]) -> bool {
+    score.venue == *venue
+        && (score.pool == Pubkey::default() || accounts.iter().any(|a| *a.key == score.pool))
+}
+
+/// VenueScore de `venue` parmi des comptes: même PDA que celui de l'exécution, writable
+pub fn find_venue_score<'a, 'info>(
+    candidates: &'a [AccountInfo<'info>],
+    venue: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Option<(&'a AccountInfo<'info>, VenueScore)> {
+    candidates.iter().find_map(|ai| {
+        if ai.owner != &crate::ID || !ai.is_writable {
+            return None;
+        }
+        let data = ai.try_borrow_data().ok()?;
+        if data.len() < 8 || data[..8] != VenueScore::DISCRIMINATOR {
+            return None;
+        }
+        let score = VenueScore::try_deserialize(&mut &data[..]).ok()?;
+        (tracks_execution(&score, venue, accounts) && score_address(&score) == Some(*ai.key))
+            .then_some((ai, score))
+    })
+}
+
+/// Moyenne exponentielle entière signée: avg + (sample - avg) * alpha
+fn ema_signed(avg: i64, sample: i64) -> i64 {
+    let alpha = SCORE_EMA_ALPHA_BPS as i128;
+    ((avg as i128 * (10_000 - alpha) + sample as i128 * alpha) / 10_000) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score() -> VenueScore {
+        VenueScore {
+            venue: Pubkey::new_unique(),
+            pool: Pubkey::default(),
+            venue_type: VenueType::Orca,
+            total_swaps: 0,
+            total_failures: 0,
+            total_volume: 0,
+            avg_price_improvement_bps: 0,
+            quality_score: 0,
+            last_updated: 0,
+            window_start: 1_000,
+            failure_strikes: 0,
+            strike_window_start: 0,
+            quarantined_until: 0,
+            max_failures: 3,
+            failure_window_secs: 600,
+            quarantine_secs: 3_600,
+            max_adverse_bps: 200,
+            bump: 255,
+        }
+    }
+
+    fn clock_at(unix_timestamp: i64) -> Clock {
+        Clock {
+            unix_timestamp,
+            ..Clock::default()
+        }
+    }
+
+    #[test]
+    fn test_find_venue_score_by_pda() {
+        let pool = Pubkey::new_unique();
+        let mut vs = score();
+        vs.pool = pool;
+        let (address, bump) = Pubkey::find_program_address(
+            &[b"venue_score", vs.venue.as_ref(), pool.as_ref()],
+            &crate::ID,
+        );
+        vs.bump = bump;
+        assert_eq!(score_address(&vs), Some(address));
+
+        let mut data = Vec::new();
+        vs.try_serialize(&mut data).unwrap();
+        let mut other_data = data.clone();
+        let (owner, mut lamports, mut other_lamports, mut pool_lamports) = (crate::ID, 0, 0, 0);
+        let other = Pubkey::new_unique();
+        let score_info = AccountInfo::new(&address, false, true, &mut lamports, &mut data, &owner, false, 0);
+        // Même contenu à une autre adresse: pas le PDA du score
+        let forged = AccountInfo::new(&other, false, true, &mut other_lamports, &mut other_data, &owner, false, 0);
+        let mut pool_data = vec![];
+        let system = Pubkey::default();
+        let pool_info = AccountInfo::new(&pool, false, true, &mut pool_lamports, &mut pool_data, &system, false, 0);
+
+        let candidates = [forged.clone(), score_info.clone()];
+        let (found, found_score) =
+            find_venue_score(&candidates, &vs.venue, std::slice::from_ref(&pool_info)).unwrap();
+        assert_eq!(*found.key, address);
+        assert_eq!(found_score.pool, pool);
+
+        // Autre venue, ou pool absente des comptes exécutés: aucun score
+        assert!(find_venue_score(&candidates, &Pubkey::new_unique(), std::slice::from_ref(&pool_info)).is_none());
+        assert!(find_venue_score(&candidates, &vs.venue, &[]).is_none());
+        assert!(find_venue_score(&[forged], &vs.venue, std::slice::from_ref(&pool_info)).is_none());
+    }
+
+    #[test]
+    fn test_price_improvement_bps() {
+        assert_eq!(price_improvement_bps(1_010_000, 1_000_000), 100);
+        assert_eq!(price_improvement_bps(995_000, 1_000_000), -50);
+        assert_eq!(price_improvement_bps(0, 1_000_000), -10_000);
+        assert_eq!(price_improvement_bps(1_000, 0), 0);
+    }
+
+    #[test]
+    fn test_fill_below_quote() {
+        // 1% de tolérance sous la cotation de la venue
+        assert!(!fill_below_quote(1_000_000, 1_000_000));
+        assert!(!fill_below_quote(990_000, 1_000_000));
+        assert!(fill_below_quote(989_999, 1_000_000));
+        assert!(!fill_below_quote(0, 0));
+    }
+
+    #[test]
+    fn test_exponential_average() {
+        let mut vs = score();
+        vs.update_stats(1_000, -50, &clock_at(1_000));
+        assert_eq!(vs.avg_price_improvement_bps, -50);
+
+        // 20% de poids pour le nouvel échantillon
+        vs.update_stats(1_000, 50, &clock_at(1_010));
+        assert_eq!(vs.avg_price_improvement_bps, -30);
+    }
+
+    #[test]
+    fn test_score_differentiates_venues() {
+        let mut good = score();
+        let mut bad = score();
+        for i in 0..10 {
+            good.update_stats(1_000, 20, &clock_at(1_000 + i));
+            bad.update_stats(1_000, -40, &clock_at(1_000 + i));
+        }
+        bad.record_failure(&clock_at(1_100));
+        bad.record_failure(&clock_at(1_101));
+
+        assert_eq!(good.quality_score, 3_000 + 3_000 + 2_000);
+        assert_eq!(bad.fill_ratio_bps(), 8_333);
+        assert!(bad.quality_score < good.quality_score);
+    }
+
+    #[test]
+    fn test_window_rollover_decays_counters() {
+        let mut vs = score();
+        vs.update_stats(8_000, 0, &clock_at(1_000));
+        vs.update_stats(8_000, 0, &clock_at(1_100));
+        vs.record_failure(&clock_at(1_200));
+        assert_eq!(vs.total_swaps, 2);
+
+        // Deux fenêtres plus tard: compteurs / 4 avant d'ajouter le nouvel échantillon
+        vs.update_stats(1_000, 10, &clock_at(1_000 + 2 * SCORE_WINDOW_SECS + 5));
+        assert_eq!(vs.total_swaps, 1);
+        assert_eq!(vs.total_failures, 0);
+        assert_eq!(vs.total_volume, 4_000 + 1_000);
+        assert_eq!(vs.avg_price_improvement_bps, 10);
+        assert_eq!(vs.window_start, 1_000 + 2 * SCORE_WINDOW_SECS);
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(
+            VenueScore::LEN,
+            8 + 32 + 32 + 1 + 8 + 8 + 8 + 4 + 2 + 8 + 8 + 2 + 8 + 8 + 1 + 8 + 8 + 2 + 1
+        );
+    }
+
+    #[test]
+    fn test_breaker_trips_after_failures_in_window() {
+        let mut vs = score();
+        assert!(!vs.record_failure(&clock_at(1_000)));
+        assert!(!vs.record_failure(&clock_at(1_100)));
+        assert!(vs.record_failure(&clock_at(1_200)));
+        assert!(vs.is_quarantined(1_200 + 3_599));
+        assert!(!vs.is_quarantined(1_200 + 3_600));
+
+        vs.reset_breaker();
+        assert!(!vs.is_quarantined(1_300));
+    }
+
+    #[test]
+    fn test_breaker_window_expiry_and_adverse_fills() {
+        let mut vs = score();
+        assert!(!vs.record_failure(&clock_at(1_000)));
+        assert!(!vs.record_failure(&clock_at(1_100)));
+        // Fenêtre expirée: le compteur repart de 1
+        assert!(!vs.record_failure(&clock_at(1_000 + 601 + 100)));
+        assert_eq!(vs.failure_strikes, 1);
+
+        // Exécutions à -250 bps vs oracle: strikes jusqu'à la quarantaine
+        assert!(!vs.update_stats(1_000, -50, &clock_at(1_800)));
+        assert!(!vs.update_stats(1_000, -250, &clock_at(1_810)));
+        assert!(vs.update_stats(1_000, -250, &clock_at(1_820)));
+        assert!(vs.is_quarantined(1_821));
+
+        vs.max_failures = 0;
+        vs.reset_breaker();
+        for i in 0..10 {
+            assert!(!vs.record_failure(&clock_at(2_000 + i)));
+        }
     }
 }
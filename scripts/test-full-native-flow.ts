@@ -169,9 +169,13 @@ async function testFullNativeFlow() {
   );
   console.log(`📍 Rebate Vault: ${rebateVault.toBase58()}`);
   
-  // Oracle Cache PDA (seeds: ["oracle_cache", primary_oracle])
+  // Oracle Cache PDA (seeds: ["oracle_cache", mint_a, mint_b], mints triées)
+  const [pairLo, pairHi] =
+    Buffer.compare(SOL_MINT.toBuffer(), USDC_MINT.toBuffer()) <= 0
+      ? [SOL_MINT, USDC_MINT]
+      : [USDC_MINT, SOL_MINT];
   const [oracleCache] = PublicKey.findProgramAddressSync(
-    [Buffer.from("oracle_cache"), PYTH_SOL_USD.toBuffer()],
+    [Buffer.from("oracle_cache"), pairLo.toBuffer(), pairHi.toBuffer()],
     ROUTER_PROGRAM_ID
   );
   console.log(`📍 Oracle Cache: ${oracleCache.toBase58()}`);